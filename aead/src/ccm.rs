@@ -0,0 +1,214 @@
+//! Generic implementation of the [Counter with CBC-MAC][1] (CCM) mode over
+//! block ciphers with 128-bit block size.
+//!
+//! Tag size `M` can be equal to 4, 6, 8, 10, 12, 14 or 16 bytes. Nonce size
+//! `N` can be in the range from 7 to 13 bytes and defines size of the message
+//! length field `L = 15 - N`, which limits maximum message length to
+//! `2^(8*L) - 1` bytes. E.g. 802.15.4 and BLE use `Ccm<C, U4, U13>` or
+//! `Ccm<C, U8, U13>`, i.e. `L = 2`.
+//!
+//! Since decryption requires computation of CBC-MAC over the decrypted
+//! message, on authentication failure buffer is encrypted back, so it
+//! contains the original ciphertext.
+//!
+//! [1]: https://tools.ietf.org/html/rfc3610
+use block_cipher_trait::BlockEncrypt;
+use generic_array::{GenericArray, ArrayLength};
+use generic_array::typenum::U16;
+use core::marker::PhantomData;
+use utils::{apply_ctr, xor};
+use {Aead, Error, ParamError, verify_tag};
+
+type Block = GenericArray<u8, U16>;
+
+/// CCM instance over block cipher `C` with tag size `M` and nonce size `N`.
+///
+/// Validity of `M` and `N` is checked by constructors, so this type does not
+/// implement `NewAead` trait.
+pub struct Ccm<C, M, N>
+    where C: BlockEncrypt<BlockSize = U16>,
+        M: ArrayLength<u8>, N: ArrayLength<u8>
+{
+    cipher: C,
+    _sizes: PhantomData<(M, N)>,
+}
+
+impl<C, M, N> Ccm<C, M, N>
+    where C: BlockEncrypt<BlockSize = U16>,
+        M: ArrayLength<u8>, N: ArrayLength<u8>
+{
+    /// Create new CCM instance from initialized block cipher.
+    ///
+    /// Returns error if tag size or nonce size is not supported by CCM.
+    pub fn from_cipher(cipher: C) -> Result<Self, ParamError> {
+        let m = M::to_usize();
+        let n = N::to_usize();
+        if !(4..=16).contains(&m) || m % 2 != 0 {
+            Err(ParamError::TagSize)
+        } else if !(7..=13).contains(&n) {
+            Err(ParamError::NonceSize)
+        } else {
+            Ok(Self { cipher, _sizes: PhantomData })
+        }
+    }
+
+    /// Create new CCM instance from key with fixed size.
+    pub fn new(key: &GenericArray<u8, C::KeySize>)
+        -> Result<Self, ParamError>
+    {
+        Self::from_cipher(C::new(key))
+    }
+
+    /// Create new CCM instance from key with variable size.
+    pub fn new_varkey(key: &[u8]) -> Result<Self, ParamError> {
+        let cipher = C::new_varkey(key).map_err(|_| ParamError::KeyLength)?;
+        Self::from_cipher(cipher)
+    }
+
+    /// Size of the message length field in bytes
+    #[inline(always)]
+    fn l() -> usize {
+        15 - N::to_usize()
+    }
+
+    fn check_lengths(buffer: &[u8]) -> Result<(), Error> {
+        let l = Self::l();
+        if l < 8 && (buffer.len() as u64) >> (8 * l) != 0 {
+            Err(Error)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Compute counter block `A_i`.
+    #[inline(always)]
+    fn counter_block(nonce: &GenericArray<u8, N>, i: u64, block: &mut Block) {
+        let l = Self::l();
+        block[0] = (l - 1) as u8;
+        block[1..16 - l].copy_from_slice(nonce);
+        block[16 - l..].copy_from_slice(&i.to_be_bytes()[8 - l..]);
+    }
+
+    fn apply_keystream(&self, nonce: &GenericArray<u8, N>, buffer: &mut [u8]) {
+        let mut i = 1;
+        apply_ctr(&self.cipher, buffer, |block| {
+            Self::counter_block(nonce, i, block);
+            i += 1;
+        });
+    }
+
+    /// Compute encrypted authentication tag over associated data and
+    /// plaintext.
+    fn compute_tag(
+        &self, nonce: &GenericArray<u8, N>, associated_data: &[u8], pt: &[u8],
+    ) -> GenericArray<u8, M> {
+        let l = Self::l();
+        let mut b0 = Block::default();
+        let adata_flag = if associated_data.is_empty() { 0 } else { 0x40 };
+        b0[0] = adata_flag | (((M::to_usize() - 2) / 2) << 3) as u8
+            | (l - 1) as u8;
+        b0[1..16 - l].copy_from_slice(nonce);
+        b0[16 - l..].copy_from_slice(&(pt.len() as u64).to_be_bytes()[8 - l..]);
+
+        let mut mac = CbcMac::new(&self.cipher, b0);
+        if !associated_data.is_empty() {
+            let len = associated_data.len() as u64;
+            if len < (1 << 16) - (1 << 8) {
+                mac.update(&(len as u16).to_be_bytes());
+            } else if len <= u32::MAX as u64 {
+                mac.update(&[0xFF, 0xFE]);
+                mac.update(&(len as u32).to_be_bytes());
+            } else {
+                mac.update(&[0xFF, 0xFF]);
+                mac.update(&len.to_be_bytes());
+            }
+            mac.update(associated_data);
+            mac.pad();
+        }
+        mac.update(pt);
+        let mut tag = mac.finalize();
+
+        let mut s0 = Block::default();
+        Self::counter_block(nonce, 0, &mut s0);
+        self.cipher.encrypt_block(&mut s0);
+        xor(&mut tag, &s0);
+        GenericArray::clone_from_slice(&tag[..M::to_usize()])
+    }
+}
+
+impl<C, M, N> Aead for Ccm<C, M, N>
+    where C: BlockEncrypt<BlockSize = U16>,
+        M: ArrayLength<u8>, N: ArrayLength<u8>
+{
+    type NonceSize = N;
+    type TagSize = M;
+
+    fn encrypt_in_place_detached(
+        &self,
+        nonce: &GenericArray<u8, N>,
+        associated_data: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<GenericArray<u8, M>, Error> {
+        Self::check_lengths(buffer)?;
+        let tag = self.compute_tag(nonce, associated_data, buffer);
+        self.apply_keystream(nonce, buffer);
+        Ok(tag)
+    }
+
+    fn decrypt_in_place_detached(
+        &self,
+        nonce: &GenericArray<u8, N>,
+        associated_data: &[u8],
+        buffer: &mut [u8],
+        tag: &GenericArray<u8, M>,
+    ) -> Result<(), Error> {
+        Self::check_lengths(buffer)?;
+        self.apply_keystream(nonce, buffer);
+        let expected = self.compute_tag(nonce, associated_data, buffer);
+        let res = verify_tag(expected, tag);
+        if res.is_err() {
+            self.apply_keystream(nonce, buffer);
+        }
+        res
+    }
+}
+
+/// CBC-MAC state which processes data with zero padding between fields.
+struct CbcMac<'a, C: BlockEncrypt<BlockSize = U16> + 'a> {
+    cipher: &'a C,
+    state: Block,
+    pos: usize,
+}
+
+impl<'a, C: BlockEncrypt<BlockSize = U16>> CbcMac<'a, C> {
+    fn new(cipher: &'a C, mut b0: Block) -> Self {
+        cipher.encrypt_block(&mut b0);
+        CbcMac { cipher, state: b0, pos: 0 }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            let n = core::cmp::min(16 - self.pos, data.len());
+            xor(&mut self.state[self.pos..self.pos + n], &data[..n]);
+            self.pos += n;
+            data = &data[n..];
+            if self.pos == 16 {
+                self.cipher.encrypt_block(&mut self.state);
+                self.pos = 0;
+            }
+        }
+    }
+
+    /// Pad processed data with zeros to a multiple of block size.
+    fn pad(&mut self) {
+        if self.pos != 0 {
+            self.cipher.encrypt_block(&mut self.state);
+            self.pos = 0;
+        }
+    }
+
+    fn finalize(mut self) -> Block {
+        self.pad();
+        self.state
+    }
+}
@@ -0,0 +1,687 @@
+//! Test vectors and helpers for testing AEAD implementations.
+//!
+//! Usage example:
+//!
+//! ```rust,ignore
+//! #[test]
+//! fn ccm_rfc3610() {
+//!     aead::dev::run_aead_tests(aead::dev::CCM_AES128_M8_N13, |key| {
+//!         Ccm::<Aes128, U8, U13>::new_varkey(key).unwrap()
+//!     });
+//! }
+//! ```
+use super::Aead;
+use siv::Siv;
+use block_cipher_trait::BlockEncrypt;
+use block_cipher_trait::dev::decode_hex as decode;
+use crypto_mac::Mac;
+use generic_array::GenericArray;
+use generic_array::typenum::{Unsigned, U16};
+
+/// AEAD test vector. All fields are hex encoded, `ct` contains ciphertext
+/// with appended authentication tag.
+pub struct Test {
+    pub key: &'static str,
+    pub nonce: &'static str,
+    pub aad: &'static str,
+    pub pt: &'static str,
+    pub ct: &'static str,
+}
+
+/// Run AEAD tests using `init` to create algorithm instance from a key.
+///
+/// Besides encryption and decryption it checks that modified ciphertext and
+/// tag are rejected and that in both cases buffer is left with the
+/// ciphertext, i.e. that unauthenticated plaintext is not released.
+pub fn run_aead_tests<A, F>(tests: &[Test], init: F)
+    where A: Aead, F: Fn(&[u8]) -> A
+{
+    let tag_len = A::TagSize::to_usize();
+    for (i, t) in tests.iter().enumerate() {
+        let (mut kb, mut nb, mut ab, mut pb, mut cb) =
+            ([0u8; 64], [0u8; 64], [0u8; 64], [0u8; 256], [0u8; 256]);
+        let key = decode(t.key, &mut kb);
+        let nonce = decode(t.nonce, &mut nb);
+        let aad = decode(t.aad, &mut ab);
+        let pt = decode(t.pt, &mut pb);
+        let ct = decode(t.ct, &mut cb);
+        let (ct, tag) = ct.split_at(ct.len() - tag_len);
+        let nonce = GenericArray::from_slice(nonce);
+        let tag = GenericArray::from_slice(tag);
+
+        let state = init(key);
+        let mut buf = [0u8; 256];
+        let buf = &mut buf[..pt.len()];
+        buf.copy_from_slice(pt);
+        let res = state.encrypt_in_place_detached(nonce, aad, buf);
+        if res.ok().as_ref() != Some(tag) || buf != ct {
+            panic!("\n\
+                Failed encryption test №{}\n\
+                key:\t{}\nnonce:\t{}\naad:\t{}\nplaintext:\t{}\n\
+                expected ciphertext:\t{}\n",
+                i, t.key, t.nonce, t.aad, t.pt, t.ct,
+            );
+        }
+
+        let res = state.decrypt_in_place_detached(nonce, aad, buf, tag);
+        if res.is_err() || buf != pt {
+            panic!("\nFailed decryption test №{}\n", i);
+        }
+
+        let mut bad_tag = tag.clone();
+        bad_tag[0] ^= 1;
+        buf.copy_from_slice(ct);
+        let res = state.decrypt_in_place_detached(nonce, aad, buf, &bad_tag);
+        if res.is_ok() || buf != ct {
+            panic!("\nModified tag was not rejected in test №{}\n", i);
+        }
+
+        if !ct.is_empty() {
+            buf[0] ^= 1;
+            let res = state.decrypt_in_place_detached(nonce, aad, buf, tag);
+            buf[0] ^= 1;
+            if res.is_ok() || buf != ct {
+                panic!("\nModified ciphertext was not rejected in test №{}\n", i);
+            }
+        }
+    }
+}
+
+/// SIV test vector. All fields are hex encoded, `ct` contains synthetic IV
+/// followed by ciphertext.
+pub struct SivTest {
+    pub key: &'static str,
+    pub headers: &'static [&'static str],
+    pub pt: &'static str,
+    pub ct: &'static str,
+}
+
+/// Run SIV tests for block cipher `C` and CMAC implementation `M`.
+pub fn run_siv_tests<C, M>(tests: &[SivTest])
+    where C: BlockEncrypt<BlockSize = U16>, M: Mac<OutputSize = U16>
+{
+    for (i, t) in tests.iter().enumerate() {
+        let (mut kb, mut pb, mut cb) = ([0u8; 64], [0u8; 256], [0u8; 256]);
+        let key = decode(t.key, &mut kb);
+        let pt = decode(t.pt, &mut pb);
+        let ct = decode(t.ct, &mut cb);
+        let mut hbufs = [[0u8; 64]; 8];
+        let mut headers = [&[][..]; 8];
+        assert!(t.headers.len() <= 8, "too many headers");
+        for ((h, buf), hex) in headers.iter_mut()
+            .zip(hbufs.iter_mut()).zip(t.headers)
+        {
+            *h = decode(hex, buf);
+        }
+        let headers = &headers[..t.headers.len()];
+        let (siv, ct) = ct.split_at(16);
+        let siv = GenericArray::from_slice(siv);
+
+        let mut state = Siv::<C, M>::new_varkey(key).unwrap();
+        let mut buf = [0u8; 256];
+        let buf = &mut buf[..pt.len()];
+        buf.copy_from_slice(pt);
+        let res = state.encrypt_in_place_detached(headers, buf);
+        if res.ok().as_ref() != Some(siv) || buf != ct {
+            panic!("\n\
+                Failed encryption test №{}\n\
+                key:\t{}\nheaders:\t{:?}\nplaintext:\t{}\n\
+                expected ciphertext:\t{}\n",
+                i, t.key, t.headers, t.pt, t.ct,
+            );
+        }
+
+        let res = state.decrypt_in_place_detached(headers, buf, siv);
+        if res.is_err() || buf != pt {
+            panic!("\nFailed decryption test №{}\n", i);
+        }
+
+        // drop the first header or add an empty one if there are no headers
+        let modified: &[&[u8]] = if headers.is_empty() {
+            &[&[]]
+        } else {
+            &headers[1..]
+        };
+        buf.copy_from_slice(ct);
+        let res = state.decrypt_in_place_detached(modified, buf, siv);
+        if res.is_ok() || buf != ct {
+            panic!("\nModified headers were not rejected in test №{}\n", i);
+        }
+    }
+}
+
+/// Test cases 1-4 from the McGrew and Viega GCM specification for AES-128
+/// GCM with 12 byte nonces.
+pub const GCM_AES128_N12: &[Test] = &[
+    Test {
+        key: "00000000000000000000000000000000",
+        nonce: "000000000000000000000000",
+        aad: "",
+        pt: "",
+        ct: "58e2fccefa7e3061367f1d57a4e7455a",
+    },
+    Test {
+        key: "00000000000000000000000000000000",
+        nonce: "000000000000000000000000",
+        aad: "",
+        pt: "00000000000000000000000000000000",
+        ct: "0388dace60b6a392f328c2b971b2fe78ab6e47d42cec13bdf53a67b21257bddf",
+    },
+    Test {
+        key: "feffe9928665731c6d6a8f9467308308",
+        nonce: "cafebabefacedbaddecaf888",
+        aad: "",
+        pt: "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+            1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b391aafd255",
+        ct: "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e\
+            21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091473f5985\
+            4d5c2af327cd64a62cf35abd2ba6fab4",
+    },
+    Test {
+        key: "feffe9928665731c6d6a8f9467308308",
+        nonce: "cafebabefacedbaddecaf888",
+        aad: "feedfacedeadbeeffeedfacedeadbeefabaddad2",
+        pt: "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+            1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39",
+        ct: "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e\
+            21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e0915bc94fbc\
+            3221a5db94fae95ae7121a47",
+    },
+];
+
+/// Test case 5 from the McGrew and Viega GCM specification for AES-128 GCM
+/// with 8 byte nonces.
+pub const GCM_AES128_N8: &[Test] = &[
+    Test {
+        key: "feffe9928665731c6d6a8f9467308308",
+        nonce: "cafebabefacedbad",
+        aad: "feedfacedeadbeeffeedfacedeadbeefabaddad2",
+        pt: "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+            1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39",
+        ct: "61353b4c2806934a777ff51fa22a4755699b2a714fcdc6f83766e5f97b6c7423\
+            73806900e49f24b22b097544d4896b424989b5e1ebac0f07c23f45983612d2e7\
+            9e3b0785561be14aaca2fccb",
+    },
+];
+
+/// Test case 6 from the McGrew and Viega GCM specification for AES-128 GCM
+/// with 60 byte nonces.
+pub const GCM_AES128_N60: &[Test] = &[
+    Test {
+        key: "feffe9928665731c6d6a8f9467308308",
+        nonce: "9313225df88406e555909c5aff5269aa6a7a9538534f7da1e4c303d2a318a728\
+            c3c0c95156809539fcf0e2429a6b525416aedbf5a0de6a57a637b39b",
+        aad: "feedfacedeadbeeffeedfacedeadbeefabaddad2",
+        pt: "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+            1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39",
+        ct: "8ce24998625615b603a033aca13fb894be9112a5c3a211a8ba262a3cca7e2ca7\
+            01e4a9a4fba43c90ccdcb281d48c7c6fd62875d2aca417034c34aee5619cc5ae\
+            fffe0bfa462af43c1699d050",
+    },
+];
+
+/// Test cases 13-16 from the McGrew and Viega GCM specification for AES-256
+/// GCM with 12 byte nonces.
+pub const GCM_AES256_N12: &[Test] = &[
+    Test {
+        key: "0000000000000000000000000000000000000000000000000000000000000000",
+        nonce: "000000000000000000000000",
+        aad: "",
+        pt: "",
+        ct: "530f8afbc74536b9a963b4f1c4cb738b",
+    },
+    Test {
+        key: "0000000000000000000000000000000000000000000000000000000000000000",
+        nonce: "000000000000000000000000",
+        aad: "",
+        pt: "00000000000000000000000000000000",
+        ct: "cea7403d4d606b6e074ec5d3baf39d18d0d1c8a799996bf0265b98b5d48ab919",
+    },
+    Test {
+        key: "feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308",
+        nonce: "cafebabefacedbaddecaf888",
+        aad: "",
+        pt: "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+            1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b391aafd255",
+        ct: "522dc1f099567d07f47f37a32a84427d643a8cdcbfe5c0c97598a2bd2555d1aa\
+            8cb08e48590dbb3da7b08b1056828838c5f61e6393ba7a0abcc9f662898015ad\
+            b094dac5d93471bdec1a502270e3cc6c",
+    },
+    Test {
+        key: "feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308",
+        nonce: "cafebabefacedbaddecaf888",
+        aad: "feedfacedeadbeeffeedfacedeadbeefabaddad2",
+        pt: "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+            1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39",
+        ct: "522dc1f099567d07f47f37a32a84427d643a8cdcbfe5c0c97598a2bd2555d1aa\
+            8cb08e48590dbb3da7b08b1056828838c5f61e6393ba7a0abcc9f66276fc6ece\
+            0f4e1768cddf8853bb2d551b",
+    },
+];
+
+/// Test case 17 from the McGrew and Viega GCM specification for AES-256 GCM
+/// with 8 byte nonces.
+pub const GCM_AES256_N8: &[Test] = &[
+    Test {
+        key: "feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308",
+        nonce: "cafebabefacedbad",
+        aad: "feedfacedeadbeeffeedfacedeadbeefabaddad2",
+        pt: "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+            1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39",
+        ct: "c3762df1ca787d32ae47c13bf19844cbaf1ae14d0b976afac52ff7d79bba9de0\
+            feb582d33934a4f0954cc2363bc73f7862ac430e64abe499f47c9b1f3a337dbf\
+            46a792c45e454913fe2ea8f2",
+    },
+];
+
+/// Test case 18 from the McGrew and Viega GCM specification for AES-256 GCM
+/// with 60 byte nonces.
+pub const GCM_AES256_N60: &[Test] = &[
+    Test {
+        key: "feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308",
+        nonce: "9313225df88406e555909c5aff5269aa6a7a9538534f7da1e4c303d2a318a728\
+            c3c0c95156809539fcf0e2429a6b525416aedbf5a0de6a57a637b39b",
+        aad: "feedfacedeadbeeffeedfacedeadbeefabaddad2",
+        pt: "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+            1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39",
+        ct: "5a8def2f0c9e53f1f75d7853659e2a20eeb2b22aafde6419a058ab4f6f746bf4\
+            0fc0c3b780f244452da3ebf1c5d82cdea2418997200ef82e44ae7e3fa44a8266\
+            ee1c8eb0c8b5d4cf5ae9f19a",
+    },
+];
+
+/// RFC 3610 packet vectors #1-#6 and #13-#18 for AES-128 CCM with
+/// `M = 8` and `N = 13`.
+pub const CCM_AES128_M8_N13: &[Test] = &[
+    Test {
+        key: "c0c1c2c3c4c5c6c7c8c9cacbcccdcecf",
+        nonce: "00000003020100a0a1a2a3a4a5",
+        aad: "0001020304050607",
+        pt: "08090a0b0c0d0e0f101112131415161718191a1b1c1d1e",
+        ct: "588c979a61c663d2f066d0c2c0f989806d5f6b61dac38417e8d12cfdf926e0",
+    },
+    Test {
+        key: "c0c1c2c3c4c5c6c7c8c9cacbcccdcecf",
+        nonce: "00000004030201a0a1a2a3a4a5",
+        aad: "0001020304050607",
+        pt: "08090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        ct: "72c91a36e135f8cf291ca894085c87e3cc15c439c9e43a3ba091d56e10400916",
+    },
+    Test {
+        key: "c0c1c2c3c4c5c6c7c8c9cacbcccdcecf",
+        nonce: "00000005040302a0a1a2a3a4a5",
+        aad: "0001020304050607",
+        pt: "08090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20",
+        ct: "51b1e5f44a197d1da46b0f8e2d282ae871e838bb64da8596574adaa76fbd9fb0c5",
+    },
+    Test {
+        key: "c0c1c2c3c4c5c6c7c8c9cacbcccdcecf",
+        nonce: "00000006050403a0a1a2a3a4a5",
+        aad: "000102030405060708090a0b",
+        pt: "0c0d0e0f101112131415161718191a1b1c1d1e",
+        ct: "a28c6865939a9a79faaa5c4c2a9d4a91cdac8c96c861b9c9e61ef1",
+    },
+    Test {
+        key: "c0c1c2c3c4c5c6c7c8c9cacbcccdcecf",
+        nonce: "00000007060504a0a1a2a3a4a5",
+        aad: "000102030405060708090a0b",
+        pt: "0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        ct: "dcf1fb7b5d9e23fb9d4e131253658ad86ebdca3e51e83f077d9c2d93",
+    },
+    Test {
+        key: "c0c1c2c3c4c5c6c7c8c9cacbcccdcecf",
+        nonce: "00000008070605a0a1a2a3a4a5",
+        aad: "000102030405060708090a0b",
+        pt: "0c0d0e0f101112131415161718191a1b1c1d1e1f20",
+        ct: "6fc1b011f006568b5171a42d953d469b2570a4bd87405a0443ac91cb94",
+    },
+    Test {
+        key: "d7828d13b2b0bdc325a76236df93cc6b",
+        nonce: "00412b4ea9cdbe3c9696766cfa",
+        aad: "0be1a88bace018b1",
+        pt: "08e8cf97d820ea258460e96ad9cf5289054d895ceac47c",
+        ct: "4cb97f86a2a4689a877947ab8091ef5386a6ffbdd080f8e78cf7cb0cddd7b3",
+    },
+    Test {
+        key: "d7828d13b2b0bdc325a76236df93cc6b",
+        nonce: "0033568ef7b2633c9696766cfa",
+        aad: "63018f76dc8a1bcb",
+        pt: "9020ea6f91bdd85afa0039ba4baff9bfb79c7028949cd0ec",
+        ct: "4ccb1e7ca981befaa0726c55d378061298c85c92814abc33c52ee81d7d77c08a",
+    },
+    Test {
+        key: "d7828d13b2b0bdc325a76236df93cc6b",
+        nonce: "00103fe41336713c9696766cfa",
+        aad: "aa6cfa36cae86b40",
+        pt: "b916e0eacc1c00d7dcec68ec0b3bbb1a02de8a2d1aa346132e",
+        ct: "b1d23a2220ddc0ac900d9aa03c61fcf4a559a4417767089708a776796edb723506",
+    },
+    Test {
+        key: "d7828d13b2b0bdc325a76236df93cc6b",
+        nonce: "00764c63b8058e3c9696766cfa",
+        aad: "d0d0735c531e1becf049c244",
+        pt: "12daac5630efa5396f770ce1a66b21f7b2101c",
+        ct: "14d253c3967b70609b7cbb7c499160283245269a6f49975bcadeaf",
+    },
+    Test {
+        key: "d7828d13b2b0bdc325a76236df93cc6b",
+        nonce: "00f8b678094e3b3c9696766cfa",
+        aad: "77b60f011c03e1525899bcae",
+        pt: "e88b6a46c78d63e52eb8c546efb5de6f75e9cc0d",
+        ct: "5545ff1a085ee2efbf52b2e04bee1e2336c73e3f762c0c7744fe7e3c",
+    },
+    Test {
+        key: "d7828d13b2b0bdc325a76236df93cc6b",
+        nonce: "00d560912d3f703c9696766cfa",
+        aad: "cd9044d2b71fdb8120ea60c0",
+        pt: "6435acbafb11a82e2f071d7ca4a5ebd93a803ba87f",
+        ct: "009769ecabdf48625594c59251e6035722675e04c847099e5ae0704551",
+    },
+];
+
+/// RFC 3610 packet vectors #7-#12 and #19-#24 for AES-128 CCM with
+/// `M = 10` and `N = 13`.
+pub const CCM_AES128_M10_N13: &[Test] = &[
+    Test {
+        key: "c0c1c2c3c4c5c6c7c8c9cacbcccdcecf",
+        nonce: "00000009080706a0a1a2a3a4a5",
+        aad: "0001020304050607",
+        pt: "08090a0b0c0d0e0f101112131415161718191a1b1c1d1e",
+        ct: "0135d1b2c95f41d5d1d4fec185d166b8094e999dfed96c048c56602c97acbb7490",
+    },
+    Test {
+        key: "c0c1c2c3c4c5c6c7c8c9cacbcccdcecf",
+        nonce: "0000000a090807a0a1a2a3a4a5",
+        aad: "0001020304050607",
+        pt: "08090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        ct: "7b75399ac0831dd2f0bbd75879a2fd8f6cae6b6cd9b7db24c17b4433f434963f34b4",
+    },
+    Test {
+        key: "c0c1c2c3c4c5c6c7c8c9cacbcccdcecf",
+        nonce: "0000000b0a0908a0a1a2a3a4a5",
+        aad: "0001020304050607",
+        pt: "08090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20",
+        ct: "82531a60cc24945a4b8279181ab5c84df21ce7f9b73f42e197ea9c07e56b5eb17e5f4e",
+    },
+    Test {
+        key: "c0c1c2c3c4c5c6c7c8c9cacbcccdcecf",
+        nonce: "0000000c0b0a09a0a1a2a3a4a5",
+        aad: "000102030405060708090a0b",
+        pt: "0c0d0e0f101112131415161718191a1b1c1d1e",
+        ct: "07342594157785152b074098330abb141b947b566aa9406b4d999988dd",
+    },
+    Test {
+        key: "c0c1c2c3c4c5c6c7c8c9cacbcccdcecf",
+        nonce: "0000000d0c0b0aa0a1a2a3a4a5",
+        aad: "000102030405060708090a0b",
+        pt: "0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        ct: "676bb20380b0e301e8ab79590a396da78b834934f53aa2e9107a8b6c022c",
+    },
+    Test {
+        key: "c0c1c2c3c4c5c6c7c8c9cacbcccdcecf",
+        nonce: "0000000e0d0c0ba0a1a2a3a4a5",
+        aad: "000102030405060708090a0b",
+        pt: "0c0d0e0f101112131415161718191a1b1c1d1e1f20",
+        ct: "c0ffa0d6f05bdb67f24d43a4338d2aa4bed7b20e43cd1aa31662e7ad65d6db",
+    },
+    Test {
+        key: "d7828d13b2b0bdc325a76236df93cc6b",
+        nonce: "0042fff8f1951c3c9696766cfa",
+        aad: "d85bc7e69f944fb8",
+        pt: "8a19b950bcf71a018e5e6701c91787659809d67dbedd18",
+        ct: "bc218daa947427b6db386a99ac1aef23ade0b52939cb6a637cf9bec2408897c6ba",
+    },
+    Test {
+        key: "d7828d13b2b0bdc325a76236df93cc6b",
+        nonce: "00920f40e56cdc3c9696766cfa",
+        aad: "74a0ebc9069f5b37",
+        pt: "1761433c37c5a35fc1f39f406302eb907c6163be38c98437",
+        ct: "5810e6fd25874022e80361a478e3e9cf484ab04f447efff6f0a477cc2fc9bf548944",
+    },
+    Test {
+        key: "d7828d13b2b0bdc325a76236df93cc6b",
+        nonce: "0027ca0c7120bc3c9696766cfa",
+        aad: "44a3aa3aae6475ca",
+        pt: "a434a8e58500c6e41530538862d686ea9e81301b5ae4226bfa",
+        ct: "f2beed7bc5098e83feb5b31608f8e29c38819a89c8e776f1544d4151a4ed3a8b87b9ce",
+    },
+    Test {
+        key: "d7828d13b2b0bdc325a76236df93cc6b",
+        nonce: "005b8ccbcd9af83c9696766cfa",
+        aad: "ec46bb63b02520c33c49fd70",
+        pt: "b96b49e21d621741632875db7f6c9243d2d7c2",
+        ct: "31d750a09da3ed7fddd49a2032aabf17ec8ebf7d22c8088c666be5c197",
+    },
+    Test {
+        key: "d7828d13b2b0bdc325a76236df93cc6b",
+        nonce: "003ebe94044b9a3c9696766cfa",
+        aad: "47a65ac78b3d594227e85e71",
+        pt: "e2fcfbb880442c731bf95167c8ffd7895e337076",
+        ct: "e882f1dbd38ce3eda7c23f04dd65071eb41342acdf7e00dccec7ae52987d",
+    },
+    Test {
+        key: "d7828d13b2b0bdc325a76236df93cc6b",
+        nonce: "008d493b30ae8b3c9696766cfa",
+        aad: "6e37a6ef546d955d34ab6059",
+        pt: "abf21c0b02feb88f856df4a37381bce3cc128517d4",
+        ct: "f32905b88a641b04b9c9ffb58cc390900f3da12ab16dce9e82efa16da62059",
+    },
+];
+
+/// Test vectors from the appendix G of the EAX paper for AES-128 EAX with
+/// `M = 16` and `N = 16`.
+pub const EAX_AES128: &[Test] = &[
+    Test {
+        key: "233952dee4d5ed5f9b9c6d6ff80ff478",
+        nonce: "62ec67f9c3a4a407fcb2a8c49031a8b3",
+        aad: "6bfb914fd07eae6b",
+        pt: "",
+        ct: "e037830e8389f27b025a2d6527e79d01",
+    },
+    Test {
+        key: "91945d3f4dcbee0bf45ef52255f095a4",
+        nonce: "becaf043b0a23d843194ba972c66debd",
+        aad: "fa3bfd4806eb53fa",
+        pt: "f7fb",
+        ct: "19dd5c4c9331049d0bdab0277408f67967e5",
+    },
+    Test {
+        key: "01f74ad64077f2e704c0f60ada3dd523",
+        nonce: "70c3db4f0d26368400a10ed05d2bff5e",
+        aad: "234a3463c1264ac6",
+        pt: "1a47cb4933",
+        ct: "d851d5bae03a59f238a23e39199dc9266626c40f80",
+    },
+    Test {
+        key: "d07cf6cbb7f313bdde66b727afd3c5e8",
+        nonce: "8408dfff3c1a2b1292dc199e46b7d617",
+        aad: "33cce2eabff5a79d",
+        pt: "481c9e39b1",
+        ct: "632a9d131ad4c168a4225d8e1ff755939974a7bede",
+    },
+    Test {
+        key: "35b6d0580005bbc12b0587124557d2c2",
+        nonce: "fdb6b06676eedc5c61d74276e1f8e816",
+        aad: "aeb96eaebe2970e9",
+        pt: "40d0c07da5e4",
+        ct: "071dfe16c675cb0677e536f73afe6a14b74ee49844dd",
+    },
+    Test {
+        key: "bd8e6e11475e60b268784c38c62feb22",
+        nonce: "6eac5c93072d8e8513f750935e46da1b",
+        aad: "d4482d1ca78dce0f",
+        pt: "4de3b35c3fc039245bd1fb7d",
+        ct: "835bb4f15d743e350e728414abb8644fd6ccb86947c5e10590210a4f",
+    },
+    Test {
+        key: "7c77d6e813bed5ac98baa417477a2e7d",
+        nonce: "1a8c98dcd73d38393b2bf1569deefc19",
+        aad: "65d2017990d62528",
+        pt: "8b0a79306c9ce7ed99dae4f87f8dd61636",
+        ct: "02083e3979da014812f59f11d52630da30137327d10649b0aa6e1c181db617d7f2",
+    },
+    Test {
+        key: "5fff20cafab119ca2fc73549e20f5b0d",
+        nonce: "dde59b97d722156d4d9aff2bc7559826",
+        aad: "54b9f04e6a09189a",
+        pt: "1bda122bce8a8dbaf1877d962b8592dd2d56",
+        ct: "2ec47b2c4954a489afc7ba4897edcdae8cc33b60450599bd02c96382902aef7f832a",
+    },
+    Test {
+        key: "a4a4782bcffd3ec5e7ef6d8c34a56123",
+        nonce: "b781fcf2f75fa5a8de97a9ca48e522ec",
+        aad: "899a175897561d7e",
+        pt: "6cf36720872b8513f6eab1a8a44438d5ef11",
+        ct: "0de18fd0fdd91e7af19f1d8ee8733938b1e8e7f6d2231618102fdb7fe55ff1991700",
+    },
+    Test {
+        key: "8395fcf1e95bebd697bd010bc766aac3",
+        nonce: "22e7add93cfc6393c57ec0b3c17d6b44",
+        aad: "126735fcc320d25a",
+        pt: "ca40d7446e545ffaed3bd12a740a659ffbbb3ceab7",
+        ct: "cb8920f87a6c75cff39627b56e3ed197c552d295a7cfc46afc253b4652b1af3795b124ab6e",
+    },
+];
+
+/// Test vectors from the appendix A of the RFC 5297 for AES-SIV-256.
+///
+/// The last two vectors use the key from the A.1 and cover messages without
+/// associated data and empty messages, they were generated using the AES-SIV
+/// implementation of OpenSSL.
+pub const SIV_AES128: &[SivTest] = &[
+    SivTest {
+        key: "fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0\
+            f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff",
+        headers: &["101112131415161718191a1b1c1d1e1f2021222324252627"],
+        pt: "112233445566778899aabbccddee",
+        ct: "85632d07c6e8f37f950acd320a2ecc9340c02b9690c4dc04daef7f6afe5c",
+    },
+    SivTest {
+        key: "7f7e7d7c7b7a79787776757473727170\
+            404142434445464748494a4b4c4d4e4f",
+        headers: &[
+            "00112233445566778899aabbccddeeffdeaddadadeaddadaffeeddccbbaa9988\
+                7766554433221100",
+            "102030405060708090a0",
+            "09f911029d74e35bd84156c5635688c0",
+        ],
+        pt: "7468697320697320736f6d6520706c61696e7465787420746f20656e6372797074\
+            207573696e67205349562d414553",
+        ct: "7bdb6e3b432667eb06f4d14bff2fbd0fcb900f2fddbe404326601965c889bf17\
+            dba77ceb094fa663b7a3f748ba8af829ea64ad544a272e9c485b62a3fd5c0d",
+    },
+    SivTest {
+        key: "fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0\
+            f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff",
+        headers: &[],
+        pt: "112233445566778899aabbccddee",
+        ct: "f1c5fdeac1f15a26779c1501f9fb758827e946c669088ab06da58c5c831c",
+    },
+    SivTest {
+        key: "fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0\
+            f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff",
+        headers: &["101112131415161718191a1b1c1d1e1f2021222324252627"],
+        pt: "",
+        ct: "b9d5cc97054dcd3f6dfda629d4f4d313",
+    },
+];
+
+/// Sample results from the appendix A of the RFC 7253 for AES-128 OCB3 with
+/// `M = 16` and `N = 12`.
+pub const OCB3_AES128: &[Test] = &[
+    Test {
+        key: "000102030405060708090a0b0c0d0e0f",
+        nonce: "bbaa99887766554433221100",
+        aad: "",
+        pt: "",
+        ct: "785407bfffc8ad9edcc5520ac9111ee6",
+    },
+    Test {
+        key: "000102030405060708090a0b0c0d0e0f",
+        nonce: "bbaa99887766554433221101",
+        aad: "0001020304050607",
+        pt: "0001020304050607",
+        ct: "6820b3657b6f615a5725bda0d3b4eb3a257c9af1f8f03009",
+    },
+    Test {
+        key: "000102030405060708090a0b0c0d0e0f",
+        nonce: "bbaa99887766554433221102",
+        aad: "0001020304050607",
+        pt: "",
+        ct: "81017f8203f081277152fade694a0a00",
+    },
+    Test {
+        key: "000102030405060708090a0b0c0d0e0f",
+        nonce: "bbaa99887766554433221103",
+        aad: "",
+        pt: "0001020304050607",
+        ct: "45dd69f8f5aae72414054cd1f35d82760b2cd00d2f99bfa9",
+    },
+    Test {
+        key: "000102030405060708090a0b0c0d0e0f",
+        nonce: "bbaa99887766554433221104",
+        aad: "000102030405060708090a0b0c0d0e0f",
+        pt: "000102030405060708090a0b0c0d0e0f",
+        ct: "571d535b60b277188be5147170a9a22c3ad7a4ff3835b8c5701c1ccec8fc3358",
+    },
+    Test {
+        key: "000102030405060708090a0b0c0d0e0f",
+        nonce: "bbaa99887766554433221105",
+        aad: "000102030405060708090a0b0c0d0e0f",
+        pt: "",
+        ct: "8cf761b6902ef764462ad86498ca6b97",
+    },
+    Test {
+        key: "000102030405060708090a0b0c0d0e0f",
+        nonce: "bbaa99887766554433221106",
+        aad: "",
+        pt: "000102030405060708090a0b0c0d0e0f",
+        ct: "5ce88ec2e0692706a915c00aeb8b2396f40e1c743f52436bdf06d8fa1eca343d",
+    },
+    Test {
+        key: "000102030405060708090a0b0c0d0e0f",
+        nonce: "bbaa99887766554433221107",
+        aad: "000102030405060708090a0b0c0d0e0f1011121314151617",
+        pt: "000102030405060708090a0b0c0d0e0f1011121314151617",
+        ct: "1ca2207308c87c010756104d8840ce1952f09673a448a122c92c62241051f573\
+            56d7f3c90bb0e07f",
+    },
+    Test {
+        key: "000102030405060708090a0b0c0d0e0f",
+        nonce: "bbaa99887766554433221108",
+        aad: "000102030405060708090a0b0c0d0e0f1011121314151617",
+        pt: "",
+        ct: "6dc225a071fc1b9f7c69f93b0f1e10de",
+    },
+    Test {
+        key: "000102030405060708090a0b0c0d0e0f",
+        nonce: "bbaa99887766554433221109",
+        aad: "",
+        pt: "000102030405060708090a0b0c0d0e0f1011121314151617",
+        ct: "221bd0de7fa6fe993eccd769460a0af2d6cded0c395b1c3ce725f32494b9f914\
+            d85c0b1eb38357ff",
+    },
+    Test {
+        key: "000102030405060708090a0b0c0d0e0f",
+        nonce: "bbaa9988776655443322110a",
+        aad: "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        pt: "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        ct: "bd6f6c496201c69296c11efd138a467abd3c707924b964deaffc40319af5a485\
+            40fbba186c5553c68ad9f592a79a4240",
+    },
+    Test {
+        key: "000102030405060708090a0b0c0d0e0f",
+        nonce: "bbaa9988776655443322110b",
+        aad: "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        pt: "",
+        ct: "fe80690bee8a485d11f32965bc9d2a32",
+    },
+    Test {
+        key: "000102030405060708090a0b0c0d0e0f",
+        nonce: "bbaa9988776655443322110c",
+        aad: "",
+        pt: "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        ct: "2942bfc773bda23cabc6acfd9bfd5835bd300f0973792ef46040c53f1432bcdf\
+            b5e1dde3bc18a5f840b52e653444d5df",
+    },
+];
@@ -0,0 +1,149 @@
+//! Generic implementation of the [EAX][1] mode over block ciphers with
+//! 128-bit block size.
+//!
+//! Tag size `M` can be in the range from 1 to 16 bytes, nonce can have any
+//! size `N` (16 bytes by default).
+//!
+//! [1]: https://web.cs.ucdavis.edu/~rogaway/papers/eax.pdf
+use block_cipher_trait::BlockEncrypt;
+use dbl::Dbl;
+use generic_array::{GenericArray, ArrayLength};
+use generic_array::typenum::U16;
+use core::marker::PhantomData;
+use utils::{apply_ctr, xor};
+use {Aead, Error, ParamError, verify_tag};
+
+type Block = GenericArray<u8, U16>;
+
+/// EAX instance over block cipher `C` with tag size `M` and nonce size `N`.
+///
+/// Validity of `M` is checked by constructors, so this type does not
+/// implement `NewAead` trait.
+pub struct Eax<C, M = U16, N = U16>
+    where C: BlockEncrypt<BlockSize = U16>,
+        M: ArrayLength<u8>, N: ArrayLength<u8>
+{
+    cipher: C,
+    k1: Block,
+    k2: Block,
+    _sizes: PhantomData<(M, N)>,
+}
+
+impl<C, M, N> Eax<C, M, N>
+    where C: BlockEncrypt<BlockSize = U16>,
+        M: ArrayLength<u8>, N: ArrayLength<u8>
+{
+    /// Create new EAX instance from initialized block cipher.
+    ///
+    /// Returns error if tag size is not supported by EAX.
+    pub fn from_cipher(cipher: C) -> Result<Self, ParamError> {
+        let m = M::to_usize();
+        if !(1..=16).contains(&m) {
+            return Err(ParamError::TagSize);
+        }
+        let mut l = Block::default();
+        cipher.encrypt_block(&mut l);
+        let k1 = l.dbl();
+        let k2 = k1.dbl();
+        Ok(Self { cipher, k1, k2, _sizes: PhantomData })
+    }
+
+    /// Create new EAX instance from key with fixed size.
+    pub fn new(key: &GenericArray<u8, C::KeySize>)
+        -> Result<Self, ParamError>
+    {
+        Self::from_cipher(C::new(key))
+    }
+
+    /// Create new EAX instance from key with variable size.
+    pub fn new_varkey(key: &[u8]) -> Result<Self, ParamError> {
+        let cipher = C::new_varkey(key).map_err(|_| ParamError::KeyLength)?;
+        Self::from_cipher(cipher)
+    }
+
+    /// Compute OMAC (CMAC) over the tweak block `[t]_16` followed by `data`.
+    fn omac(&self, t: u8, data: &[u8]) -> Block {
+        let mut state = Block::default();
+        state[15] = t;
+        if data.is_empty() {
+            xor(&mut state, &self.k1);
+            self.cipher.encrypt_block(&mut state);
+            return state;
+        }
+        self.cipher.encrypt_block(&mut state);
+
+        let n = (data.len() - 1) / 16 * 16;
+        let (head, last) = data.split_at(n);
+        for chunk in head.chunks(16) {
+            xor(&mut state, chunk);
+            self.cipher.encrypt_block(&mut state);
+        }
+        xor(&mut state, last);
+        if last.len() == 16 {
+            xor(&mut state, &self.k1);
+        } else {
+            state[last.len()] ^= 0x80;
+            xor(&mut state, &self.k2);
+        }
+        self.cipher.encrypt_block(&mut state);
+        state
+    }
+
+    fn apply_keystream(&self, n: &Block, buffer: &mut [u8]) {
+        let mut ctr = u128::from_be_bytes(to_array(n));
+        apply_ctr(&self.cipher, buffer, |block| {
+            block.copy_from_slice(&ctr.to_be_bytes());
+            ctr = ctr.wrapping_add(1);
+        });
+    }
+
+    fn compute_tag(&self, n: &Block, h: &Block, ct: &[u8])
+        -> GenericArray<u8, M>
+    {
+        let mut tag = self.omac(2, ct);
+        xor(&mut tag, n);
+        xor(&mut tag, h);
+        GenericArray::clone_from_slice(&tag[..M::to_usize()])
+    }
+}
+
+impl<C, M, N> Aead for Eax<C, M, N>
+    where C: BlockEncrypt<BlockSize = U16>,
+        M: ArrayLength<u8>, N: ArrayLength<u8>
+{
+    type NonceSize = N;
+    type TagSize = M;
+
+    fn encrypt_in_place_detached(
+        &self,
+        nonce: &GenericArray<u8, N>,
+        associated_data: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<GenericArray<u8, M>, Error> {
+        let n = self.omac(0, nonce);
+        let h = self.omac(1, associated_data);
+        self.apply_keystream(&n, buffer);
+        Ok(self.compute_tag(&n, &h, buffer))
+    }
+
+    fn decrypt_in_place_detached(
+        &self,
+        nonce: &GenericArray<u8, N>,
+        associated_data: &[u8],
+        buffer: &mut [u8],
+        tag: &GenericArray<u8, M>,
+    ) -> Result<(), Error> {
+        let n = self.omac(0, nonce);
+        let h = self.omac(1, associated_data);
+        verify_tag(self.compute_tag(&n, &h, buffer), tag)?;
+        self.apply_keystream(&n, buffer);
+        Ok(())
+    }
+}
+
+#[inline(always)]
+fn to_array(block: &Block) -> [u8; 16] {
+    let mut buf = [0u8; 16];
+    buf.copy_from_slice(block);
+    buf
+}
@@ -0,0 +1,209 @@
+//! Generic implementation of the [Galois/Counter Mode][1] (GCM) over block
+//! ciphers with 128-bit block size.
+//!
+//! [1]: https://csrc.nist.gov/publications/detail/sp/800-38d/final
+use block_cipher_trait::BlockEncrypt;
+use generic_array::{GenericArray, ArrayLength};
+use generic_array::typenum::{U12, U16};
+use core::marker::PhantomData;
+use utils::{apply_ctr, xor};
+use {Aead, NewAead, Error, verify_tag};
+
+type Block = GenericArray<u8, U16>;
+
+/// Maximum message length in bytes: `2^39 - 256` bits
+const P_MAX: u64 = (1 << 36) - 32;
+/// Maximum associated data length in bytes: `2^64 - 1` bits
+const A_MAX: u64 = (1 << 61) - 1;
+
+/// GCM instance over block cipher `C` with nonce size `N` (12 bytes by
+/// default).
+///
+/// Nonces with size other than 12 bytes are processed with GHASH as
+/// described in the NIST SP 800-38D. Authentication tag has size of 16 bytes.
+pub struct Gcm<C, N = U12>
+    where C: BlockEncrypt<BlockSize = U16>, N: ArrayLength<u8>
+{
+    cipher: C,
+    ghash_key: u128,
+    _nonce: PhantomData<N>,
+}
+
+impl<C, N> Gcm<C, N>
+    where C: BlockEncrypt<BlockSize = U16>, N: ArrayLength<u8>
+{
+    /// Create new GCM instance from initialized block cipher.
+    pub fn from_cipher(cipher: C) -> Self {
+        let mut h = Block::default();
+        cipher.encrypt_block(&mut h);
+        Self { cipher, ghash_key: to_u128(&h), _nonce: PhantomData }
+    }
+
+    /// Compute pre-counter block `J0` from nonce.
+    fn init_counter(&self, nonce: &GenericArray<u8, N>) -> Block {
+        let mut j0 = Block::default();
+        if N::to_usize() == 12 {
+            j0[..12].copy_from_slice(nonce);
+            j0[15] = 1;
+        } else {
+            let mut ghash = GHash::new(self.ghash_key);
+            ghash.update_padded(nonce);
+            ghash.update_lengths(0, nonce.len() as u64);
+            j0 = ghash.finalize();
+        }
+        j0
+    }
+
+    /// Apply keystream generated from counter blocks starting with `j0 + 1`.
+    fn apply_keystream(&self, j0: &Block, buffer: &mut [u8]) {
+        let mut counter = 1u32;
+        apply_ctr(&self.cipher, buffer, |block| {
+            *block = inc32(j0, counter);
+            counter = counter.wrapping_add(1);
+        });
+    }
+
+    fn compute_tag(&self, j0: &Block, associated_data: &[u8], ct: &[u8])
+        -> Block
+    {
+        let mut ghash = GHash::new(self.ghash_key);
+        ghash.update_padded(associated_data);
+        ghash.update_padded(ct);
+        ghash.update_lengths(associated_data.len() as u64, ct.len() as u64);
+        let mut tag = ghash.finalize();
+        let mut mask = *j0;
+        self.cipher.encrypt_block(&mut mask);
+        xor(&mut tag, &mask);
+        tag
+    }
+
+    fn check_lengths(associated_data: &[u8], buffer: &[u8])
+        -> Result<(), Error>
+    {
+        if buffer.len() as u64 > P_MAX || associated_data.len() as u64 > A_MAX
+            || N::to_usize() == 0
+        {
+            Err(Error)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<C, N> NewAead for Gcm<C, N>
+    where C: BlockEncrypt<BlockSize = U16>, N: ArrayLength<u8>
+{
+    type KeySize = C::KeySize;
+
+    fn new(key: &GenericArray<u8, C::KeySize>) -> Self {
+        Self::from_cipher(C::new(key))
+    }
+
+    fn new_varkey(key: &[u8]) -> Result<Self, ::InvalidKeyLength> {
+        C::new_varkey(key)
+            .map(Self::from_cipher)
+            .map_err(|_| ::InvalidKeyLength)
+    }
+}
+
+impl<C, N> Aead for Gcm<C, N>
+    where C: BlockEncrypt<BlockSize = U16>, N: ArrayLength<u8>
+{
+    type NonceSize = N;
+    type TagSize = U16;
+
+    fn encrypt_in_place_detached(
+        &self,
+        nonce: &GenericArray<u8, N>,
+        associated_data: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<Block, Error> {
+        Self::check_lengths(associated_data, buffer)?;
+        let j0 = self.init_counter(nonce);
+        self.apply_keystream(&j0, buffer);
+        Ok(self.compute_tag(&j0, associated_data, buffer))
+    }
+
+    fn decrypt_in_place_detached(
+        &self,
+        nonce: &GenericArray<u8, N>,
+        associated_data: &[u8],
+        buffer: &mut [u8],
+        tag: &Block,
+    ) -> Result<(), Error> {
+        Self::check_lengths(associated_data, buffer)?;
+        let j0 = self.init_counter(nonce);
+        let expected = self.compute_tag(&j0, associated_data, buffer);
+        verify_tag(expected, tag)?;
+        self.apply_keystream(&j0, buffer);
+        Ok(())
+    }
+}
+
+/// Return counter block with the last 32 bits of `j0` incremented by `n`.
+#[inline(always)]
+fn inc32(j0: &Block, n: u32) -> Block {
+    let mut block = *j0;
+    let ctr = u32::from_be_bytes([j0[12], j0[13], j0[14], j0[15]]);
+    block[12..].copy_from_slice(&ctr.wrapping_add(n).to_be_bytes());
+    block
+}
+
+#[inline(always)]
+fn to_u128(block: &[u8]) -> u128 {
+    let mut buf = [0u8; 16];
+    buf.copy_from_slice(block);
+    u128::from_be_bytes(buf)
+}
+
+/// GHASH universal hash function.
+///
+/// Multiplication in GF(2^128) is implemented without secret dependent
+/// branches and table lookups.
+pub(crate) struct GHash {
+    h: u128,
+    y: u128,
+}
+
+impl GHash {
+    pub(crate) fn new(h: u128) -> Self {
+        GHash { h, y: 0 }
+    }
+
+    fn update_block(&mut self, block: u128) {
+        self.y = gf_mul(self.y ^ block, self.h);
+    }
+
+    /// Process data zero-padded to a multiple of block size.
+    pub(crate) fn update_padded(&mut self, data: &[u8]) {
+        for chunk in data.chunks(16) {
+            let mut buf = [0u8; 16];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            self.update_block(u128::from_be_bytes(buf));
+        }
+    }
+
+    /// Process block with bit lengths of associated data and ciphertext.
+    pub(crate) fn update_lengths(&mut self, a_len: u64, c_len: u64) {
+        let block = ((a_len as u128 * 8) << 64) | (c_len as u128 * 8);
+        self.update_block(block);
+    }
+
+    pub(crate) fn finalize(self) -> Block {
+        GenericArray::clone_from_slice(&self.y.to_be_bytes())
+    }
+}
+
+/// Multiplication in GF(2^128) with GCM bit order.
+fn gf_mul(x: u128, y: u128) -> u128 {
+    const R: u128 = 0xE1 << 120;
+    let mut z = 0;
+    let mut v = y;
+    for i in 0..128 {
+        let bit = (x >> (127 - i)) & 1;
+        z ^= v & bit.wrapping_neg();
+        let lsb = v & 1;
+        v = (v >> 1) ^ (R & lsb.wrapping_neg());
+    }
+    z
+}
@@ -0,0 +1,203 @@
+//! This crate provides traits for Authenticated Encryption with Associated
+//! Data (AEAD) algorithms and their generic implementations over block
+//! ciphers.
+//!
+//! Since methods of the `Aead` trait take `&self`, the generic modes require
+//! `BlockEncrypt` (`BlockCipher` for OCB3) instead of the `BlockEncryptMut`
+//! trait. `Siv` uses the same bounds, since its state is shared with the
+//! `SivAead` wrapper.
+//!
+//! Methods which allocate (`encrypt`, `decrypt` and in-place methods which
+//! operate on `Vec<u8>`) are available with enabled `alloc` feature. `std`
+//! feature additionally enables `std::io` adapters in the `stream` module.
+#![cfg_attr(not(feature = "std"), no_std)]
+pub extern crate block_cipher_trait;
+pub extern crate crypto_mac;
+extern crate dbl;
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+use std as core;
+
+pub use block_cipher_trait::generic_array;
+
+use generic_array::{GenericArray, ArrayLength};
+use generic_array::typenum::Unsigned;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+mod utils;
+pub mod gcm;
+pub mod ccm;
+pub mod eax;
+pub mod ocb3;
+pub mod siv;
+pub mod stream;
+#[cfg(feature = "dev")]
+pub mod dev;
+
+pub use gcm::Gcm;
+pub use ccm::Ccm;
+pub use eax::Eax;
+pub use ocb3::Ocb3;
+pub use siv::{Siv, SivAead};
+
+/// Error type for AEAD operations.
+///
+/// It intentionally does not reveal the reason of a failure, e.g. it can not
+/// be used to distinguish authentication failure from invalid input length.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Error;
+
+/// Error type for signaling invalid key length for AEAD initialization
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct InvalidKeyLength;
+
+/// Error type for signaling invalid parameters of AEAD algorithm instance
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ParamError {
+    /// Key length is not supported by the underlying block cipher
+    KeyLength,
+    /// Nonce size is not supported by the algorithm
+    NonceSize,
+    /// Tag size is not supported by the algorithm
+    TagSize,
+}
+
+/// Instantiation of AEAD algorithm from a key.
+pub trait NewAead: core::marker::Sized {
+    /// Size of the key in bytes
+    type KeySize: ArrayLength<u8>;
+
+    /// Create new AEAD instance from key with fixed size.
+    fn new(key: &GenericArray<u8, Self::KeySize>) -> Self;
+
+    /// Create new AEAD instance from key with variable size.
+    ///
+    /// Default implementation will accept only keys with length equal to
+    /// `KeySize`.
+    fn new_varkey(key: &[u8]) -> Result<Self, InvalidKeyLength> {
+        if key.len() != Self::KeySize::to_usize() {
+            Err(InvalidKeyLength)
+        } else {
+            Ok(Self::new(GenericArray::from_slice(key)))
+        }
+    }
+}
+
+/// Authenticated Encryption with Associated Data (AEAD) algorithm.
+///
+/// Ciphertext produced by allocating methods is a concatenation of the
+/// encrypted message and the authentication tag, unless implementation
+/// documents otherwise (e.g. `SivAead` prepends the tag as described in
+/// the RFC 5297).
+pub trait Aead {
+    /// Size of the nonce in bytes
+    type NonceSize: ArrayLength<u8>;
+    /// Size of the authentication tag in bytes
+    type TagSize: ArrayLength<u8>;
+
+    /// Encrypt the data in-place and return authentication tag.
+    ///
+    /// Returns `Err(Error)` without modifying `buffer` if lengths of the
+    /// message or associated data exceed limits of the algorithm.
+    fn encrypt_in_place_detached(
+        &self,
+        nonce: &GenericArray<u8, Self::NonceSize>,
+        associated_data: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<GenericArray<u8, Self::TagSize>, Error>;
+
+    /// Verify authentication tag and decrypt the data in-place.
+    ///
+    /// On verification failure `Err(Error)` is returned and `buffer` does
+    /// not contain any part of decrypted message.
+    fn decrypt_in_place_detached(
+        &self,
+        nonce: &GenericArray<u8, Self::NonceSize>,
+        associated_data: &[u8],
+        buffer: &mut [u8],
+        tag: &GenericArray<u8, Self::TagSize>,
+    ) -> Result<(), Error>;
+
+    /// Encrypt the data in-place and append authentication tag to it.
+    #[cfg(feature = "alloc")]
+    fn encrypt_in_place(
+        &self,
+        nonce: &GenericArray<u8, Self::NonceSize>,
+        associated_data: &[u8],
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        let tag = self.encrypt_in_place_detached(
+            nonce, associated_data, buffer)?;
+        buffer.extend_from_slice(&tag);
+        Ok(())
+    }
+
+    /// Verify authentication tag at the end of the buffer, decrypt the data
+    /// in-place and truncate buffer to the message length.
+    ///
+    /// On verification failure `Err(Error)` is returned and `buffer` is left
+    /// with the ciphertext.
+    #[cfg(feature = "alloc")]
+    fn decrypt_in_place(
+        &self,
+        nonce: &GenericArray<u8, Self::NonceSize>,
+        associated_data: &[u8],
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        let tag_len = Self::TagSize::to_usize();
+        if buffer.len() < tag_len {
+            return Err(Error);
+        }
+        let n = buffer.len() - tag_len;
+        let tag = GenericArray::clone_from_slice(&buffer[n..]);
+        self.decrypt_in_place_detached(
+            nonce, associated_data, &mut buffer[..n], &tag)?;
+        buffer.truncate(n);
+        Ok(())
+    }
+
+    /// Encrypt the message and return ciphertext with appended
+    /// authentication tag.
+    #[cfg(feature = "alloc")]
+    fn encrypt(
+        &self,
+        nonce: &GenericArray<u8, Self::NonceSize>,
+        associated_data: &[u8],
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        let mut buffer = Vec::with_capacity(
+            plaintext.len() + Self::TagSize::to_usize());
+        buffer.extend_from_slice(plaintext);
+        self.encrypt_in_place(nonce, associated_data, &mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Verify authentication tag appended to the ciphertext and return
+    /// decrypted message.
+    #[cfg(feature = "alloc")]
+    fn decrypt(
+        &self,
+        nonce: &GenericArray<u8, Self::NonceSize>,
+        associated_data: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        let mut buffer = ciphertext.to_vec();
+        self.decrypt_in_place(nonce, associated_data, &mut buffer)?;
+        Ok(buffer)
+    }
+}
+
+/// Compare authentication tags in constant time.
+#[inline]
+fn verify_tag<N>(expected: GenericArray<u8, N>, tag: &[u8])
+    -> Result<(), Error>
+    where N: ArrayLength<u8>
+{
+    if crypto_mac::MacResult::new(expected).is_equal(tag) {
+        Ok(())
+    } else {
+        Err(Error)
+    }
+}
@@ -0,0 +1,283 @@
+//! Generic implementation of the [OCB3][1] mode over block ciphers with
+//! 128-bit block size.
+//!
+//! Tag size `M` can be in the range from 1 to 16 bytes, nonce size `N` can be
+//! in the range from 1 to 15 bytes (12 bytes by default).
+//!
+//! Every message and associated data block is processed independently, so
+//! full batches of `BlockCipher::ParBlocks` blocks are processed using
+//! `encrypt_blocks` and `decrypt_blocks` methods.
+//!
+//! Since checksum is computed over the plaintext, on authentication failure
+//! buffer is encrypted back, so it contains the original ciphertext.
+//!
+//! [1]: https://tools.ietf.org/html/rfc7253
+use block_cipher_trait::BlockCipher;
+use dbl::Dbl;
+use generic_array::{GenericArray, ArrayLength};
+use generic_array::typenum::{U12, U16, Unsigned};
+use core::marker::PhantomData;
+use utils::xor;
+use {Aead, Error, ParamError, verify_tag};
+
+type Block = GenericArray<u8, U16>;
+
+/// Number of precomputed `L_i` values, it's enough to process messages with
+/// length up to `2^64` blocks.
+const L_TABLE_SIZE: usize = 64;
+
+/// OCB3 instance over block cipher `C` with tag size `M` and nonce size `N`.
+///
+/// Validity of `M` and `N` is checked by constructors, so this type does not
+/// implement `NewAead` trait.
+pub struct Ocb3<C, M = U16, N = U12>
+    where C: BlockCipher<BlockSize = U16>,
+        M: ArrayLength<u8>, N: ArrayLength<u8>
+{
+    cipher: C,
+    l_star: Block,
+    l_dollar: Block,
+    l: [Block; L_TABLE_SIZE],
+    _sizes: PhantomData<(M, N)>,
+}
+
+impl<C, M, N> Ocb3<C, M, N>
+    where C: BlockCipher<BlockSize = U16>,
+        M: ArrayLength<u8>, N: ArrayLength<u8>
+{
+    /// Create new OCB3 instance from initialized block cipher.
+    ///
+    /// Returns error if tag size or nonce size is not supported by OCB3.
+    pub fn from_cipher(cipher: C) -> Result<Self, ParamError> {
+        if !(1..=16).contains(&M::to_usize()) {
+            return Err(ParamError::TagSize);
+        }
+        if !(1..=15).contains(&N::to_usize()) {
+            return Err(ParamError::NonceSize);
+        }
+        let mut l_star = Block::default();
+        cipher.encrypt_block(&mut l_star);
+        let l_dollar = l_star.dbl();
+        let mut l = [Block::default(); L_TABLE_SIZE];
+        let mut prev = l_dollar;
+        for li in l.iter_mut() {
+            prev = prev.dbl();
+            *li = prev;
+        }
+        Ok(Self { cipher, l_star, l_dollar, l, _sizes: PhantomData })
+    }
+
+    /// Create new OCB3 instance from key with fixed size.
+    pub fn new(key: &GenericArray<u8, C::KeySize>)
+        -> Result<Self, ParamError>
+    {
+        Self::from_cipher(C::new(key))
+    }
+
+    /// Create new OCB3 instance from key with variable size.
+    pub fn new_varkey(key: &[u8]) -> Result<Self, ParamError> {
+        let cipher = C::new_varkey(key).map_err(|_| ParamError::KeyLength)?;
+        Self::from_cipher(cipher)
+    }
+
+    /// Get `L_{ntz(i)}` value for the block with index `i`.
+    #[inline(always)]
+    fn l_ntz(&self, i: u64) -> &Block {
+        &self.l[i.trailing_zeros() as usize]
+    }
+
+    /// Compute initial offset `Offset_0` from nonce.
+    fn init_offset(&self, nonce: &GenericArray<u8, N>) -> Block {
+        let n = N::to_usize();
+        let mut block = Block::default();
+        block[0] = ((M::to_usize() * 8 % 128) << 1) as u8;
+        block[15 - n] |= 1;
+        block[16 - n..].copy_from_slice(nonce);
+
+        let bottom = (block[15] & 0x3f) as u32;
+        block[15] &= 0xc0;
+        self.cipher.encrypt_block(&mut block);
+        let ktop = u128::from_be_bytes(to_array(&block));
+        let stretch = ((ktop >> 64) as u64) ^ ((ktop >> 56) as u64);
+        let offset = if bottom == 0 {
+            ktop
+        } else {
+            (ktop << bottom) | (stretch >> (64 - bottom)) as u128
+        };
+        GenericArray::clone_from_slice(&offset.to_be_bytes())
+    }
+
+    /// Compute `HASH` of the associated data.
+    fn hash(&self, associated_data: &[u8]) -> Block {
+        let pb = C::ParBlocks::to_usize();
+        let mut offset = Block::default();
+        let mut sum = Block::default();
+        let mut blocks = GenericArray::<Block, C::ParBlocks>::default();
+
+        let n = associated_data.len() / 16 * 16;
+        let (full, tail) = associated_data.split_at(n);
+        let mut i = 1;
+        for chunk in full.chunks(16 * pb) {
+            for (block, a) in blocks.iter_mut().zip(chunk.chunks(16)) {
+                xor(&mut offset, self.l_ntz(i));
+                i += 1;
+                block.copy_from_slice(a);
+                xor(block, &offset);
+            }
+            let n = chunk.len() / 16;
+            self.encrypt_batch(&mut blocks, n);
+            for block in blocks.iter().take(n) {
+                xor(&mut sum, block);
+            }
+        }
+
+        if !tail.is_empty() {
+            xor(&mut offset, &self.l_star);
+            let mut block = Block::default();
+            block[..tail.len()].copy_from_slice(tail);
+            block[tail.len()] = 0x80;
+            xor(&mut block, &offset);
+            self.cipher.encrypt_block(&mut block);
+            xor(&mut sum, &block);
+        }
+        sum
+    }
+
+    /// Encrypt or decrypt buffer in-place and return checksum xored with the
+    /// final offset.
+    fn crypt(&self, nonce: &GenericArray<u8, N>, buffer: &mut [u8],
+        decrypt: bool) -> Block
+    {
+        let pb = C::ParBlocks::to_usize();
+        let mut offset = self.init_offset(nonce);
+        let mut checksum = Block::default();
+        let mut offsets = GenericArray::<Block, C::ParBlocks>::default();
+        let mut blocks = GenericArray::<Block, C::ParBlocks>::default();
+
+        let n = buffer.len() / 16 * 16;
+        let (full, tail) = buffer.split_at_mut(n);
+        let mut i = 1;
+        for chunk in full.chunks_mut(16 * pb) {
+            let iter = blocks.iter_mut().zip(offsets.iter_mut());
+            for ((block, o), p) in iter.zip(chunk.chunks(16)) {
+                xor(&mut offset, self.l_ntz(i));
+                i += 1;
+                *o = offset;
+                block.copy_from_slice(p);
+                xor(block, o);
+            }
+            let n = chunk.len() / 16;
+            if decrypt {
+                self.decrypt_batch(&mut blocks, n);
+            } else {
+                self.encrypt_batch(&mut blocks, n);
+            }
+            let iter = blocks.iter().zip(offsets.iter());
+            for ((block, o), c) in iter.zip(chunk.chunks_mut(16)) {
+                if !decrypt {
+                    xor(&mut checksum, c);
+                }
+                c.copy_from_slice(block);
+                xor(c, o);
+                if decrypt {
+                    xor(&mut checksum, c);
+                }
+            }
+        }
+
+        if !tail.is_empty() {
+            xor(&mut offset, &self.l_star);
+            let mut pad = offset;
+            self.cipher.encrypt_block(&mut pad);
+            if !decrypt {
+                xor(&mut checksum, tail);
+            }
+            xor(tail, &pad);
+            if decrypt {
+                xor(&mut checksum, tail);
+            }
+            checksum[tail.len()] ^= 0x80;
+        }
+
+        xor(&mut checksum, &offset);
+        checksum
+    }
+
+    fn compute_tag(&self, checksum: Block, associated_data: &[u8])
+        -> GenericArray<u8, M>
+    {
+        let mut tag = checksum;
+        xor(&mut tag, &self.l_dollar);
+        self.cipher.encrypt_block(&mut tag);
+        xor(&mut tag, &self.hash(associated_data));
+        GenericArray::clone_from_slice(&tag[..M::to_usize()])
+    }
+
+    /// Encrypt first `n` blocks, using `encrypt_blocks` for a full batch.
+    #[inline(always)]
+    fn encrypt_batch(&self, blocks: &mut GenericArray<Block, C::ParBlocks>,
+        n: usize)
+    {
+        if n == C::ParBlocks::to_usize() {
+            self.cipher.encrypt_blocks(blocks);
+        } else {
+            for block in blocks.iter_mut().take(n) {
+                self.cipher.encrypt_block(block);
+            }
+        }
+    }
+
+    /// Decrypt first `n` blocks, using `decrypt_blocks` for a full batch.
+    #[inline(always)]
+    fn decrypt_batch(&self, blocks: &mut GenericArray<Block, C::ParBlocks>,
+        n: usize)
+    {
+        if n == C::ParBlocks::to_usize() {
+            self.cipher.decrypt_blocks(blocks);
+        } else {
+            for block in blocks.iter_mut().take(n) {
+                self.cipher.decrypt_block(block);
+            }
+        }
+    }
+}
+
+impl<C, M, N> Aead for Ocb3<C, M, N>
+    where C: BlockCipher<BlockSize = U16>,
+        M: ArrayLength<u8>, N: ArrayLength<u8>
+{
+    type NonceSize = N;
+    type TagSize = M;
+
+    fn encrypt_in_place_detached(
+        &self,
+        nonce: &GenericArray<u8, N>,
+        associated_data: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<GenericArray<u8, M>, Error> {
+        let checksum = self.crypt(nonce, buffer, false);
+        Ok(self.compute_tag(checksum, associated_data))
+    }
+
+    fn decrypt_in_place_detached(
+        &self,
+        nonce: &GenericArray<u8, N>,
+        associated_data: &[u8],
+        buffer: &mut [u8],
+        tag: &GenericArray<u8, M>,
+    ) -> Result<(), Error> {
+        let checksum = self.crypt(nonce, buffer, true);
+        let res = verify_tag(self.compute_tag(checksum, associated_data), tag);
+        if res.is_err() {
+            self.crypt(nonce, buffer, false);
+        }
+        res
+    }
+}
+
+#[inline(always)]
+fn to_array(block: &Block) -> [u8; 16] {
+    let mut buf = [0u8; 16];
+    buf.copy_from_slice(block);
+    buf
+}
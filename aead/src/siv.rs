@@ -0,0 +1,296 @@
+//! Generic implementation of the [Synthetic Initialization Vector][1] (SIV)
+//! mode over block ciphers with 128-bit block size.
+//!
+//! SIV is a deterministic authenticated encryption mode: the same message
+//! and the same associated data always produce the same ciphertext. It does
+//! not require a nonce, but a nonce can be passed as the last component of
+//! the associated data, in which case repeated nonce only reveals whether the
+//! same message was encrypted twice.
+//!
+//! The S2V part is computed using a MAC `M` which has to implement CMAC (e.g.
+//! `Cmac<C>`), while the message is encrypted in CTR mode using block cipher
+//! `C`. The key is a concatenation of the MAC key and the cipher key, so
+//! AES-SIV-256 uses 32 byte keys and AES-SIV-512 uses 64 byte keys.
+//!
+//! [1]: https://tools.ietf.org/html/rfc5297
+use block_cipher_trait::BlockEncrypt;
+use crypto_mac::Mac;
+use dbl::Dbl;
+use generic_array::{GenericArray, ArrayLength};
+use generic_array::typenum::{U16, Unsigned};
+use generic_array::typenum::Sum;
+use core::ops::Add;
+use core::marker::PhantomData;
+use utils::{apply_ctr, xor};
+use {Aead, Error, verify_tag};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+type Block = GenericArray<u8, U16>;
+
+/// Key size of the SIV instance over cipher `C` and MAC `M`
+pub type SivKeySize<C, M> = Sum<
+    <M as Mac>::KeySize, <C as BlockEncrypt>::KeySize>;
+
+/// Maximum number of associated data components supported by S2V
+pub const MAX_HEADERS: usize = 126;
+
+/// SIV instance over block cipher `C` and CMAC implementation `M`.
+///
+/// Since `Mac` methods require mutable access, all methods of this type take
+/// `&mut self`. `SivAead` wrapper can be used for the `Aead` trait.
+pub struct Siv<C, M>
+    where C: BlockEncrypt<BlockSize = U16>, M: Mac<OutputSize = U16>
+{
+    cipher: C,
+    mac: M,
+}
+
+impl<C, M> Siv<C, M>
+    where C: BlockEncrypt<BlockSize = U16>, M: Mac<OutputSize = U16>
+{
+    /// Create new SIV instance from initialized MAC and block cipher.
+    pub fn from_parts(mac: M, cipher: C) -> Self {
+        Self { cipher, mac }
+    }
+
+    /// Create new SIV instance from key with fixed size.
+    pub fn new(key: &GenericArray<u8, SivKeySize<C, M>>) -> Self
+        where M::KeySize: Add<C::KeySize>,
+            SivKeySize<C, M>: ArrayLength<u8>
+    {
+        let (k1, k2) = key.split_at(M::KeySize::to_usize());
+        Self::from_parts(
+            M::new(GenericArray::from_slice(k1)),
+            C::new(GenericArray::from_slice(k2)),
+        )
+    }
+
+    /// Create new SIV instance from key with variable size.
+    ///
+    /// Key is split in two halves which are used as MAC and cipher keys
+    /// respectively.
+    pub fn new_varkey(key: &[u8]) -> Result<Self, ::InvalidKeyLength> {
+        if key.len() % 2 != 0 {
+            return Err(::InvalidKeyLength);
+        }
+        let (k1, k2) = key.split_at(key.len() / 2);
+        let mac = M::new_varkey(k1).map_err(|_| ::InvalidKeyLength)?;
+        let cipher = C::new_varkey(k2).map_err(|_| ::InvalidKeyLength)?;
+        Ok(Self::from_parts(mac, cipher))
+    }
+
+    /// Encrypt the data in-place and return synthetic IV which acts as an
+    /// authentication tag.
+    ///
+    /// `headers` is a list of associated data components. Returns error if
+    /// there are more than `MAX_HEADERS` components.
+    pub fn encrypt_in_place_detached<I, T>(
+        &mut self, headers: I, buffer: &mut [u8],
+    ) -> Result<Block, Error>
+        where I: IntoIterator<Item = T>, T: AsRef<[u8]>
+    {
+        let siv = s2v(&mut self.mac, headers, buffer)?;
+        self.apply_keystream(&siv, buffer);
+        Ok(siv)
+    }
+
+    /// Decrypt the data in-place and verify synthetic IV.
+    ///
+    /// On verification failure `Err(Error)` is returned and `buffer` is left
+    /// with the ciphertext.
+    pub fn decrypt_in_place_detached<I, T>(
+        &mut self, headers: I, buffer: &mut [u8], siv: &Block,
+    ) -> Result<(), Error>
+        where I: IntoIterator<Item = T>, T: AsRef<[u8]>
+    {
+        self.apply_keystream(siv, buffer);
+        let res = s2v(&mut self.mac, headers, buffer)
+            .and_then(|expected| verify_tag(expected, siv));
+        if res.is_err() {
+            self.apply_keystream(siv, buffer);
+        }
+        res
+    }
+
+    /// Encrypt the message and return synthetic IV followed by ciphertext,
+    /// i.e. in the format described in the RFC 5297.
+    #[cfg(feature = "alloc")]
+    pub fn encrypt<I, T>(&mut self, headers: I, plaintext: &[u8])
+        -> Result<Vec<u8>, Error>
+        where I: IntoIterator<Item = T>, T: AsRef<[u8]>
+    {
+        let mut buffer = Vec::with_capacity(plaintext.len() + 16);
+        buffer.extend_from_slice(&[0; 16]);
+        buffer.extend_from_slice(plaintext);
+        let siv = self.encrypt_in_place_detached(headers, &mut buffer[16..])?;
+        buffer[..16].copy_from_slice(&siv);
+        Ok(buffer)
+    }
+
+    /// Verify synthetic IV prepended to the ciphertext and return decrypted
+    /// message.
+    #[cfg(feature = "alloc")]
+    pub fn decrypt<I, T>(&mut self, headers: I, ciphertext: &[u8])
+        -> Result<Vec<u8>, Error>
+        where I: IntoIterator<Item = T>, T: AsRef<[u8]>
+    {
+        if ciphertext.len() < 16 {
+            return Err(Error);
+        }
+        let (siv, ct) = ciphertext.split_at(16);
+        let mut buffer = ct.to_vec();
+        self.decrypt_in_place_detached(
+            headers, &mut buffer, GenericArray::from_slice(siv))?;
+        Ok(buffer)
+    }
+
+    fn apply_keystream(&self, siv: &Block, buffer: &mut [u8]) {
+        let mut q = *siv;
+        q[8] &= 0x7f;
+        q[12] &= 0x7f;
+        let mut ctr = u128::from_be_bytes(to_array(&q));
+        apply_ctr(&self.cipher, buffer, |block| {
+            block.copy_from_slice(&ctr.to_be_bytes());
+            ctr = ctr.wrapping_add(1);
+        });
+    }
+}
+
+/// SIV instance which implements `Aead` trait with nonce of size `N` (16
+/// bytes by default).
+///
+/// Associated data and nonce are passed to S2V as two separate components.
+/// Allocating methods of the `Aead` trait prepend the synthetic IV to the
+/// ciphertext, i.e. use the same format as `Siv::encrypt`, while the tag is
+/// appended by other `Aead` implementations.
+pub struct SivAead<C, M, N = U16>
+    where C: BlockEncrypt<BlockSize = U16>, M: Mac<OutputSize = U16> + Clone,
+        N: ArrayLength<u8>
+{
+    siv: Siv<C, M>,
+    _nonce: PhantomData<N>,
+}
+
+impl<C, M, N> SivAead<C, M, N>
+    where C: BlockEncrypt<BlockSize = U16>, M: Mac<OutputSize = U16> + Clone,
+        N: ArrayLength<u8>
+{
+    /// Create new instance from SIV state.
+    pub fn from_siv(siv: Siv<C, M>) -> Self {
+        Self { siv, _nonce: PhantomData }
+    }
+
+    /// Create new instance from key with variable size.
+    pub fn new_varkey(key: &[u8]) -> Result<Self, ::InvalidKeyLength> {
+        Siv::new_varkey(key).map(Self::from_siv)
+    }
+}
+
+impl<C, M, N> Aead for SivAead<C, M, N>
+    where C: BlockEncrypt<BlockSize = U16>, M: Mac<OutputSize = U16> + Clone,
+        N: ArrayLength<u8>
+{
+    type NonceSize = N;
+    type TagSize = U16;
+
+    fn encrypt_in_place_detached(
+        &self,
+        nonce: &GenericArray<u8, N>,
+        associated_data: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<Block, Error> {
+        let mut mac = self.siv.mac.clone();
+        let siv = s2v(&mut mac, [associated_data, &nonce[..]], buffer)?;
+        self.siv.apply_keystream(&siv, buffer);
+        Ok(siv)
+    }
+
+    fn decrypt_in_place_detached(
+        &self,
+        nonce: &GenericArray<u8, N>,
+        associated_data: &[u8],
+        buffer: &mut [u8],
+        tag: &Block,
+    ) -> Result<(), Error> {
+        let mut mac = self.siv.mac.clone();
+        self.siv.apply_keystream(tag, buffer);
+        let res = s2v(&mut mac, [associated_data, &nonce[..]], buffer)
+            .and_then(|expected| verify_tag(expected, tag));
+        if res.is_err() {
+            self.siv.apply_keystream(tag, buffer);
+        }
+        res
+    }
+
+    /// Encrypt the data in-place and prepend synthetic IV to it.
+    #[cfg(feature = "alloc")]
+    fn encrypt_in_place(
+        &self,
+        nonce: &GenericArray<u8, N>,
+        associated_data: &[u8],
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        let siv = self.encrypt_in_place_detached(
+            nonce, associated_data, buffer)?;
+        buffer.splice(..0, siv.iter().cloned());
+        Ok(())
+    }
+
+    /// Verify synthetic IV at the beginning of the buffer, decrypt the data
+    /// in-place and remove synthetic IV from the buffer.
+    ///
+    /// On verification failure `Err(Error)` is returned and `buffer` is left
+    /// with the synthetic IV and the ciphertext.
+    #[cfg(feature = "alloc")]
+    fn decrypt_in_place(
+        &self,
+        nonce: &GenericArray<u8, N>,
+        associated_data: &[u8],
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        if buffer.len() < 16 {
+            return Err(Error);
+        }
+        let siv = Block::clone_from_slice(&buffer[..16]);
+        self.decrypt_in_place_detached(
+            nonce, associated_data, &mut buffer[16..], &siv)?;
+        buffer.drain(..16);
+        Ok(())
+    }
+}
+
+/// S2V function over associated data components and the message.
+fn s2v<M, I, T>(mac: &mut M, headers: I, msg: &[u8]) -> Result<Block, Error>
+    where M: Mac<OutputSize = U16>, I: IntoIterator<Item = T>, T: AsRef<[u8]>
+{
+    mac.input(&[0; 16]);
+    let mut d = mac.result().code();
+    for (i, header) in headers.into_iter().enumerate() {
+        if i >= MAX_HEADERS {
+            return Err(Error);
+        }
+        mac.input(header.as_ref());
+        d = d.dbl();
+        xor(&mut d, &mac.result().code());
+    }
+
+    if msg.len() >= 16 {
+        let (head, tail) = msg.split_at(msg.len() - 16);
+        mac.input(head);
+        xor(&mut d, tail);
+    } else {
+        d = d.dbl();
+        xor(&mut d, msg);
+        d[msg.len()] ^= 0x80;
+    }
+    mac.input(&d);
+    Ok(mac.result().code())
+}
+
+#[inline(always)]
+fn to_array(block: &Block) -> [u8; 16] {
+    let mut buf = [0u8; 16];
+    buf.copy_from_slice(block);
+    buf
+}
@@ -0,0 +1,361 @@
+//! Online authenticated encryption of segmented messages using the
+//! [STREAM][1] construction over any AEAD algorithm.
+//!
+//! Message is split into chunks which are encrypted separately. Nonce of
+//! each chunk consists of a user provided prefix, 32-bit big endian chunk
+//! counter and a flag byte which is equal to 1 for the last chunk and to 0
+//! otherwise. Thus reordering, removal or duplication of chunks, as well as
+//! truncation of the stream, are detected on decryption.
+//!
+//! Prefix has size of `NonceSize - 5` bytes (e.g. 7 bytes for `Gcm`) and must
+//! be unique for every stream encrypted with the same key. Stream can contain
+//! up to `2^32` chunks.
+//!
+//! With enabled `std` feature `EncryptWriter` and `DecryptReader` adapters
+//! can be used for encryption of data from `std::io` sources.
+//!
+//! [1]: https://eprint.iacr.org/2015/189.pdf
+use generic_array::{GenericArray, ArrayLength};
+#[cfg(feature = "std")]
+use generic_array::typenum::Unsigned;
+use {Aead, Error, ParamError};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+
+type Tag<A> = GenericArray<u8, <A as Aead>::TagSize>;
+
+/// Sequence of chunk nonces.
+struct NonceSeq<N: ArrayLength<u8>> {
+    nonce: GenericArray<u8, N>,
+    counter: u64,
+}
+
+impl<N: ArrayLength<u8>> NonceSeq<N> {
+    fn new(prefix: &[u8]) -> Result<Self, ParamError> {
+        let n = N::to_usize();
+        if n < 5 || prefix.len() != n - 5 {
+            return Err(ParamError::NonceSize);
+        }
+        let mut nonce = GenericArray::default();
+        nonce[..n - 5].copy_from_slice(prefix);
+        Ok(Self { nonce, counter: 0 })
+    }
+
+    /// Get nonce for the next chunk or error if counter is exhausted.
+    fn next(&mut self, last: bool) -> Result<&GenericArray<u8, N>, Error> {
+        if self.counter > u32::MAX as u64 {
+            return Err(Error);
+        }
+        let n = N::to_usize();
+        let ctr = (self.counter as u32).to_be_bytes();
+        self.nonce[n - 5..n - 1].copy_from_slice(&ctr);
+        self.nonce[n - 1] = last as u8;
+        self.counter += 1;
+        Ok(&self.nonce)
+    }
+}
+
+/// STREAM encryptor over AEAD algorithm `A`.
+///
+/// Encryption of the last chunk consumes encryptor, so no chunks can be
+/// encrypted after it.
+pub struct StreamEncryptor<A: Aead> {
+    aead: A,
+    nonces: NonceSeq<A::NonceSize>,
+}
+
+impl<A: Aead> StreamEncryptor<A> {
+    /// Create new encryptor from AEAD instance and nonce prefix.
+    ///
+    /// Returns error if prefix length is not equal to `NonceSize - 5`.
+    pub fn new(aead: A, prefix: &[u8]) -> Result<Self, ParamError> {
+        Ok(Self { aead, nonces: NonceSeq::new(prefix)? })
+    }
+
+    /// Encrypt the next (non-last) chunk in-place and return its tag.
+    pub fn encrypt_next_in_place(
+        &mut self, associated_data: &[u8], buffer: &mut [u8],
+    ) -> Result<Tag<A>, Error> {
+        let nonce = self.nonces.next(false)?;
+        self.aead.encrypt_in_place_detached(nonce, associated_data, buffer)
+    }
+
+    /// Encrypt the last chunk in-place and return its tag.
+    pub fn encrypt_last_in_place(
+        mut self, associated_data: &[u8], buffer: &mut [u8],
+    ) -> Result<Tag<A>, Error> {
+        let nonce = self.nonces.next(true)?;
+        self.aead.encrypt_in_place_detached(nonce, associated_data, buffer)
+    }
+
+    /// Encrypt the next (non-last) chunk and return ciphertext with appended
+    /// tag.
+    #[cfg(feature = "alloc")]
+    pub fn encrypt_next(&mut self, associated_data: &[u8], plaintext: &[u8])
+        -> Result<Vec<u8>, Error>
+    {
+        let nonce = self.nonces.next(false)?;
+        self.aead.encrypt(nonce, associated_data, plaintext)
+    }
+
+    /// Encrypt the last chunk and return ciphertext with appended tag.
+    #[cfg(feature = "alloc")]
+    pub fn encrypt_last(mut self, associated_data: &[u8], plaintext: &[u8])
+        -> Result<Vec<u8>, Error>
+    {
+        let nonce = self.nonces.next(true)?;
+        self.aead.encrypt(nonce, associated_data, plaintext)
+    }
+}
+
+/// STREAM decryptor over AEAD algorithm `A`.
+///
+/// Decryption of the last chunk consumes decryptor. If the last chunk was
+/// not decrypted, the stream must be considered truncated.
+pub struct StreamDecryptor<A: Aead> {
+    aead: A,
+    nonces: NonceSeq<A::NonceSize>,
+}
+
+impl<A: Aead> StreamDecryptor<A> {
+    /// Create new decryptor from AEAD instance and nonce prefix.
+    ///
+    /// Returns error if prefix length is not equal to `NonceSize - 5`.
+    pub fn new(aead: A, prefix: &[u8]) -> Result<Self, ParamError> {
+        Ok(Self { aead, nonces: NonceSeq::new(prefix)? })
+    }
+
+    /// Verify tag and decrypt the next (non-last) chunk in-place.
+    pub fn decrypt_next_in_place(
+        &mut self, associated_data: &[u8], buffer: &mut [u8], tag: &Tag<A>,
+    ) -> Result<(), Error> {
+        let nonce = self.nonces.next(false)?;
+        self.aead.decrypt_in_place_detached(
+            nonce, associated_data, buffer, tag)
+    }
+
+    /// Verify tag and decrypt the last chunk in-place.
+    pub fn decrypt_last_in_place(
+        mut self, associated_data: &[u8], buffer: &mut [u8], tag: &Tag<A>,
+    ) -> Result<(), Error> {
+        let nonce = self.nonces.next(true)?;
+        self.aead.decrypt_in_place_detached(
+            nonce, associated_data, buffer, tag)
+    }
+
+    /// Verify tag appended to the next (non-last) chunk and return decrypted
+    /// data.
+    #[cfg(feature = "alloc")]
+    pub fn decrypt_next(&mut self, associated_data: &[u8], ciphertext: &[u8])
+        -> Result<Vec<u8>, Error>
+    {
+        let nonce = self.nonces.next(false)?;
+        self.aead.decrypt(nonce, associated_data, ciphertext)
+    }
+
+    /// Verify tag appended to the last chunk and return decrypted data.
+    #[cfg(feature = "alloc")]
+    pub fn decrypt_last(mut self, associated_data: &[u8], ciphertext: &[u8])
+        -> Result<Vec<u8>, Error>
+    {
+        let nonce = self.nonces.next(true)?;
+        self.aead.decrypt(nonce, associated_data, ciphertext)
+    }
+}
+
+/// Writer which encrypts data in chunks of `chunk_size` bytes and writes
+/// ciphertext chunks with appended tags into the underlying writer.
+///
+/// `finish` must be called after writing all data, otherwise the last chunk
+/// will not be written and decryption will fail.
+#[cfg(feature = "std")]
+pub struct EncryptWriter<A: Aead, W: Write> {
+    encryptor: StreamEncryptor<A>,
+    writer: W,
+    buffer: Vec<u8>,
+    chunk_size: usize,
+}
+
+#[cfg(feature = "std")]
+impl<A: Aead, W: Write> EncryptWriter<A, W> {
+    /// Create new writer.
+    ///
+    /// # Panics
+    /// If `chunk_size` is equal to zero.
+    pub fn new(encryptor: StreamEncryptor<A>, chunk_size: usize, writer: W)
+        -> Self
+    {
+        assert!(chunk_size != 0, "chunk size must not be zero");
+        let buffer = Vec::with_capacity(chunk_size);
+        Self { encryptor, writer, buffer, chunk_size }
+    }
+
+    /// Encrypt and write the last chunk, and return the underlying writer.
+    pub fn finish(self) -> io::Result<W> {
+        let EncryptWriter { encryptor, mut writer, mut buffer, .. } = self;
+        let tag = encryptor.encrypt_last_in_place(&[], &mut buffer)
+            .map_err(|_| encryption_error())?;
+        writer.write_all(&buffer)?;
+        writer.write_all(&tag)?;
+        writer.flush()?;
+        Ok(writer)
+    }
+
+    fn write_chunk(&mut self) -> io::Result<()> {
+        let tag = self.encryptor.encrypt_next_in_place(&[], &mut self.buffer)
+            .map_err(|_| encryption_error())?;
+        self.writer.write_all(&self.buffer)?;
+        self.writer.write_all(&tag)?;
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A: Aead, W: Write> Write for EncryptWriter<A, W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        if data.is_empty() {
+            return Ok(0);
+        }
+        // full chunk is written only after we know that it's not the last one
+        if self.buffer.len() == self.chunk_size {
+            self.write_chunk()?;
+        }
+        let n = core::cmp::min(self.chunk_size - self.buffer.len(), data.len());
+        self.buffer.extend_from_slice(&data[..n]);
+        Ok(n)
+    }
+
+    /// Flush the underlying writer. Buffered data is not written, since
+    /// chunks can not be shortened.
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Reader which reads ciphertext chunks produced by `EncryptWriter` with the
+/// same `chunk_size` from the underlying reader and decrypts them.
+///
+/// On authentication failure or if the stream is truncated an error with
+/// `InvalidData` kind is returned.
+#[cfg(feature = "std")]
+pub struct DecryptReader<A: Aead, R: Read> {
+    /// `None` after the last chunk was decrypted
+    decryptor: Option<StreamDecryptor<A>>,
+    reader: R,
+    /// Ciphertext chunk with tag and one read-ahead byte
+    buffer: Vec<u8>,
+    /// Number of ciphertext bytes in `buffer`
+    filled: usize,
+    /// Range of decrypted data in `buffer` which was not read yet
+    pos: usize,
+    end: usize,
+    chunk_size: usize,
+    /// Set after decryption failure, all following reads return error
+    failed: bool,
+}
+
+#[cfg(feature = "std")]
+impl<A: Aead, R: Read> DecryptReader<A, R> {
+    /// Create new reader.
+    ///
+    /// # Panics
+    /// If `chunk_size` is equal to zero.
+    pub fn new(decryptor: StreamDecryptor<A>, chunk_size: usize, reader: R)
+        -> Self
+    {
+        assert!(chunk_size != 0, "chunk size must not be zero");
+        let buffer = vec![0; chunk_size + A::TagSize::to_usize() + 1];
+        Self {
+            decryptor: Some(decryptor), reader, buffer,
+            filled: 0, pos: 0, end: 0, chunk_size, failed: false,
+        }
+    }
+
+    /// Return the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Read and decrypt the next chunk.
+    fn fill(&mut self) -> io::Result<()> {
+        let tag_len = A::TagSize::to_usize();
+        let full = self.chunk_size + tag_len;
+        if self.failed {
+            return Err(decryption_error());
+        }
+        if self.decryptor.is_none() {
+            return Ok(());
+        }
+        while self.filled < self.buffer.len() {
+            match self.reader.read(&mut self.buffer[self.filled..]) {
+                Ok(0) => break,
+                Ok(n) => self.filled += n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {},
+                Err(e) => return Err(e),
+            }
+        }
+
+        let res = if self.filled > full {
+            let (chunk, tag) = self.buffer[..full].split_at_mut(self.chunk_size);
+            let tag = GenericArray::from_slice(tag);
+            let decryptor = self.decryptor.as_mut().unwrap();
+            decryptor.decrypt_next_in_place(&[], chunk, tag)
+                .map(|_| self.chunk_size)
+        } else if self.filled >= tag_len {
+            let n = self.filled - tag_len;
+            let (chunk, tag) = self.buffer[..self.filled].split_at_mut(n);
+            let tag = GenericArray::from_slice(tag);
+            let decryptor = self.decryptor.take().unwrap();
+            decryptor.decrypt_last_in_place(&[], chunk, tag).map(|_| n)
+        } else {
+            Err(Error)
+        };
+        match res {
+            Ok(n) => {
+                self.pos = 0;
+                self.end = n;
+                Ok(())
+            },
+            Err(_) => {
+                self.failed = true;
+                Err(decryption_error())
+            },
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<A: Aead, R: Read> Read for DecryptReader<A, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos == self.end {
+            // move read-ahead byte to the beginning of the buffer
+            let full = self.chunk_size + A::TagSize::to_usize();
+            if self.filled > full {
+                self.buffer[0] = self.buffer[full];
+                self.filled = 1;
+            } else {
+                self.filled = 0;
+            }
+            self.pos = 0;
+            self.end = 0;
+            self.fill()?;
+        }
+        let n = core::cmp::min(self.end - self.pos, buf.len());
+        buf[..n].copy_from_slice(&self.buffer[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(feature = "std")]
+fn decryption_error() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "stream decryption failed")
+}
+
+#[cfg(feature = "std")]
+fn encryption_error() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "stream encryption failed")
+}
@@ -0,0 +1,42 @@
+use block_cipher_trait::BlockEncrypt;
+use generic_array::GenericArray;
+use generic_array::typenum::Unsigned;
+
+pub type Block<C> = GenericArray<u8, <C as BlockEncrypt>::BlockSize>;
+
+/// XOR `key` into the `buf`. If `key` is longer than `buf`, its tail is
+/// ignored.
+#[inline(always)]
+pub fn xor(buf: &mut [u8], key: &[u8]) {
+    for (a, b) in buf.iter_mut().zip(key) {
+        *a ^= *b;
+    }
+}
+
+/// Apply keystream produced by encryption of counter blocks to the `buffer`.
+///
+/// Closure `next_ctr` writes the next counter block into provided block.
+/// Counter blocks are encrypted in batches of `C::ParBlocks`.
+pub fn apply_ctr<C, F>(cipher: &C, buffer: &mut [u8], mut next_ctr: F)
+    where C: BlockEncrypt, F: FnMut(&mut Block<C>)
+{
+    let bs = C::BlockSize::to_usize();
+    let pb = C::ParBlocks::to_usize();
+    let mut ks = GenericArray::<Block<C>, C::ParBlocks>::default();
+    for chunk in buffer.chunks_mut(bs * pb) {
+        let n = (chunk.len() + bs - 1) / bs;
+        for block in ks.iter_mut().take(n) {
+            next_ctr(block);
+        }
+        if n == pb {
+            cipher.encrypt_blocks(&mut ks);
+        } else {
+            for block in ks.iter_mut().take(n) {
+                cipher.encrypt_block(block);
+            }
+        }
+        for (block, k) in chunk.chunks_mut(bs).zip(ks.iter()) {
+            xor(block, k);
+        }
+    }
+}
@@ -0,0 +1,337 @@
+extern crate aead;
+extern crate aes;
+#[macro_use(impl_cipher)]
+extern crate block_cipher_trait;
+extern crate cmac;
+
+use aead::{
+    Aead, Ccm, Eax, Gcm, NewAead, Ocb3, ParamError, Siv, SivAead,
+};
+use aead::dev::{run_aead_tests, run_siv_tests};
+use aead::stream::{
+    DecryptReader, EncryptWriter, StreamDecryptor, StreamEncryptor,
+};
+use block_cipher_trait::generic_array::{ArrayLength, GenericArray};
+use block_cipher_trait::generic_array::typenum::{
+    U1, U3, U6, U8, U10, U12, U13, U14, U16, U17, U32, U60,
+};
+use cmac::Cmac;
+use std::io::{self, Read, Write};
+
+impl_cipher!(Aes128, aes::Aes128, U16, U16);
+impl_cipher!(Aes128Seq, aes::Aes128, U16, U16, U1);
+impl_cipher!(Aes256, aes::Aes256, U32, U16);
+
+#[test]
+fn gcm_aes128() {
+    run_aead_tests(aead::dev::GCM_AES128_N12, |key| {
+        Gcm::<Aes128>::new_varkey(key).unwrap()
+    });
+    run_aead_tests(aead::dev::GCM_AES128_N8, |key| {
+        Gcm::<Aes128, U8>::new_varkey(key).unwrap()
+    });
+    run_aead_tests(aead::dev::GCM_AES128_N60, |key| {
+        Gcm::<Aes128, U60>::new_varkey(key).unwrap()
+    });
+}
+
+#[test]
+fn gcm_aes256() {
+    run_aead_tests(aead::dev::GCM_AES256_N12, |key| {
+        Gcm::<Aes256>::new_varkey(key).unwrap()
+    });
+    run_aead_tests(aead::dev::GCM_AES256_N8, |key| {
+        Gcm::<Aes256, U8>::new_varkey(key).unwrap()
+    });
+    run_aead_tests(aead::dev::GCM_AES256_N60, |key| {
+        Gcm::<Aes256, U60>::new_varkey(key).unwrap()
+    });
+}
+
+#[test]
+fn ccm_aes128() {
+    run_aead_tests(aead::dev::CCM_AES128_M8_N13, |key| {
+        Ccm::<Aes128, U8, U13>::new_varkey(key).unwrap()
+    });
+    run_aead_tests(aead::dev::CCM_AES128_M10_N13, |key| {
+        Ccm::<Aes128, U10, U13>::new_varkey(key).unwrap()
+    });
+}
+
+#[test]
+fn ccm_params() {
+    let key = [0; 16];
+    let res = Ccm::<Aes128, U3, U13>::new_varkey(&key);
+    assert_eq!(res.err(), Some(ParamError::TagSize));
+    let res = Ccm::<Aes128, U17, U13>::new_varkey(&key);
+    assert_eq!(res.err(), Some(ParamError::TagSize));
+    let res = Ccm::<Aes128, U8, U6>::new_varkey(&key);
+    assert_eq!(res.err(), Some(ParamError::NonceSize));
+    let res = Ccm::<Aes128, U8, U14>::new_varkey(&key);
+    assert_eq!(res.err(), Some(ParamError::NonceSize));
+    let res = Ccm::<Aes128, U8, U13>::new_varkey(&key[..15]);
+    assert_eq!(res.err(), Some(ParamError::KeyLength));
+}
+
+#[test]
+fn eax_aes128() {
+    run_aead_tests(aead::dev::EAX_AES128, |key| {
+        Eax::<Aes128>::new_varkey(key).unwrap()
+    });
+}
+
+#[test]
+fn siv_aes128() {
+    run_siv_tests::<Aes128, Cmac<Aes128>>(aead::dev::SIV_AES128);
+}
+
+/// `SivAead` must produce the same output as `Siv` with associated data and
+/// nonce passed as two headers, with synthetic IV prepended to ciphertext.
+#[test]
+fn siv_aead_layout() {
+    type Aes128Siv = SivAead<Aes128, Cmac<Aes128>>;
+    let key = [0x42; 32];
+    let nonce = GenericArray::clone_from_slice(&[0x24; 16]);
+    let aad = b"associated data";
+    let pt = b"hello world, this is a message";
+
+    let aead = Aes128Siv::new_varkey(&key).unwrap();
+    let ct = aead.encrypt(&nonce, aad, pt).unwrap();
+    let mut siv = Siv::<Aes128, Cmac<Aes128>>::new_varkey(&key).unwrap();
+    let expected = siv.encrypt(&[&aad[..], &nonce[..]], pt).unwrap();
+    assert_eq!(ct, expected);
+
+    let mut buf = pt.to_vec();
+    let tag = aead.encrypt_in_place_detached(&nonce, aad, &mut buf).unwrap();
+    assert_eq!(ct[..16], tag[..]);
+    assert_eq!(ct[16..], buf[..]);
+
+    assert_eq!(aead.decrypt(&nonce, aad, &ct).unwrap(), &pt[..]);
+    let mut bad = ct.clone();
+    bad[0] ^= 1;
+    let mut buf = bad.clone();
+    assert!(aead.decrypt_in_place(&nonce, aad, &mut buf).is_err());
+    assert_eq!(buf, bad);
+    assert!(aead.decrypt(&nonce, aad, &ct[..15]).is_err());
+}
+
+#[test]
+fn ocb3_aes128() {
+    run_aead_tests(aead::dev::OCB3_AES128, |key| {
+        Ocb3::<Aes128>::new_varkey(key).unwrap()
+    });
+}
+
+/// Compute the iterated test from the appendix A of the RFC 7253 for tag
+/// size `M`.
+fn ocb3_iterated<M: ArrayLength<u8>>() -> Vec<u8> {
+    let mut key = [0u8; 16];
+    key[15] = 8 * M::to_u8();
+    let ocb = Ocb3::<Aes128, M>::new_varkey(&key).unwrap();
+    let nonce = |n: u32| {
+        let mut buf = GenericArray::<u8, U12>::default();
+        buf[8..].copy_from_slice(&n.to_be_bytes());
+        buf
+    };
+    let mut c = Vec::new();
+    for i in 0..128 {
+        let s = vec![0u8; i as usize];
+        c.extend(ocb.encrypt(&nonce(3*i + 1), &s, &s).unwrap());
+        c.extend(ocb.encrypt(&nonce(3*i + 2), &[], &s).unwrap());
+        c.extend(ocb.encrypt(&nonce(3*i + 3), &s, &[]).unwrap());
+    }
+    ocb.encrypt(&nonce(385), &c, &[]).unwrap()
+}
+
+#[test]
+fn ocb3_aes128_iterated() {
+    let mut buf = [0u8; 16];
+    let decode = block_cipher_trait::dev::decode_hex;
+    assert_eq!(
+        ocb3_iterated::<U16>(),
+        decode("67e944d23256c5e0b6c61fa22fdf1ea2", &mut buf),
+    );
+    assert_eq!(
+        ocb3_iterated::<U12>(),
+        decode("77a3d8e73589158d25d01209", &mut buf),
+    );
+    assert_eq!(
+        ocb3_iterated::<U8>(),
+        decode("192c9b7bd90ba06a", &mut buf),
+    );
+}
+
+/// Messages longer than `ParBlocks` blocks are processed in batches, check
+/// that the result does not depend on the batch size.
+#[test]
+fn ocb3_par_blocks() {
+    let key = [0x42; 16];
+    let nonce = GenericArray::clone_from_slice(&[0x24; 12]);
+    let aad: Vec<u8> = (0..200).map(|i| i as u8).collect();
+    let pt: Vec<u8> = (0..300).map(|i| (3 * i) as u8).collect();
+
+    let ocb = Ocb3::<Aes128>::new_varkey(&key).unwrap();
+    let ct = ocb.encrypt(&nonce, &aad, &pt).unwrap();
+    let ocb_seq = Ocb3::<Aes128Seq>::new_varkey(&key).unwrap();
+    assert_eq!(ocb_seq.encrypt(&nonce, &aad, &pt).unwrap(), ct);
+    assert_eq!(ocb.decrypt(&nonce, &aad, &ct).unwrap(), pt);
+
+    let mut buf = ct.clone();
+    buf[250] ^= 1;
+    let bad = buf.clone();
+    assert!(ocb.decrypt_in_place(&nonce, &aad, &mut buf).is_err());
+    assert_eq!(buf, bad);
+}
+
+const PREFIX: &[u8] = b"prefix!";
+
+fn stream_aead() -> Gcm<Aes128> {
+    Gcm::new_varkey(&[0x42; 16]).unwrap()
+}
+
+/// Encrypt chunks of the message using STREAM.
+fn stream_encrypt(chunks: &[&[u8]]) -> Vec<Vec<u8>> {
+    let mut enc = StreamEncryptor::new(stream_aead(), PREFIX).unwrap();
+    let (last, chunks) = chunks.split_last().unwrap();
+    let mut res: Vec<Vec<u8>> = chunks.iter()
+        .map(|chunk| enc.encrypt_next(b"ad", chunk).unwrap())
+        .collect();
+    res.push(enc.encrypt_last(b"ad", last).unwrap());
+    res
+}
+
+/// Decrypt chunks of the message, the last one is decrypted as the last
+/// chunk of the stream.
+fn stream_decrypt(chunks: &[Vec<u8>]) -> Result<Vec<u8>, aead::Error> {
+    let mut dec = StreamDecryptor::new(stream_aead(), PREFIX).unwrap();
+    let (last, chunks) = chunks.split_last().unwrap();
+    let mut res = Vec::new();
+    for chunk in chunks {
+        res.extend(dec.decrypt_next(b"ad", chunk)?);
+    }
+    res.extend(dec.decrypt_last(b"ad", last)?);
+    Ok(res)
+}
+
+#[test]
+fn stream_roundtrip() {
+    let msg: Vec<u8> = (0..100).collect();
+    let chunks = [&msg[..10], &msg[10..20], &msg[20..99], &msg[99..]];
+    let ct = stream_encrypt(&chunks);
+    assert_eq!(stream_decrypt(&ct).unwrap(), msg);
+    assert_eq!(stream_decrypt(&stream_encrypt(&[&[]])).unwrap(), b"");
+
+    let mut enc = StreamEncryptor::new(stream_aead(), PREFIX).unwrap();
+    let mut dec = StreamDecryptor::new(stream_aead(), PREFIX).unwrap();
+    let mut buf = msg.clone();
+    let (first, last) = buf.split_at_mut(50);
+    let tag1 = enc.encrypt_next_in_place(b"ad", first).unwrap();
+    let tag2 = enc.encrypt_last_in_place(b"ad", last).unwrap();
+    let mut ct2 = first.to_vec();
+    ct2.extend_from_slice(&tag1);
+    assert_eq!(ct2, stream_encrypt(&[&msg[..50], &msg[50..]])[0]);
+    dec.decrypt_next_in_place(b"ad", first, &tag1).unwrap();
+    dec.decrypt_last_in_place(b"ad", last, &tag2).unwrap();
+    assert_eq!(buf, msg);
+
+    let res = StreamEncryptor::new(stream_aead(), &PREFIX[1..]);
+    assert_eq!(res.err(), Some(ParamError::NonceSize));
+}
+
+#[test]
+fn stream_truncation() {
+    let ct = stream_encrypt(&[b"first", b"second", b"third"]);
+    // missing last chunk
+    assert!(stream_decrypt(&ct[..2]).is_err());
+    assert!(stream_decrypt(&ct[..1]).is_err());
+    // missing first chunk
+    assert!(stream_decrypt(&ct[1..]).is_err());
+}
+
+#[test]
+fn stream_reordering() {
+    let ct = stream_encrypt(&[b"first", b"second", b"third"]);
+    let swapped = [ct[1].clone(), ct[0].clone(), ct[2].clone()];
+    assert!(stream_decrypt(&swapped).is_err());
+    let duplicated = [ct[0].clone(), ct[0].clone(), ct[2].clone()];
+    assert!(stream_decrypt(&duplicated).is_err());
+    assert_eq!(stream_decrypt(&ct).unwrap(), b"firstsecondthird");
+}
+
+#[test]
+fn stream_last_flag() {
+    // the last chunk encrypted as a non-last one
+    let mut enc = StreamEncryptor::new(stream_aead(), PREFIX).unwrap();
+    let ct = vec![
+        enc.encrypt_next(b"ad", b"first").unwrap(),
+        enc.encrypt_next(b"ad", b"second").unwrap(),
+    ];
+    assert!(stream_decrypt(&ct).is_err());
+
+    // the last chunk decrypted as a non-last one
+    let ct = stream_encrypt(&[b"first", b"second"]);
+    let mut dec = StreamDecryptor::new(stream_aead(), PREFIX).unwrap();
+    dec.decrypt_next(b"ad", &ct[0]).unwrap();
+    assert!(dec.decrypt_next(b"ad", &ct[1]).is_err());
+}
+
+/// Encrypt message with `EncryptWriter` using writes of `write_size` bytes.
+fn write_stream(msg: &[u8], chunk_size: usize, write_size: usize) -> Vec<u8> {
+    let enc = StreamEncryptor::new(stream_aead(), PREFIX).unwrap();
+    let mut writer = EncryptWriter::new(enc, chunk_size, Vec::new());
+    for data in msg.chunks(write_size) {
+        writer.write_all(data).unwrap();
+    }
+    writer.finish().unwrap()
+}
+
+/// Decrypt message with `DecryptReader` using reads of `read_size` bytes.
+fn read_stream(ct: &[u8], chunk_size: usize, read_size: usize)
+    -> io::Result<Vec<u8>>
+{
+    let dec = StreamDecryptor::new(stream_aead(), PREFIX).unwrap();
+    let mut reader = DecryptReader::new(dec, chunk_size, ct);
+    let mut res = Vec::new();
+    let mut buf = vec![0; read_size];
+    loop {
+        match reader.read(&mut buf)? {
+            0 => return Ok(res),
+            n => res.extend_from_slice(&buf[..n]),
+        }
+    }
+}
+
+#[test]
+fn stream_io_roundtrip() {
+    let chunk_size = 16;
+    let msg: Vec<u8> = (0..100).collect();
+    for &len in &[0, 1, 15, 16, 17, 32, 48, 100] {
+        let msg = &msg[..len];
+        for &io_size in &[1, 7, 16, 33] {
+            let ct = write_stream(msg, chunk_size, io_size);
+            let chunks = if len == 0 { 1 } else { (len + 15) / 16 };
+            assert_eq!(ct.len(), len + 16 * chunks);
+            let pt = read_stream(&ct, chunk_size, io_size).unwrap();
+            assert_eq!(pt, msg);
+        }
+    }
+}
+
+#[test]
+fn stream_io_errors() {
+    let msg: Vec<u8> = (0..100).collect();
+    let ct = write_stream(&msg, 16, 10);
+    // missing last chunk, part of it or its tag
+    for &n in &[4 + 16, 4 + 15, 1] {
+        let res = read_stream(&ct[..ct.len() - n], 16, 10);
+        assert_eq!(res.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+    // modified data
+    let mut bad = ct.clone();
+    bad[40] ^= 1;
+    let res = read_stream(&bad, 16, 10);
+    assert_eq!(res.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    // wrong chunk size
+    let res = read_stream(&ct, 17, 10);
+    assert_eq!(res.unwrap_err().kind(), io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,284 @@
+use core::ops::Add;
+use generic_array::{GenericArray, ArrayLength};
+use generic_array::typenum::{Sum, Unsigned};
+use {BlockDecrypt, BlockEncrypt, ParBlocks};
+
+type Block<A> = GenericArray<u8, <A as BlockEncrypt>::BlockSize>;
+
+/// Key size of the combination of ciphers `A` and `B`
+pub type CombinedKeySize<A, B> =
+    Sum<<A as BlockEncrypt>::KeySize, <B as BlockEncrypt>::KeySize>;
+/// Key size of the combination of ciphers `A`, `B` and `C`
+pub type CombinedKeySize3<A, B, C> =
+    Sum<CombinedKeySize<A, B>, <C as BlockEncrypt>::KeySize>;
+
+/// Cascade of three block ciphers: `E(C, E(B, E(A, P)))`.
+pub type Cascade3<A, B, C> = Cascade<Cascade<A, B>, C>;
+
+/// Cascade of two block ciphers with equal block sizes: `E(B, E(A, P))`.
+///
+/// Key is the concatenation of keys of `A` and `B`. `ParBlocks` of the
+/// combinator is equal to `A::ParBlocks`, the second cipher processes
+/// parallel blocks in groups of `B::ParBlocks`.
+pub struct Cascade<A, B>
+    where A: BlockEncrypt, B: BlockEncrypt<BlockSize = A::BlockSize>
+{
+    a: A,
+    b: B,
+}
+
+impl<A, B> Cascade<A, B>
+    where A: BlockEncrypt, B: BlockEncrypt<BlockSize = A::BlockSize>
+{
+    /// Create new cascade from initialized block ciphers.
+    pub fn from_ciphers(a: A, b: B) -> Self {
+        Self { a, b }
+    }
+}
+
+impl<A, B> BlockEncrypt for Cascade<A, B>
+    where A: BlockEncrypt, B: BlockEncrypt<BlockSize = A::BlockSize>,
+        A::KeySize: Add<B::KeySize>, CombinedKeySize<A, B>: ArrayLength<u8>,
+{
+    type KeySize = CombinedKeySize<A, B>;
+    type BlockSize = A::BlockSize;
+    type ParBlocks = A::ParBlocks;
+
+    fn new(key: &GenericArray<u8, CombinedKeySize<A, B>>) -> Self {
+        let (ka, kb) = key.split_at(A::KeySize::to_usize());
+        Self::from_ciphers(A::new(GenericArray::from_slice(ka)),
+            B::new(GenericArray::from_slice(kb)))
+    }
+
+    #[inline]
+    fn encrypt_block(&self, block: &mut Block<A>) {
+        self.a.encrypt_block(block);
+        self.b.encrypt_block(block);
+    }
+
+    #[inline]
+    fn encrypt_blocks(&self,
+        blocks: &mut ParBlocks<A::BlockSize, A::ParBlocks>)
+    {
+        self.a.encrypt_blocks(blocks);
+        self.b.encrypt_slice(blocks);
+    }
+
+    #[inline]
+    fn encrypt_slice(&self, blocks: &mut [Block<A>]) {
+        self.a.encrypt_slice(blocks);
+        self.b.encrypt_slice(blocks);
+    }
+}
+
+impl<A, B> BlockDecrypt for Cascade<A, B>
+    where A: BlockDecrypt, B: BlockDecrypt<BlockSize = A::BlockSize>,
+        A::KeySize: Add<B::KeySize>, CombinedKeySize<A, B>: ArrayLength<u8>,
+{
+    #[inline]
+    fn decrypt_block(&self, block: &mut Block<A>) {
+        self.b.decrypt_block(block);
+        self.a.decrypt_block(block);
+    }
+
+    #[inline]
+    fn decrypt_blocks(&self,
+        blocks: &mut ParBlocks<A::BlockSize, A::ParBlocks>)
+    {
+        self.b.decrypt_slice(blocks);
+        self.a.decrypt_blocks(blocks);
+    }
+
+    #[inline]
+    fn decrypt_slice(&self, blocks: &mut [Block<A>]) {
+        self.b.decrypt_slice(blocks);
+        self.a.decrypt_slice(blocks);
+    }
+}
+
+/// Two-key encrypt-decrypt-encrypt combination of block ciphers with equal
+/// block sizes: `E(A, D(B, E(A, P)))`.
+///
+/// Key is the concatenation of keys of `A` and `B`, i.e. with DES it's
+/// 2-key Triple DES (keying option 2). `ParBlocks` of the combinator is
+/// equal to `A::ParBlocks`.
+pub struct Ede2<A, B>
+    where A: BlockEncrypt, B: BlockEncrypt<BlockSize = A::BlockSize>
+{
+    a: A,
+    b: B,
+}
+
+impl<A, B> Ede2<A, B>
+    where A: BlockEncrypt, B: BlockEncrypt<BlockSize = A::BlockSize>
+{
+    /// Create new EDE instance from initialized block ciphers.
+    pub fn from_ciphers(a: A, b: B) -> Self {
+        Self { a, b }
+    }
+}
+
+impl<A, B> BlockEncrypt for Ede2<A, B>
+    where A: BlockEncrypt, B: BlockDecrypt<BlockSize = A::BlockSize>,
+        A::KeySize: Add<B::KeySize>, CombinedKeySize<A, B>: ArrayLength<u8>,
+{
+    type KeySize = CombinedKeySize<A, B>;
+    type BlockSize = A::BlockSize;
+    type ParBlocks = A::ParBlocks;
+
+    fn new(key: &GenericArray<u8, CombinedKeySize<A, B>>) -> Self {
+        let (ka, kb) = key.split_at(A::KeySize::to_usize());
+        Self::from_ciphers(A::new(GenericArray::from_slice(ka)),
+            B::new(GenericArray::from_slice(kb)))
+    }
+
+    #[inline]
+    fn encrypt_block(&self, block: &mut Block<A>) {
+        self.a.encrypt_block(block);
+        self.b.decrypt_block(block);
+        self.a.encrypt_block(block);
+    }
+
+    #[inline]
+    fn encrypt_blocks(&self,
+        blocks: &mut ParBlocks<A::BlockSize, A::ParBlocks>)
+    {
+        self.a.encrypt_blocks(blocks);
+        self.b.decrypt_slice(blocks);
+        self.a.encrypt_blocks(blocks);
+    }
+
+    #[inline]
+    fn encrypt_slice(&self, blocks: &mut [Block<A>]) {
+        self.a.encrypt_slice(blocks);
+        self.b.decrypt_slice(blocks);
+        self.a.encrypt_slice(blocks);
+    }
+}
+
+impl<A, B> BlockDecrypt for Ede2<A, B>
+    where A: BlockDecrypt, B: BlockDecrypt<BlockSize = A::BlockSize>,
+        A::KeySize: Add<B::KeySize>, CombinedKeySize<A, B>: ArrayLength<u8>,
+{
+    #[inline]
+    fn decrypt_block(&self, block: &mut Block<A>) {
+        self.a.decrypt_block(block);
+        self.b.encrypt_block(block);
+        self.a.decrypt_block(block);
+    }
+
+    #[inline]
+    fn decrypt_blocks(&self,
+        blocks: &mut ParBlocks<A::BlockSize, A::ParBlocks>)
+    {
+        self.a.decrypt_blocks(blocks);
+        self.b.encrypt_slice(blocks);
+        self.a.decrypt_blocks(blocks);
+    }
+
+    #[inline]
+    fn decrypt_slice(&self, blocks: &mut [Block<A>]) {
+        self.a.decrypt_slice(blocks);
+        self.b.encrypt_slice(blocks);
+        self.a.decrypt_slice(blocks);
+    }
+}
+
+/// Three-key encrypt-decrypt-encrypt combination of block ciphers with equal
+/// block sizes: `E(C, D(B, E(A, P)))`.
+///
+/// Key is the concatenation of keys of `A`, `B` and `C`, i.e. with DES it's
+/// 3-key Triple DES (keying option 1). `ParBlocks` of the combinator is
+/// equal to `A::ParBlocks`.
+pub struct Ede3<A, B, C>
+    where A: BlockEncrypt, B: BlockEncrypt<BlockSize = A::BlockSize>,
+        C: BlockEncrypt<BlockSize = A::BlockSize>,
+{
+    a: A,
+    b: B,
+    c: C,
+}
+
+impl<A, B, C> Ede3<A, B, C>
+    where A: BlockEncrypt, B: BlockEncrypt<BlockSize = A::BlockSize>,
+        C: BlockEncrypt<BlockSize = A::BlockSize>,
+{
+    /// Create new EDE instance from initialized block ciphers.
+    pub fn from_ciphers(a: A, b: B, c: C) -> Self {
+        Self { a, b, c }
+    }
+}
+
+impl<A, B, C> BlockEncrypt for Ede3<A, B, C>
+    where A: BlockEncrypt, B: BlockDecrypt<BlockSize = A::BlockSize>,
+        C: BlockEncrypt<BlockSize = A::BlockSize>,
+        A::KeySize: Add<B::KeySize>,
+        CombinedKeySize<A, B>: ArrayLength<u8> + Add<C::KeySize>,
+        CombinedKeySize3<A, B, C>: ArrayLength<u8>,
+{
+    type KeySize = CombinedKeySize3<A, B, C>;
+    type BlockSize = A::BlockSize;
+    type ParBlocks = A::ParBlocks;
+
+    fn new(key: &GenericArray<u8, CombinedKeySize3<A, B, C>>) -> Self {
+        let (ka, rest) = key.split_at(A::KeySize::to_usize());
+        let (kb, kc) = rest.split_at(B::KeySize::to_usize());
+        Self::from_ciphers(A::new(GenericArray::from_slice(ka)),
+            B::new(GenericArray::from_slice(kb)),
+            C::new(GenericArray::from_slice(kc)))
+    }
+
+    #[inline]
+    fn encrypt_block(&self, block: &mut Block<A>) {
+        self.a.encrypt_block(block);
+        self.b.decrypt_block(block);
+        self.c.encrypt_block(block);
+    }
+
+    #[inline]
+    fn encrypt_blocks(&self,
+        blocks: &mut ParBlocks<A::BlockSize, A::ParBlocks>)
+    {
+        self.a.encrypt_blocks(blocks);
+        self.b.decrypt_slice(blocks);
+        self.c.encrypt_slice(blocks);
+    }
+
+    #[inline]
+    fn encrypt_slice(&self, blocks: &mut [Block<A>]) {
+        self.a.encrypt_slice(blocks);
+        self.b.decrypt_slice(blocks);
+        self.c.encrypt_slice(blocks);
+    }
+}
+
+impl<A, B, C> BlockDecrypt for Ede3<A, B, C>
+    where A: BlockDecrypt, B: BlockDecrypt<BlockSize = A::BlockSize>,
+        C: BlockDecrypt<BlockSize = A::BlockSize>,
+        A::KeySize: Add<B::KeySize>,
+        CombinedKeySize<A, B>: ArrayLength<u8> + Add<C::KeySize>,
+        CombinedKeySize3<A, B, C>: ArrayLength<u8>,
+{
+    #[inline]
+    fn decrypt_block(&self, block: &mut Block<A>) {
+        self.c.decrypt_block(block);
+        self.b.encrypt_block(block);
+        self.a.decrypt_block(block);
+    }
+
+    #[inline]
+    fn decrypt_blocks(&self,
+        blocks: &mut ParBlocks<A::BlockSize, A::ParBlocks>)
+    {
+        self.c.decrypt_slice(blocks);
+        self.b.encrypt_slice(blocks);
+        self.a.decrypt_blocks(blocks);
+    }
+
+    #[inline]
+    fn decrypt_slice(&self, blocks: &mut [Block<A>]) {
+        self.c.decrypt_slice(blocks);
+        self.b.encrypt_slice(blocks);
+        self.a.decrypt_slice(blocks);
+    }
+}
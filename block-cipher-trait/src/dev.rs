@@ -4,11 +4,11 @@ macro_rules! new_test {
     ($name:ident, $test_name:expr, $cipher:ty) => {
         #[test]
         fn $name() {
-            use block_cipher_trait::BlockCipher;
+            use block_cipher_trait::{BlockDecrypt, BlockEncrypt};
             use block_cipher_trait::generic_array::GenericArray;
 
             fn run_test(key: &[u8], pt: &[u8], ct: &[u8]) -> bool {
-                let state = <$cipher as BlockCipher>::new_varkey(key).unwrap();
+                let state = <$cipher as BlockEncrypt>::new_varkey(key).unwrap();
 
                 let mut block = GenericArray::clone_from_slice(pt);
                 state.encrypt_block(&mut block);
@@ -64,13 +64,211 @@ macro_rules! new_test {
     }
 }
 
+/// Define test for tweakable block cipher which reads test vectors from
+/// `data/$test_name.{keys,tweaks,plaintexts,ciphertexts,index}.bin` files.
+///
+/// Index file contains `u16` little endian start and end offsets of key,
+/// tweak, plaintext and ciphertext for every test vector.
+#[macro_export]
+macro_rules! new_tweak_test {
+    ($name:ident, $test_name:expr, $cipher:ty) => {
+        #[test]
+        fn $name() {
+            use block_cipher_trait::TweakableBlockCipher;
+            use block_cipher_trait::generic_array::GenericArray;
+
+            fn run_test(key: &[u8], tweak: &[u8], pt: &[u8], ct: &[u8])
+                -> bool
+            {
+                let state = <$cipher as TweakableBlockCipher>
+                    ::new_varkey(key).unwrap();
+                let tweak = GenericArray::from_slice(tweak);
+
+                let mut block = GenericArray::clone_from_slice(pt);
+                state.encrypt_block_with_tweak(tweak, &mut block);
+                if ct != block.as_slice() {
+                    return false;
+                }
+
+                state.decrypt_block_with_tweak(tweak, &mut block);
+                if pt != block.as_slice() {
+                    return false;
+                }
+                true
+            }
+
+            let keys = include_bytes!(
+                concat!("data/", $test_name, ".keys.bin"));
+            let tweaks = include_bytes!(
+                concat!("data/", $test_name, ".tweaks.bin"));
+            let plaintexts = include_bytes!(
+                concat!("data/", $test_name, ".plaintexts.bin"));
+            let ciphertexts = include_bytes!(
+                concat!("data/", $test_name, ".ciphertexts.bin"));
+            let index = include_bytes!(
+                concat!("data/", $test_name, ".index.bin"));
+            // u16 (2 bytes); start + end (x2);
+            // key, tweak, plaintext, ciphertext (x4)
+            assert_eq!(index.len() % (2*2*4), 0, "invalid index length");
+            for (i, chunk) in index.chunks(2*2*4).enumerate() {
+                let mut idx = [[0usize; 2]; 4];
+                for (j, val) in idx.iter_mut()
+                    .flat_map(|v| v.iter_mut()).enumerate()
+                {
+                    let b = [chunk[2*j], chunk[2*j + 1]];
+                    *val = u16::from_le_bytes(b) as usize;
+                }
+                let key = &keys[idx[0][0]..idx[0][1]];
+                let tweak = &tweaks[idx[1][0]..idx[1][1]];
+                let plaintext = &plaintexts[idx[2][0]..idx[2][1]];
+                let ciphertext = &ciphertexts[idx[3][0]..idx[3][1]];
+                if !run_test(key, tweak, plaintext, ciphertext) {
+                    panic!("\n\
+                        Failed tweak test №{}\n\
+                        key: [{}..{}]\t{:?}\n\
+                        tweak: [{}..{}]\t{:?}\n\
+                        plaintext: [{}..{}]\t{:?}\n\
+                        ciphertext: [{}..{}]\t{:?}\n",
+                        i, idx[0][0], idx[0][1], key,
+                        idx[1][0], idx[1][1], tweak,
+                        idx[2][0], idx[2][1], plaintext,
+                        idx[3][0], idx[3][1], ciphertext,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Define test which checks that `new_varkey` accepts exactly the key lengths
+/// declared by `key_sizes`. Lengths are checked up to 1024 bytes or up to the
+/// maximal declared length plus one, whichever is smaller.
+#[macro_export]
+macro_rules! key_sizes_test {
+    ($name:ident, $cipher:ty) => {
+        #[test]
+        fn $name() {
+            use block_cipher_trait::BlockEncrypt;
+
+            let sizes = <$cipher as BlockEncrypt>::key_sizes();
+            let mut key = [0u8; 1024];
+            for (i, b) in key.iter_mut().enumerate() { *b = i as u8; }
+            let max = if sizes.max() < key.len() {
+                sizes.max() + 1
+            } else {
+                key.len()
+            };
+            for len in 0..max + 1 {
+                let accepted = <$cipher as BlockEncrypt>
+                    ::new_varkey(&key[..len]).is_ok();
+                if accepted != sizes.contains(len) {
+                    panic!("\n\
+                        Failed key sizes test for key length {}\n\
+                        declared: {:?}\naccepted: {}\n",
+                        len, sizes, accepted,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Define test which checks that both branches of `Dispatch` over preferred
+/// implementation `$preferred` and fallback implementation `$fallback` give
+/// identical results for single blocks, parallel blocks and slices of
+/// different lengths.
+#[macro_export]
+macro_rules! dispatch_test {
+    ($name:ident, $preferred:ty, $fallback:ty) => {
+        #[test]
+        fn $name() {
+            use block_cipher_trait::{
+                BlockDecrypt, BlockEncrypt, Detect, Dispatch,
+            };
+            use block_cipher_trait::generic_array::GenericArray;
+            use block_cipher_trait::generic_array::typenum::Unsigned;
+
+            struct Never;
+
+            impl Detect for Never {
+                fn detect() -> bool { false }
+            }
+
+            type D = Dispatch<$preferred, $fallback, Never>;
+            type Block = GenericArray<u8, <D as BlockEncrypt>::BlockSize>;
+            type ParBlocks =
+                GenericArray<Block, <D as BlockEncrypt>::ParBlocks>;
+
+            let mut key = GenericArray::default();
+            for (i, b) in key.iter_mut().enumerate() { *b = i as u8; }
+            let preferred = D::from_preferred(<$preferred>::new(&key));
+            let fallback = D::from_fallback(<$fallback>::new(&key));
+            assert!(preferred.is_preferred() && !fallback.is_preferred());
+            assert!(!D::new(&key).is_preferred());
+
+            let mut blocks = ParBlocks::default();
+            for (i, b) in blocks.iter_mut().flat_map(|b| b.iter_mut())
+                .enumerate()
+            {
+                *b = (i * 7) as u8;
+            }
+            let (mut a, mut b) = (blocks.clone(), blocks.clone());
+            preferred.encrypt_blocks(&mut a);
+            fallback.encrypt_blocks(&mut b);
+            if a != b {
+                panic!("\nFailed dispatch parallel blocks test\n");
+            }
+            preferred.decrypt_blocks(&mut a);
+            fallback.decrypt_blocks(&mut b);
+            if a != blocks || b != blocks {
+                panic!("\nFailed dispatch parallel blocks test\n");
+            }
+
+            let (mut c, mut d) = (ParBlocks::default(), ParBlocks::default());
+            preferred.encrypt_blocks_b2b(&blocks, &mut c);
+            fallback.encrypt_blocks_b2b(&blocks, &mut d);
+            preferred.encrypt_blocks(&mut a);
+            if c != a || d != a {
+                panic!("\nFailed dispatch parallel blocks b2b test\n");
+            }
+            preferred.decrypt_blocks_b2b(&c, &mut a);
+            fallback.decrypt_blocks_b2b(&d, &mut b);
+            if a != blocks || b != blocks {
+                panic!("\nFailed dispatch parallel blocks b2b test\n");
+            }
+
+            let bs = <$preferred as BlockEncrypt>::BlockSize::to_usize();
+            let pb = <$preferred as BlockEncrypt>::ParBlocks::to_usize();
+            let mut buf = [0u8; 4096];
+            for (i, b) in buf.iter_mut().enumerate() { *b = (i * 3) as u8; }
+            for n in 0..3 * pb + 2 {
+                if n * bs > buf.len() { break; }
+                let (mut a, mut b) = (buf, buf);
+                let (a, b) = (&mut a[..n * bs], &mut b[..n * bs]);
+                preferred.encrypt_bytes(a).unwrap();
+                fallback.encrypt_bytes(b).unwrap();
+                if a != b {
+                    panic!("\nFailed dispatch encryption test for {} blocks\n",
+                        n);
+                }
+                preferred.decrypt_bytes(a).unwrap();
+                fallback.decrypt_bytes(b).unwrap();
+                if a != &buf[..n * bs] || b != &buf[..n * bs] {
+                    panic!("\nFailed dispatch decryption test for {} blocks\n",
+                        n);
+                }
+            }
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! bench {
     ($cipher:path, $key_len:expr) => {
         extern crate test;
 
         use test::Bencher;
-        use block_cipher_trait::BlockCipher;
+        use block_cipher_trait::{BlockDecrypt, BlockEncrypt};
 
         #[bench]
         pub fn encrypt(bh: &mut Bencher) {
@@ -97,3 +295,82 @@ macro_rules! bench {
         }
     }
 }
+
+/// Define type `$name` which implements `BlockEncrypt` and `BlockDecrypt`
+/// for block cipher `$krate::$cipher` implemented using traits of the
+/// `cipher` crate, e.g. `impl_cipher!(Aes128, aes::Aes128, U16, U16)`.
+/// Cipher crate `$krate` must re-export the `cipher` crate and must be
+/// declared in the crate root. `ParBlocks` is equal to 8 by default.
+///
+/// Helper for tests of crates which are built on top of block ciphers.
+#[macro_export]
+macro_rules! impl_cipher {
+    ($name:ident, $krate:ident::$cipher:ident, $key_size:ty,
+        $block_size:ty) =>
+    {
+        impl_cipher!($name, $krate::$cipher, $key_size, $block_size,
+            $crate::generic_array::typenum::U8);
+    };
+    ($name:ident, $krate:ident::$cipher:ident, $key_size:ty,
+        $block_size:ty, $par_blocks:ty) =>
+    {
+        #[derive(Clone)]
+        struct $name(::$krate::$cipher);
+
+        impl $crate::BlockEncrypt for $name {
+            type KeySize = $key_size;
+            type BlockSize = $block_size;
+            type ParBlocks = $par_blocks;
+
+            fn new(key: &$crate::generic_array::GenericArray<u8, $key_size>)
+                -> Self
+            {
+                use ::$krate::cipher::KeyInit;
+                $name(::$krate::$cipher::new_from_slice(key).unwrap())
+            }
+
+            fn encrypt_block(&self,
+                block: &mut $crate::generic_array::GenericArray<u8,
+                    $block_size>)
+            {
+                use ::$krate::cipher::BlockEncrypt;
+                self.0.encrypt_block(block.as_mut_slice().into());
+            }
+        }
+
+        impl $crate::BlockDecrypt for $name {
+            fn decrypt_block(&self,
+                block: &mut $crate::generic_array::GenericArray<u8,
+                    $block_size>)
+            {
+                use ::$krate::cipher::BlockDecrypt;
+                self.0.decrypt_block(block.as_mut_slice().into());
+            }
+        }
+    }
+}
+
+/// Decode hex string into the buffer and return decoded part.
+///
+/// Helper for test vectors of crates which are built on top of block ciphers.
+///
+/// # Panics
+/// If the string is not a valid hex string or if the buffer is too small.
+pub fn decode_hex<'a>(hex: &str, buf: &'a mut [u8]) -> &'a [u8] {
+    let hex = hex.as_bytes();
+    assert_eq!(hex.len() % 2, 0, "invalid hex length");
+    assert!(hex.len() / 2 <= buf.len(), "buffer is too small");
+    for (b, pair) in buf.iter_mut().zip(hex.chunks(2)) {
+        *b = (nibble(pair[0]) << 4) | nibble(pair[1]);
+    }
+    &buf[..hex.len() / 2]
+}
+
+fn nibble(c: u8) -> u8 {
+    match c {
+        b'0'..=b'9' => c - b'0',
+        b'a'..=b'f' => c - b'a' + 10,
+        b'A'..=b'F' => c - b'A' + 10,
+        _ => panic!("invalid hex character"),
+    }
+}
@@ -0,0 +1,196 @@
+use core::marker::PhantomData;
+use generic_array::GenericArray;
+use {BlockDecrypt, BlockEncrypt, InvalidKeyLength, KeySizes, ParBlocks};
+
+type Block<A> = GenericArray<u8, <A as BlockEncrypt>::BlockSize>;
+
+/// Predicate which detects at runtime if the preferred implementation is
+/// supported, e.g. by checking CPU features.
+pub trait Detect {
+    /// Returns `true` if the preferred implementation can be used.
+    fn detect() -> bool;
+}
+
+enum Inner<A, B> {
+    Preferred(A),
+    Fallback(B),
+}
+
+/// Block cipher which uses preferred implementation `A` if predicate `P`
+/// returns `true` during initialization and fallback implementation `B`
+/// otherwise.
+///
+/// `ParBlocks` of the combinator is equal to `A::ParBlocks`, if fallback
+/// implementation is used, parallel blocks are processed in groups of
+/// `B::ParBlocks`. Both implementations must accept the same key lengths,
+/// `key_sizes` returns the ones declared by `A`.
+pub struct Dispatch<A, B, P>
+    where A: BlockEncrypt,
+        B: BlockEncrypt<KeySize = A::KeySize, BlockSize = A::BlockSize>,
+        P: Detect,
+{
+    inner: Inner<A, B>,
+    _predicate: PhantomData<P>,
+}
+
+impl<A, B, P> Dispatch<A, B, P>
+    where A: BlockEncrypt,
+        B: BlockEncrypt<KeySize = A::KeySize, BlockSize = A::BlockSize>,
+        P: Detect,
+{
+    /// Create new instance which uses initialized preferred implementation
+    /// regardless of the predicate result.
+    pub fn from_preferred(cipher: A) -> Self {
+        Self { inner: Inner::Preferred(cipher), _predicate: PhantomData }
+    }
+
+    /// Create new instance which uses initialized fallback implementation
+    /// regardless of the predicate result.
+    pub fn from_fallback(cipher: B) -> Self {
+        Self { inner: Inner::Fallback(cipher), _predicate: PhantomData }
+    }
+
+    /// Returns `true` if preferred implementation is used.
+    pub fn is_preferred(&self) -> bool {
+        match self.inner {
+            Inner::Preferred(_) => true,
+            Inner::Fallback(_) => false,
+        }
+    }
+}
+
+impl<A, B, P> BlockEncrypt for Dispatch<A, B, P>
+    where A: BlockEncrypt,
+        B: BlockEncrypt<KeySize = A::KeySize, BlockSize = A::BlockSize>,
+        P: Detect,
+{
+    type KeySize = A::KeySize;
+    type BlockSize = A::BlockSize;
+    type ParBlocks = A::ParBlocks;
+
+    fn new(key: &GenericArray<u8, A::KeySize>) -> Self {
+        if P::detect() {
+            Self::from_preferred(A::new(key))
+        } else {
+            Self::from_fallback(B::new(key))
+        }
+    }
+
+    fn new_varkey(key: &[u8]) -> Result<Self, InvalidKeyLength> {
+        if P::detect() {
+            A::new_varkey(key).map(Self::from_preferred)
+        } else {
+            B::new_varkey(key).map(Self::from_fallback)
+        }
+    }
+
+    fn key_sizes() -> KeySizes {
+        A::key_sizes()
+    }
+
+    #[inline]
+    fn encrypt_block(&self, block: &mut Block<A>) {
+        match self.inner {
+            Inner::Preferred(ref c) => c.encrypt_block(block),
+            Inner::Fallback(ref c) => c.encrypt_block(block),
+        }
+    }
+
+    #[inline]
+    fn encrypt_blocks(&self,
+        blocks: &mut ParBlocks<A::BlockSize, A::ParBlocks>)
+    {
+        match self.inner {
+            Inner::Preferred(ref c) => c.encrypt_blocks(blocks),
+            Inner::Fallback(ref c) => c.encrypt_slice(blocks),
+        }
+    }
+
+    #[inline]
+    fn encrypt_block_b2b(&self, in_block: &Block<A>, out_block: &mut Block<A>) {
+        match self.inner {
+            Inner::Preferred(ref c) => c.encrypt_block_b2b(in_block, out_block),
+            Inner::Fallback(ref c) => c.encrypt_block_b2b(in_block, out_block),
+        }
+    }
+
+    #[inline]
+    fn encrypt_blocks_b2b(&self,
+        in_blocks: &ParBlocks<A::BlockSize, A::ParBlocks>,
+        out_blocks: &mut ParBlocks<A::BlockSize, A::ParBlocks>)
+    {
+        match self.inner {
+            Inner::Preferred(ref c) => {
+                c.encrypt_blocks_b2b(in_blocks, out_blocks)
+            },
+            Inner::Fallback(ref c) => {
+                out_blocks.clone_from_slice(in_blocks);
+                c.encrypt_slice(out_blocks);
+            },
+        }
+    }
+
+    #[inline]
+    fn encrypt_slice(&self, blocks: &mut [Block<A>]) {
+        match self.inner {
+            Inner::Preferred(ref c) => c.encrypt_slice(blocks),
+            Inner::Fallback(ref c) => c.encrypt_slice(blocks),
+        }
+    }
+}
+
+impl<A, B, P> BlockDecrypt for Dispatch<A, B, P>
+    where A: BlockDecrypt,
+        B: BlockDecrypt<KeySize = A::KeySize, BlockSize = A::BlockSize>,
+        P: Detect,
+{
+    #[inline]
+    fn decrypt_block(&self, block: &mut Block<A>) {
+        match self.inner {
+            Inner::Preferred(ref c) => c.decrypt_block(block),
+            Inner::Fallback(ref c) => c.decrypt_block(block),
+        }
+    }
+
+    #[inline]
+    fn decrypt_blocks(&self,
+        blocks: &mut ParBlocks<A::BlockSize, A::ParBlocks>)
+    {
+        match self.inner {
+            Inner::Preferred(ref c) => c.decrypt_blocks(blocks),
+            Inner::Fallback(ref c) => c.decrypt_slice(blocks),
+        }
+    }
+
+    #[inline]
+    fn decrypt_block_b2b(&self, in_block: &Block<A>, out_block: &mut Block<A>) {
+        match self.inner {
+            Inner::Preferred(ref c) => c.decrypt_block_b2b(in_block, out_block),
+            Inner::Fallback(ref c) => c.decrypt_block_b2b(in_block, out_block),
+        }
+    }
+
+    #[inline]
+    fn decrypt_blocks_b2b(&self,
+        in_blocks: &ParBlocks<A::BlockSize, A::ParBlocks>,
+        out_blocks: &mut ParBlocks<A::BlockSize, A::ParBlocks>)
+    {
+        match self.inner {
+            Inner::Preferred(ref c) => {
+                c.decrypt_blocks_b2b(in_blocks, out_blocks)
+            },
+            Inner::Fallback(ref c) => {
+                out_blocks.clone_from_slice(in_blocks);
+                c.decrypt_slice(out_blocks);
+            },
+        }
+    }
+
+    #[inline]
+    fn decrypt_slice(&self, blocks: &mut [Block<A>]) {
+        match self.inner {
+            Inner::Preferred(ref c) => c.decrypt_slice(blocks),
+            Inner::Fallback(ref c) => c.decrypt_slice(blocks),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use generic_array::typenum::Unsigned;
+use {BlockCipher, InvalidLength, KeySizes};
+#[cfg(feature = "alloc")]
+use InvalidKeyLength;
+
+/// Object-safe block cipher interface, which allows to select algorithm at
+/// runtime.
+///
+/// This trait is implemented automatically for every type which implements
+/// `BlockCipher`. Instances can be created from a key slice using
+/// `new_boxed`, e.g. `new_boxed::<Aes128>(&key)`.
+pub trait DynBlockCipher {
+    /// Size of the block in bytes
+    fn block_size(&self) -> usize;
+
+    /// Key lengths in bytes accepted by the cipher, i.e. `key_sizes` of the
+    /// underlying `BlockEncrypt` implementation
+    fn key_sizes(&self) -> KeySizes;
+
+    /// Encrypt data in-place.
+    ///
+    /// Returns `Err(InvalidLength)` without processing data if length of
+    /// the buffer is not a multiple of block size.
+    fn encrypt(&self, data: &mut [u8]) -> Result<(), InvalidLength>;
+
+    /// Decrypt data in-place.
+    ///
+    /// Returns `Err(InvalidLength)` without processing data if length of
+    /// the buffer is not a multiple of block size.
+    fn decrypt(&self, data: &mut [u8]) -> Result<(), InvalidLength>;
+}
+
+impl<C: BlockCipher> DynBlockCipher for C {
+    fn block_size(&self) -> usize {
+        C::BlockSize::to_usize()
+    }
+
+    fn key_sizes(&self) -> KeySizes {
+        C::key_sizes()
+    }
+
+    fn encrypt(&self, data: &mut [u8]) -> Result<(), InvalidLength> {
+        self.encrypt_bytes(data)
+    }
+
+    fn decrypt(&self, data: &mut [u8]) -> Result<(), InvalidLength> {
+        self.decrypt_bytes(data)
+    }
+}
+
+/// Create boxed block cipher instance from key with variable size.
+///
+/// Constructors for different ciphers have the same type, so they can be
+/// stored in a table and selected at runtime:
+///
+/// ```rust,ignore
+/// let ciphers: &[(&str, DynConstructor)] = &[
+///     ("aes128", new_boxed::<Aes128>),
+///     ("sm4", new_boxed::<Sm4>),
+/// ];
+/// ```
+#[cfg(feature = "alloc")]
+pub fn new_boxed<C: BlockCipher + 'static>(key: &[u8])
+    -> Result<Box<dyn DynBlockCipher>, InvalidKeyLength>
+{
+    let cipher = C::new_varkey(key)?;
+    Ok(Box::new(cipher))
+}
+
+/// Type of the `new_boxed` function
+#[cfg(feature = "alloc")]
+pub type DynConstructor =
+    fn(&[u8]) -> Result<Box<dyn DynBlockCipher>, InvalidKeyLength>;
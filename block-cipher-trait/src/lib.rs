@@ -1,13 +1,34 @@
 //! This crate defines a set of simple traits used to define functionality of
 //! block ciphers.
+//!
+//! Constructor of boxed `DynBlockCipher` instances is available with enabled
+//! `alloc` feature.
 #![no_std]
 pub extern crate generic_array;
+extern crate key_sizes;
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
 use generic_array::{GenericArray, ArrayLength};
 use generic_array::typenum::Unsigned;
+use core::slice;
 
 #[cfg(feature = "dev")]
 pub mod dev;
+mod cascade;
+mod dispatch;
+mod dyn_cipher;
+mod network;
+
+pub use cascade::{
+    Cascade, Cascade3, CombinedKeySize, CombinedKeySize3, Ede2, Ede3,
+};
+pub use dispatch::{Detect, Dispatch};
+pub use dyn_cipher::DynBlockCipher;
+pub use key_sizes::KeySizes;
+pub use network::{Feistel, KeySchedule, LaiMassey, RoundFunction};
+#[cfg(feature = "alloc")]
+pub use dyn_cipher::{DynConstructor, new_boxed};
 
 type ParBlocks<B, P> = GenericArray<GenericArray<u8, B>, P>;
 
@@ -15,9 +36,18 @@ type ParBlocks<B, P> = GenericArray<GenericArray<u8, B>, P>;
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct InvalidKeyLength;
 
-/// The trait which defines in-place encryption and decryption
-/// over single block or several blocks in parallel.
-pub trait BlockCipher: core::marker::Sized {
+/// Error struct which signals that buffer length is not a multiple of block
+/// size
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct InvalidLength;
+
+/// The trait which defines in-place encryption over single block or several
+/// blocks in parallel.
+///
+/// Ciphers which are used only in the forward direction (e.g. in CTR, CFB
+/// or OFB modes) can implement only this trait and skip computation of the
+/// decryption key schedule.
+pub trait BlockEncrypt: core::marker::Sized {
     /// Key size in bytes with which cipher guaranteed to be initialized
     type KeySize: ArrayLength<u8>;
     /// Size of the block in bytes
@@ -32,7 +62,8 @@ pub trait BlockCipher: core::marker::Sized {
     /// Create new block cipher instance from key with variable size.
     ///
     /// Default implementation will accept only keys with length equal to
-    /// `KeySize`, but some ciphers can accept range of key lengths.
+    /// `KeySize`, but some ciphers can accept range of key lengths,
+    /// in which case `key_sizes` must be overridden as well.
     fn new_varkey(key: &[u8]) -> Result<Self, InvalidKeyLength> {
         if key.len() != Self::KeySize::to_usize() {
             Err(InvalidKeyLength)
@@ -41,31 +72,496 @@ pub trait BlockCipher: core::marker::Sized {
         }
     }
 
+    /// Key lengths accepted by `new_varkey`.
+    ///
+    /// Default implementation returns `KeySize` as the only length.
+    fn key_sizes() -> KeySizes {
+        KeySizes::fixed(Self::KeySize::to_usize())
+    }
+
     /// Encrypt block in-place
     fn encrypt_block(&self, block: &mut GenericArray<u8, Self::BlockSize>);
 
+    /// Encrypt several blocks in parallel using instruction level parallelism
+    /// if possible.
+    ///
+    /// If `ParBlocks` equals to 1 it's equivalent to `encrypt_block`.
+    #[inline]
+    fn encrypt_blocks(&self,
+        blocks: &mut ParBlocks<Self::BlockSize, Self::ParBlocks>)
+    {
+        for block in blocks.iter_mut() { self.encrypt_block(block); }
+    }
+
+    /// Encrypt block from `in_block` and write result into `out_block`.
+    ///
+    /// Default implementation copies input into output and calls
+    /// `encrypt_block`, ciphers can override it to avoid the copy.
+    #[inline]
+    fn encrypt_block_b2b(&self,
+        in_block: &GenericArray<u8, Self::BlockSize>,
+        out_block: &mut GenericArray<u8, Self::BlockSize>)
+    {
+        out_block.clone_from_slice(in_block);
+        self.encrypt_block(out_block);
+    }
+
+    /// Encrypt several blocks in parallel from `in_blocks` and write result
+    /// into `out_blocks`.
+    ///
+    /// Default implementation copies input into output and calls
+    /// `encrypt_blocks`.
+    #[inline]
+    fn encrypt_blocks_b2b(&self,
+        in_blocks: &ParBlocks<Self::BlockSize, Self::ParBlocks>,
+        out_blocks: &mut ParBlocks<Self::BlockSize, Self::ParBlocks>)
+    {
+        out_blocks.clone_from_slice(in_blocks);
+        self.encrypt_blocks(out_blocks);
+    }
+
+    /// Encrypt slice of blocks in-place.
+    ///
+    /// Blocks are processed in groups of `ParBlocks` using `encrypt_blocks`,
+    /// the remaining blocks are processed using `encrypt_block`.
+    #[inline]
+    fn encrypt_slice(&self,
+        blocks: &mut [GenericArray<u8, Self::BlockSize>])
+    {
+        let n = Self::ParBlocks::to_usize();
+        for chunk in blocks.chunks_mut(n) {
+            if chunk.len() == n {
+                self.encrypt_blocks(GenericArray::from_mut_slice(chunk));
+            } else {
+                for block in chunk { self.encrypt_block(block); }
+            }
+        }
+    }
+
+    /// Encrypt data in-place using `encrypt_slice`.
+    ///
+    /// Returns `Err(InvalidLength)` without processing data if length of
+    /// the buffer is not a multiple of block size.
+    #[inline]
+    fn encrypt_bytes(&self, data: &mut [u8]) -> Result<(), InvalidLength> {
+        self.encrypt_slice(to_blocks(data)?);
+        Ok(())
+    }
+}
+
+/// The trait which defines in-place decryption over single block or several
+/// blocks in parallel.
+pub trait BlockDecrypt: BlockEncrypt {
     /// Decrypt block in-place
     fn decrypt_block(&self, block: &mut GenericArray<u8, Self::BlockSize>);
 
+    /// Decrypt several blocks in parallel using instruction level parallelism
+    /// if possible.
+    ///
+    /// If `ParBlocks` equals to 1 it's equivalent to `decrypt_block`.
+    #[inline]
+    fn decrypt_blocks(&self,
+        blocks: &mut ParBlocks<Self::BlockSize, Self::ParBlocks>)
+    {
+        for block in blocks.iter_mut() { self.decrypt_block(block); }
+    }
+
+    /// Decrypt block from `in_block` and write result into `out_block`.
+    ///
+    /// Default implementation copies input into output and calls
+    /// `decrypt_block`, ciphers can override it to avoid the copy.
+    #[inline]
+    fn decrypt_block_b2b(&self,
+        in_block: &GenericArray<u8, Self::BlockSize>,
+        out_block: &mut GenericArray<u8, Self::BlockSize>)
+    {
+        out_block.clone_from_slice(in_block);
+        self.decrypt_block(out_block);
+    }
+
+    /// Decrypt several blocks in parallel from `in_blocks` and write result
+    /// into `out_blocks`.
+    ///
+    /// Default implementation copies input into output and calls
+    /// `decrypt_blocks`.
+    #[inline]
+    fn decrypt_blocks_b2b(&self,
+        in_blocks: &ParBlocks<Self::BlockSize, Self::ParBlocks>,
+        out_blocks: &mut ParBlocks<Self::BlockSize, Self::ParBlocks>)
+    {
+        out_blocks.clone_from_slice(in_blocks);
+        self.decrypt_blocks(out_blocks);
+    }
+
+    /// Decrypt slice of blocks in-place.
+    ///
+    /// Blocks are processed in groups of `ParBlocks` using `decrypt_blocks`,
+    /// the remaining blocks are processed using `decrypt_block`.
+    #[inline]
+    fn decrypt_slice(&self,
+        blocks: &mut [GenericArray<u8, Self::BlockSize>])
+    {
+        let n = Self::ParBlocks::to_usize();
+        for chunk in blocks.chunks_mut(n) {
+            if chunk.len() == n {
+                self.decrypt_blocks(GenericArray::from_mut_slice(chunk));
+            } else {
+                for block in chunk { self.decrypt_block(block); }
+            }
+        }
+    }
+
+    /// Decrypt data in-place using `decrypt_slice`.
+    ///
+    /// Returns `Err(InvalidLength)` without processing data if length of
+    /// the buffer is not a multiple of block size.
+    #[inline]
+    fn decrypt_bytes(&self, data: &mut [u8]) -> Result<(), InvalidLength> {
+        self.decrypt_slice(to_blocks(data)?);
+        Ok(())
+    }
+}
+
+/// Block cipher which supports both encryption and decryption.
+///
+/// This trait is implemented automatically for every type which implements
+/// `BlockEncrypt` and `BlockDecrypt`.
+pub trait BlockCipher: BlockEncrypt + BlockDecrypt {}
+
+impl<T: BlockEncrypt + BlockDecrypt> BlockCipher for T {}
+
+/// The trait which defines in-place encryption for ciphers which update
+/// their internal state on every call, e.g. ciphers backed by hardware
+/// tokens or ciphers with scratch buffers.
+///
+/// This trait is implemented automatically for every type which implements
+/// `BlockEncrypt`.
+pub trait BlockEncryptMut {
+    /// Size of the block in bytes
+    type BlockSize: ArrayLength<u8>;
+    /// Number of blocks which can be processed in parallel by
+    /// cipher implementation
+    type ParBlocks: ArrayLength<GenericArray<u8, Self::BlockSize>>;
+
+    /// Encrypt block in-place
+    fn encrypt_block(&mut self,
+        block: &mut GenericArray<u8, Self::BlockSize>);
+
     /// Encrypt several blocks in parallel using instruction level parallelism
     /// if possible.
     ///
     /// If `ParBlocks` equals to 1 it's equivalent to `encrypt_block`.
     #[inline]
-    fn encrypt_blocks(&self,
+    fn encrypt_blocks(&mut self,
         blocks: &mut ParBlocks<Self::BlockSize, Self::ParBlocks>)
     {
         for block in blocks.iter_mut() { self.encrypt_block(block); }
     }
 
+    /// Encrypt block from `in_block` and write result into `out_block`.
+    ///
+    /// Default implementation copies input into output and calls
+    /// `encrypt_block`, ciphers can override it to avoid the copy.
+    #[inline]
+    fn encrypt_block_b2b(&mut self,
+        in_block: &GenericArray<u8, Self::BlockSize>,
+        out_block: &mut GenericArray<u8, Self::BlockSize>)
+    {
+        out_block.clone_from_slice(in_block);
+        self.encrypt_block(out_block);
+    }
+
+    /// Encrypt several blocks in parallel from `in_blocks` and write result
+    /// into `out_blocks`.
+    ///
+    /// Default implementation copies input into output and calls
+    /// `encrypt_blocks`.
+    #[inline]
+    fn encrypt_blocks_b2b(&mut self,
+        in_blocks: &ParBlocks<Self::BlockSize, Self::ParBlocks>,
+        out_blocks: &mut ParBlocks<Self::BlockSize, Self::ParBlocks>)
+    {
+        out_blocks.clone_from_slice(in_blocks);
+        self.encrypt_blocks(out_blocks);
+    }
+
+    /// Encrypt slice of blocks in-place.
+    ///
+    /// Blocks are processed in groups of `ParBlocks` using `encrypt_blocks`,
+    /// the remaining blocks are processed using `encrypt_block`.
+    #[inline]
+    fn encrypt_slice(&mut self,
+        blocks: &mut [GenericArray<u8, Self::BlockSize>])
+    {
+        let n = Self::ParBlocks::to_usize();
+        for chunk in blocks.chunks_mut(n) {
+            if chunk.len() == n {
+                self.encrypt_blocks(GenericArray::from_mut_slice(chunk));
+            } else {
+                for block in chunk { self.encrypt_block(block); }
+            }
+        }
+    }
+
+    /// Encrypt data in-place using `encrypt_slice`.
+    ///
+    /// Returns `Err(InvalidLength)` without processing data if length of
+    /// the buffer is not a multiple of block size.
+    #[inline]
+    fn encrypt_bytes(&mut self, data: &mut [u8]) -> Result<(), InvalidLength> {
+        self.encrypt_slice(to_blocks(data)?);
+        Ok(())
+    }
+}
+
+/// The trait which defines in-place decryption for ciphers which update
+/// their internal state on every call.
+///
+/// This trait is implemented automatically for every type which implements
+/// `BlockDecrypt`.
+pub trait BlockDecryptMut: BlockEncryptMut {
+    /// Decrypt block in-place
+    fn decrypt_block(&mut self,
+        block: &mut GenericArray<u8, Self::BlockSize>);
+
     /// Decrypt several blocks in parallel using instruction level parallelism
     /// if possible.
     ///
     /// If `ParBlocks` equals to 1 it's equivalent to `decrypt_block`.
     #[inline]
-    fn decrypt_blocks(&self,
+    fn decrypt_blocks(&mut self,
         blocks: &mut ParBlocks<Self::BlockSize, Self::ParBlocks>)
     {
         for block in blocks.iter_mut() { self.decrypt_block(block); }
     }
+
+    /// Decrypt block from `in_block` and write result into `out_block`.
+    ///
+    /// Default implementation copies input into output and calls
+    /// `decrypt_block`, ciphers can override it to avoid the copy.
+    #[inline]
+    fn decrypt_block_b2b(&mut self,
+        in_block: &GenericArray<u8, Self::BlockSize>,
+        out_block: &mut GenericArray<u8, Self::BlockSize>)
+    {
+        out_block.clone_from_slice(in_block);
+        self.decrypt_block(out_block);
+    }
+
+    /// Decrypt several blocks in parallel from `in_blocks` and write result
+    /// into `out_blocks`.
+    ///
+    /// Default implementation copies input into output and calls
+    /// `decrypt_blocks`.
+    #[inline]
+    fn decrypt_blocks_b2b(&mut self,
+        in_blocks: &ParBlocks<Self::BlockSize, Self::ParBlocks>,
+        out_blocks: &mut ParBlocks<Self::BlockSize, Self::ParBlocks>)
+    {
+        out_blocks.clone_from_slice(in_blocks);
+        self.decrypt_blocks(out_blocks);
+    }
+
+    /// Decrypt slice of blocks in-place.
+    ///
+    /// Blocks are processed in groups of `ParBlocks` using `decrypt_blocks`,
+    /// the remaining blocks are processed using `decrypt_block`.
+    #[inline]
+    fn decrypt_slice(&mut self,
+        blocks: &mut [GenericArray<u8, Self::BlockSize>])
+    {
+        let n = Self::ParBlocks::to_usize();
+        for chunk in blocks.chunks_mut(n) {
+            if chunk.len() == n {
+                self.decrypt_blocks(GenericArray::from_mut_slice(chunk));
+            } else {
+                for block in chunk { self.decrypt_block(block); }
+            }
+        }
+    }
+
+    /// Decrypt data in-place using `decrypt_slice`.
+    ///
+    /// Returns `Err(InvalidLength)` without processing data if length of
+    /// the buffer is not a multiple of block size.
+    #[inline]
+    fn decrypt_bytes(&mut self, data: &mut [u8]) -> Result<(), InvalidLength> {
+        self.decrypt_slice(to_blocks(data)?);
+        Ok(())
+    }
+}
+
+/// Block cipher with mutable state which supports both encryption and
+/// decryption.
+///
+/// This trait is implemented automatically for every type which implements
+/// `BlockEncryptMut` and `BlockDecryptMut`, including every `BlockCipher`.
+pub trait BlockCipherMut: BlockEncryptMut + BlockDecryptMut {}
+
+impl<T: BlockEncryptMut + BlockDecryptMut> BlockCipherMut for T {}
+
+impl<T: BlockEncrypt> BlockEncryptMut for T {
+    type BlockSize = T::BlockSize;
+    type ParBlocks = T::ParBlocks;
+
+    #[inline]
+    fn encrypt_block(&mut self,
+        block: &mut GenericArray<u8, Self::BlockSize>)
+    {
+        BlockEncrypt::encrypt_block(self, block);
+    }
+
+    #[inline]
+    fn encrypt_blocks(&mut self,
+        blocks: &mut ParBlocks<Self::BlockSize, Self::ParBlocks>)
+    {
+        BlockEncrypt::encrypt_blocks(self, blocks);
+    }
+
+    #[inline]
+    fn encrypt_block_b2b(&mut self,
+        in_block: &GenericArray<u8, Self::BlockSize>,
+        out_block: &mut GenericArray<u8, Self::BlockSize>)
+    {
+        BlockEncrypt::encrypt_block_b2b(self, in_block, out_block);
+    }
+
+    #[inline]
+    fn encrypt_blocks_b2b(&mut self,
+        in_blocks: &ParBlocks<Self::BlockSize, Self::ParBlocks>,
+        out_blocks: &mut ParBlocks<Self::BlockSize, Self::ParBlocks>)
+    {
+        BlockEncrypt::encrypt_blocks_b2b(self, in_blocks, out_blocks);
+    }
+
+    #[inline]
+    fn encrypt_slice(&mut self,
+        blocks: &mut [GenericArray<u8, Self::BlockSize>])
+    {
+        BlockEncrypt::encrypt_slice(self, blocks);
+    }
+
+    #[inline]
+    fn encrypt_bytes(&mut self, data: &mut [u8])
+        -> Result<(), InvalidLength>
+    {
+        BlockEncrypt::encrypt_bytes(self, data)
+    }
+}
+
+impl<T: BlockDecrypt> BlockDecryptMut for T {
+    #[inline]
+    fn decrypt_block(&mut self,
+        block: &mut GenericArray<u8, Self::BlockSize>)
+    {
+        BlockDecrypt::decrypt_block(self, block);
+    }
+
+    #[inline]
+    fn decrypt_blocks(&mut self,
+        blocks: &mut ParBlocks<Self::BlockSize, Self::ParBlocks>)
+    {
+        BlockDecrypt::decrypt_blocks(self, blocks);
+    }
+
+    #[inline]
+    fn decrypt_block_b2b(&mut self,
+        in_block: &GenericArray<u8, Self::BlockSize>,
+        out_block: &mut GenericArray<u8, Self::BlockSize>)
+    {
+        BlockDecrypt::decrypt_block_b2b(self, in_block, out_block);
+    }
+
+    #[inline]
+    fn decrypt_blocks_b2b(&mut self,
+        in_blocks: &ParBlocks<Self::BlockSize, Self::ParBlocks>,
+        out_blocks: &mut ParBlocks<Self::BlockSize, Self::ParBlocks>)
+    {
+        BlockDecrypt::decrypt_blocks_b2b(self, in_blocks, out_blocks);
+    }
+
+    #[inline]
+    fn decrypt_slice(&mut self,
+        blocks: &mut [GenericArray<u8, Self::BlockSize>])
+    {
+        BlockDecrypt::decrypt_slice(self, blocks);
+    }
+
+    #[inline]
+    fn decrypt_bytes(&mut self, data: &mut [u8])
+        -> Result<(), InvalidLength>
+    {
+        BlockDecrypt::decrypt_bytes(self, data)
+    }
+}
+
+/// Reinterpret byte slice as a slice of blocks.
+#[inline(always)]
+fn to_blocks<N>(data: &mut [u8])
+    -> Result<&mut [GenericArray<u8, N>], InvalidLength>
+    where N: ArrayLength<u8>
+{
+    let n = N::to_usize();
+    if data.len() % n != 0 {
+        return Err(InvalidLength);
+    }
+    // Safety: `GenericArray<u8, N>` has the same layout as `[u8; N]`, i.e.
+    // size `n` and alignment 1 (`GenericArray::from_mut_slice` relies on it
+    // as well), so any pointer into `data` is properly aligned. `data.len()`
+    // is a multiple of `n`, thus the resulting slice covers exactly the same
+    // memory as `data`, and it inherits the unique borrow of `data`.
+    // Zero `n` can't reach this point, since the remainder above panics.
+    debug_assert_eq!(core::mem::size_of::<GenericArray<u8, N>>(), n);
+    debug_assert_eq!(core::mem::align_of::<GenericArray<u8, N>>(), 1);
+    unsafe {
+        Ok(slice::from_raw_parts_mut(
+            data.as_mut_ptr() as *mut GenericArray<u8, N>,
+            data.len() / n,
+        ))
+    }
+}
+
+/// The trait which defines in-place encryption and decryption of a single
+/// block using additional public input, the tweak.
+pub trait TweakableBlockCipher: core::marker::Sized {
+    /// Key size in bytes with which cipher guaranteed to be initialized
+    type KeySize: ArrayLength<u8>;
+    /// Size of the block in bytes
+    type BlockSize: ArrayLength<u8>;
+    /// Size of the tweak in bytes
+    type TweakSize: ArrayLength<u8>;
+
+    /// Create new tweakable block cipher instance from key with fixed size.
+    fn new(key: &GenericArray<u8, Self::KeySize>) -> Self;
+
+    /// Create new tweakable block cipher instance from key with variable
+    /// size.
+    ///
+    /// Default implementation will accept only keys with length equal to
+    /// `KeySize`, but some ciphers can accept range of key lengths,
+    /// in which case `key_sizes` must be overridden as well.
+    fn new_varkey(key: &[u8]) -> Result<Self, InvalidKeyLength> {
+        if key.len() != Self::KeySize::to_usize() {
+            Err(InvalidKeyLength)
+        } else {
+            Ok(Self::new(GenericArray::from_slice(key)))
+        }
+    }
+
+    /// Key lengths accepted by `new_varkey`.
+    ///
+    /// Default implementation returns `KeySize` as the only length.
+    fn key_sizes() -> KeySizes {
+        KeySizes::fixed(Self::KeySize::to_usize())
+    }
+
+    /// Encrypt block in-place using the given tweak
+    fn encrypt_block_with_tweak(&self,
+        tweak: &GenericArray<u8, Self::TweakSize>,
+        block: &mut GenericArray<u8, Self::BlockSize>);
+
+    /// Decrypt block in-place using the given tweak
+    fn decrypt_block_with_tweak(&self,
+        tweak: &GenericArray<u8, Self::TweakSize>,
+        block: &mut GenericArray<u8, Self::BlockSize>);
 }
@@ -0,0 +1,218 @@
+use core::marker::PhantomData;
+use core::ops::{Add, Rem};
+use generic_array::{GenericArray, ArrayLength};
+use generic_array::typenum::{Sum, U0, U1, U2, Unsigned};
+use {BlockDecrypt, BlockEncrypt};
+
+/// Key schedule of a cipher built with `Feistel` or `LaiMassey`.
+pub trait KeySchedule {
+    /// Key size in bytes
+    type KeySize: ArrayLength<u8>;
+    /// Round key used by the round function
+    type RoundKey;
+    /// Number of rounds
+    type Rounds: ArrayLength<Self::RoundKey>;
+
+    /// Expand cipher key into round keys, one key per round.
+    fn expand(key: &GenericArray<u8, Self::KeySize>)
+        -> GenericArray<Self::RoundKey, Self::Rounds>;
+}
+
+/// Round function (F-function) of a cipher built with `Feistel` or
+/// `LaiMassey`.
+pub trait RoundFunction {
+    /// Round key used by the round function
+    type RoundKey;
+    /// Size of the round function input in bytes
+    type InputSize: ArrayLength<u8>;
+    /// Size of the round function output in bytes
+    type OutputSize: ArrayLength<u8>;
+
+    /// Compute round function of `input` with round key `key` and write
+    /// result into `output`.
+    fn round(key: &Self::RoundKey,
+        input: &GenericArray<u8, Self::InputSize>,
+        output: &mut GenericArray<u8, Self::OutputSize>);
+}
+
+type RoundKeys<S> =
+    GenericArray<<S as KeySchedule>::RoundKey, <S as KeySchedule>::Rounds>;
+type FeistelBlockSize<F> =
+    Sum<<F as RoundFunction>::InputSize, <F as RoundFunction>::OutputSize>;
+
+/// Feistel network built from round function `F` and key schedule `S`.
+///
+/// Block `X = S || T` consists of the source part `S` with size of the
+/// round function input and the target part `T` with size of its output.
+/// Every round transforms it into `(T xor F(K, S)) || S`, so network is
+/// balanced if input and output sizes are equal and unbalanced otherwise.
+/// Decryption applies inverse rounds with round keys in the reverse order.
+pub struct Feistel<F, S>
+    where F: RoundFunction, S: KeySchedule<RoundKey = F::RoundKey>
+{
+    round_keys: RoundKeys<S>,
+    _round: PhantomData<F>,
+}
+
+impl<F, S> Feistel<F, S>
+    where F: RoundFunction, S: KeySchedule<RoundKey = F::RoundKey>
+{
+    /// Create new cipher instance from expanded round keys.
+    pub fn from_round_keys(round_keys: RoundKeys<S>) -> Self {
+        Self { round_keys, _round: PhantomData }
+    }
+
+    #[inline]
+    fn apply_round(key: &F::RoundKey, block: &mut [u8]) {
+        let (s, t) = block.split_at_mut(F::InputSize::to_usize());
+        let mut f = GenericArray::default();
+        F::round(key, GenericArray::from_slice(s), &mut f);
+        xor(t, &f);
+    }
+}
+
+impl<F, S> BlockEncrypt for Feistel<F, S>
+    where F: RoundFunction, S: KeySchedule<RoundKey = F::RoundKey>,
+        F::InputSize: Add<F::OutputSize>, FeistelBlockSize<F>: ArrayLength<u8>,
+{
+    type KeySize = S::KeySize;
+    type BlockSize = FeistelBlockSize<F>;
+    type ParBlocks = U1;
+
+    fn new(key: &GenericArray<u8, S::KeySize>) -> Self {
+        Self::from_round_keys(S::expand(key))
+    }
+
+    fn encrypt_block(&self, block: &mut GenericArray<u8, Self::BlockSize>) {
+        let n = F::InputSize::to_usize();
+        for key in self.round_keys.iter() {
+            Self::apply_round(key, block);
+            block.rotate_left(n);
+        }
+    }
+}
+
+impl<F, S> BlockDecrypt for Feistel<F, S>
+    where F: RoundFunction, S: KeySchedule<RoundKey = F::RoundKey>,
+        F::InputSize: Add<F::OutputSize>, FeistelBlockSize<F>: ArrayLength<u8>,
+{
+    fn decrypt_block(&self, block: &mut GenericArray<u8, Self::BlockSize>) {
+        let n = F::InputSize::to_usize();
+        for key in self.round_keys.iter().rev() {
+            block.rotate_right(n);
+            Self::apply_round(key, block);
+        }
+    }
+}
+
+/// Lai–Massey scheme built from round function `F` and key schedule `S`.
+///
+/// Block `L || R` consists of two halves with size of the round function
+/// input and output, which must be equal. Every round computes
+/// `T = F(K, L xor R)` and transforms block into `σ(L xor T) || (R xor T)`,
+/// where orthomorphism `σ(a || b) = b || (a xor b)` operates on quarters of
+/// the block and is omitted in the last round. Half size must be even,
+/// which is enforced by a bound on the round function output size.
+/// Decryption applies inverse rounds with round keys in the reverse order.
+pub struct LaiMassey<F, S>
+    where F: RoundFunction, S: KeySchedule<RoundKey = F::RoundKey>
+{
+    round_keys: RoundKeys<S>,
+    _round: PhantomData<F>,
+}
+
+impl<F, S> LaiMassey<F, S>
+    where F: RoundFunction, S: KeySchedule<RoundKey = F::RoundKey>
+{
+    /// Create new cipher instance from expanded round keys.
+    pub fn from_round_keys(round_keys: RoundKeys<S>) -> Self {
+        Self { round_keys, _round: PhantomData }
+    }
+}
+
+impl<F, S, N> LaiMassey<F, S>
+    where F: RoundFunction<InputSize = N, OutputSize = N>,
+        S: KeySchedule<RoundKey = F::RoundKey>, N: ArrayLength<u8>,
+{
+    #[inline]
+    fn apply_round(key: &F::RoundKey, block: &mut [u8]) {
+        let (l, r) = block.split_at_mut(N::to_usize());
+        let mut d = GenericArray::<u8, N>::clone_from_slice(l);
+        xor(&mut d, r);
+        let mut t = GenericArray::default();
+        F::round(key, &d, &mut t);
+        xor(l, &t);
+        xor(r, &t);
+    }
+}
+
+impl<F, S, N> BlockEncrypt for LaiMassey<F, S>
+    where F: RoundFunction<InputSize = N, OutputSize = N>,
+        S: KeySchedule<RoundKey = F::RoundKey>,
+        N: ArrayLength<u8> + Add<N> + Rem<U2, Output = U0>,
+        Sum<N, N>: ArrayLength<u8>,
+{
+    type KeySize = S::KeySize;
+    type BlockSize = Sum<N, N>;
+    type ParBlocks = U1;
+
+    fn new(key: &GenericArray<u8, S::KeySize>) -> Self {
+        Self::from_round_keys(S::expand(key))
+    }
+
+    fn encrypt_block(&self, block: &mut GenericArray<u8, Sum<N, N>>) {
+        let n = self.round_keys.len();
+        for (i, key) in self.round_keys.iter().enumerate() {
+            Self::apply_round(key, block);
+            if i + 1 != n {
+                sigma(&mut block[..N::to_usize()]);
+            }
+        }
+    }
+}
+
+impl<F, S, N> BlockDecrypt for LaiMassey<F, S>
+    where F: RoundFunction<InputSize = N, OutputSize = N>,
+        S: KeySchedule<RoundKey = F::RoundKey>,
+        N: ArrayLength<u8> + Add<N> + Rem<U2, Output = U0>,
+        Sum<N, N>: ArrayLength<u8>,
+{
+    fn decrypt_block(&self, block: &mut GenericArray<u8, Sum<N, N>>) {
+        let n = self.round_keys.len();
+        for (i, key) in self.round_keys.iter().enumerate().rev() {
+            if i + 1 != n {
+                sigma_inv(&mut block[..N::to_usize()]);
+            }
+            Self::apply_round(key, block);
+        }
+    }
+}
+
+/// Orthomorphism `σ(a || b) = b || (a xor b)`
+#[inline]
+fn sigma(half: &mut [u8]) {
+    let (a, b) = half.split_at_mut(half.len() / 2);
+    for (x, y) in a.iter_mut().zip(b.iter_mut()) {
+        let t = *x;
+        *x = *y;
+        *y ^= t;
+    }
+}
+
+/// Inverse orthomorphism `σ^-1(c || d) = (c xor d) || c`
+#[inline]
+fn sigma_inv(half: &mut [u8]) {
+    let (c, d) = half.split_at_mut(half.len() / 2);
+    for (x, y) in c.iter_mut().zip(d.iter_mut()) {
+        let t = *x;
+        *x ^= *y;
+        *y = t;
+    }
+}
+
+#[inline(always)]
+fn xor(buf: &mut [u8], data: &[u8]) {
+    for (a, b) in buf.iter_mut().zip(data) {
+        *a ^= *b;
+    }
+}
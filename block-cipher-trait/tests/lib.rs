@@ -0,0 +1,662 @@
+extern crate aes;
+#[macro_use]
+extern crate block_cipher_trait;
+extern crate blowfish;
+extern crate des;
+
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+use block_cipher_trait::{BlockDecrypt, BlockEncrypt, InvalidKeyLength};
+use block_cipher_trait::{BlockDecryptMut, BlockEncryptMut, InvalidLength};
+use block_cipher_trait::{DynBlockCipher, DynConstructor, KeySizes};
+use block_cipher_trait::{Cascade, Cascade3, Ede2, Ede3, new_boxed};
+use block_cipher_trait::{Feistel, KeySchedule, LaiMassey, RoundFunction};
+use block_cipher_trait::dev::decode_hex;
+use block_cipher_trait::generic_array::{ArrayLength, GenericArray};
+use block_cipher_trait::generic_array::typenum::{
+    U1, U2, U3, U4, U5, U6, U8, U16, U32, U56, Unsigned,
+};
+
+impl_cipher!(Aes128, aes::Aes128, U16, U16);
+impl_cipher!(Aes128Seq, aes::Aes128, U16, U16, U1);
+impl_cipher!(Des, des::Des, U8, U8);
+
+/// Blowfish accepts keys from 4 to 56 bytes.
+struct Blowfish(blowfish::Blowfish);
+
+impl BlockEncrypt for Blowfish {
+    type KeySize = U56;
+    type BlockSize = U8;
+    type ParBlocks = U8;
+
+    fn new(key: &GenericArray<u8, U56>) -> Self {
+        Self::new_varkey(key).unwrap()
+    }
+
+    fn new_varkey(key: &[u8]) -> Result<Self, InvalidKeyLength> {
+        use blowfish::cipher::KeyInit;
+        blowfish::Blowfish::new_from_slice(key)
+            .map(Blowfish)
+            .map_err(|_| InvalidKeyLength)
+    }
+
+    fn key_sizes() -> KeySizes {
+        KeySizes::Range { min: 4, max: 56, step: 1 }
+    }
+
+    fn encrypt_block(&self, block: &mut GenericArray<u8, U8>) {
+        use blowfish::cipher::BlockEncrypt;
+        self.0.encrypt_block(block.as_mut_slice().into());
+    }
+}
+
+impl BlockDecrypt for Blowfish {
+    fn decrypt_block(&self, block: &mut GenericArray<u8, U8>) {
+        use blowfish::cipher::BlockDecrypt;
+        self.0.decrypt_block(block.as_mut_slice().into());
+    }
+}
+
+/// AES with key length selected by `new_varkey`.
+enum Aes {
+    Aes128(aes::Aes128),
+    Aes192(aes::Aes192),
+    Aes256(aes::Aes256),
+}
+
+impl BlockEncrypt for Aes {
+    type KeySize = U32;
+    type BlockSize = U16;
+    type ParBlocks = U8;
+
+    fn new(key: &GenericArray<u8, U32>) -> Self {
+        Self::new_varkey(key).unwrap()
+    }
+
+    fn new_varkey(key: &[u8]) -> Result<Self, InvalidKeyLength> {
+        use aes::cipher::KeyInit;
+        let res = match key.len() {
+            16 => aes::Aes128::new_from_slice(key).map(Aes::Aes128),
+            24 => aes::Aes192::new_from_slice(key).map(Aes::Aes192),
+            32 => aes::Aes256::new_from_slice(key).map(Aes::Aes256),
+            _ => return Err(InvalidKeyLength),
+        };
+        res.map_err(|_| InvalidKeyLength)
+    }
+
+    fn key_sizes() -> KeySizes {
+        KeySizes::List(&[16, 24, 32])
+    }
+
+    fn encrypt_block(&self, block: &mut GenericArray<u8, U16>) {
+        use aes::cipher::BlockEncrypt;
+        let block = block.as_mut_slice().into();
+        match *self {
+            Aes::Aes128(ref c) => c.encrypt_block(block),
+            Aes::Aes192(ref c) => c.encrypt_block(block),
+            Aes::Aes256(ref c) => c.encrypt_block(block),
+        }
+    }
+}
+
+impl BlockDecrypt for Aes {
+    fn decrypt_block(&self, block: &mut GenericArray<u8, U16>) {
+        use aes::cipher::BlockDecrypt;
+        let block = block.as_mut_slice().into();
+        match *self {
+            Aes::Aes128(ref c) => c.decrypt_block(block),
+            Aes::Aes192(ref c) => c.decrypt_block(block),
+            Aes::Aes256(ref c) => c.decrypt_block(block),
+        }
+    }
+}
+
+key_sizes_test!(aes128_key_sizes, Aes128);
+key_sizes_test!(blowfish_key_sizes, Blowfish);
+key_sizes_test!(aes_key_sizes, Aes);
+
+type Block = GenericArray<u8, U16>;
+type ParBlocks = GenericArray<Block, U4>;
+
+/// AES-128 with `ParBlocks` equal to 4 which counts calls of single and
+/// parallel block methods. Buffer-to-buffer methods are overridden and
+/// counted separately.
+struct Counting {
+    cipher: Aes128,
+    single: Cell<usize>,
+    par: Cell<usize>,
+    b2b: Cell<usize>,
+}
+
+impl Counting {
+    fn calls(&self) -> (usize, usize) {
+        (self.single.replace(0), self.par.replace(0))
+    }
+
+    fn b2b_calls(&self) -> usize {
+        self.b2b.replace(0)
+    }
+}
+
+impl BlockEncrypt for Counting {
+    type KeySize = U16;
+    type BlockSize = U16;
+    type ParBlocks = U4;
+
+    fn new(key: &GenericArray<u8, U16>) -> Self {
+        Counting {
+            cipher: Aes128::new(key),
+            single: Cell::new(0),
+            par: Cell::new(0),
+            b2b: Cell::new(0),
+        }
+    }
+
+    fn encrypt_block(&self, block: &mut Block) {
+        self.single.set(self.single.get() + 1);
+        self.cipher.encrypt_block(block);
+    }
+
+    fn encrypt_blocks(&self, blocks: &mut ParBlocks) {
+        self.par.set(self.par.get() + 1);
+        for block in blocks.iter_mut() { self.cipher.encrypt_block(block); }
+    }
+
+    fn encrypt_block_b2b(&self, in_block: &Block, out_block: &mut Block) {
+        self.b2b.set(self.b2b.get() + 1);
+        self.cipher.encrypt_block_b2b(in_block, out_block);
+    }
+
+    fn encrypt_blocks_b2b(&self, in_blocks: &ParBlocks,
+        out_blocks: &mut ParBlocks)
+    {
+        self.b2b.set(self.b2b.get() + 1);
+        for (i, o) in in_blocks.iter().zip(out_blocks.iter_mut()) {
+            self.cipher.encrypt_block_b2b(i, o);
+        }
+    }
+}
+
+impl BlockDecrypt for Counting {
+    fn decrypt_block(&self, block: &mut Block) {
+        self.single.set(self.single.get() + 1);
+        self.cipher.decrypt_block(block);
+    }
+
+    fn decrypt_blocks(&self, blocks: &mut ParBlocks) {
+        self.par.set(self.par.get() + 1);
+        for block in blocks.iter_mut() { self.cipher.decrypt_block(block); }
+    }
+
+    fn decrypt_block_b2b(&self, in_block: &Block, out_block: &mut Block) {
+        self.b2b.set(self.b2b.get() + 1);
+        self.cipher.decrypt_block_b2b(in_block, out_block);
+    }
+
+    fn decrypt_blocks_b2b(&self, in_blocks: &ParBlocks,
+        out_blocks: &mut ParBlocks)
+    {
+        self.b2b.set(self.b2b.get() + 1);
+        for (i, o) in in_blocks.iter().zip(out_blocks.iter_mut()) {
+            self.cipher.decrypt_block_b2b(i, o);
+        }
+    }
+}
+
+/// Numbers of blocks around `ParBlocks` boundaries.
+const BLOCK_NUMS: &[usize] = &[0, 1, 3, 4, 5, 7, 8, 9];
+
+fn test_data(n: usize) -> (Vec<Block>, Vec<Block>) {
+    let key = GenericArray::from_slice(&[0x42; 16]);
+    let cipher = Aes128::new(key);
+    let mut pt = vec![Block::default(); n];
+    for (i, b) in pt.iter_mut().flat_map(|b| b.iter_mut()).enumerate() {
+        *b = i as u8;
+    }
+    let mut ct = pt.clone();
+    for block in ct.iter_mut() { cipher.encrypt_block(block); }
+    (pt, ct)
+}
+
+fn flatten(blocks: &[Block]) -> Vec<u8> {
+    blocks.iter().flat_map(|b| b.iter().cloned()).collect()
+}
+
+#[test]
+fn encrypt_slice() {
+    let cipher = Counting::new(GenericArray::from_slice(&[0x42; 16]));
+    for &n in BLOCK_NUMS {
+        let (pt, ct) = test_data(n);
+        let mut buf = pt.clone();
+        cipher.encrypt_slice(&mut buf);
+        assert_eq!(buf, ct);
+        assert_eq!(cipher.calls(), (n % 4, n / 4));
+        cipher.decrypt_slice(&mut buf);
+        assert_eq!(buf, pt);
+        assert_eq!(cipher.calls(), (n % 4, n / 4));
+    }
+}
+
+#[test]
+fn encrypt_bytes() {
+    let cipher = Counting::new(GenericArray::from_slice(&[0x42; 16]));
+    for &n in BLOCK_NUMS {
+        let (pt, ct) = test_data(n);
+        let (pt, ct) = (flatten(&pt), flatten(&ct));
+        let mut buf = pt.clone();
+        cipher.encrypt_bytes(&mut buf).unwrap();
+        assert_eq!(buf, ct);
+        assert_eq!(cipher.calls(), (n % 4, n / 4));
+        cipher.decrypt_bytes(&mut buf).unwrap();
+        assert_eq!(buf, pt);
+        assert_eq!(cipher.calls(), (n % 4, n / 4));
+    }
+}
+
+#[test]
+fn encrypt_bytes_mut() {
+    let mut cipher = Counting::new(GenericArray::from_slice(&[0x42; 16]));
+    for &n in BLOCK_NUMS {
+        let (pt, ct) = test_data(n);
+        let (pt, ct) = (flatten(&pt), flatten(&ct));
+        let mut buf = pt.clone();
+        BlockEncryptMut::encrypt_bytes(&mut cipher, &mut buf).unwrap();
+        assert_eq!(buf, ct);
+        BlockDecryptMut::decrypt_bytes(&mut cipher, &mut buf).unwrap();
+        assert_eq!(buf, pt);
+        assert_eq!(cipher.calls(), (2 * (n % 4), 2 * (n / 4)));
+    }
+}
+
+#[test]
+fn encrypt_bytes_invalid_length() {
+    let mut cipher = Counting::new(GenericArray::from_slice(&[0x42; 16]));
+    for &len in &[1, 15, 17, 63, 65, 79] {
+        let pt: Vec<u8> = (0..len).map(|i| i as u8).collect();
+        let mut buf = pt.clone();
+        assert_eq!(cipher.encrypt_bytes(&mut buf), Err(InvalidLength));
+        assert_eq!(cipher.decrypt_bytes(&mut buf), Err(InvalidLength));
+        assert_eq!(BlockEncryptMut::encrypt_bytes(&mut cipher, &mut buf),
+            Err(InvalidLength));
+        assert_eq!(BlockDecryptMut::decrypt_bytes(&mut cipher, &mut buf),
+            Err(InvalidLength));
+        assert_eq!(buf, pt);
+        assert_eq!(cipher.calls(), (0, 0));
+    }
+}
+
+/// Default buffer-to-buffer methods must give the same result as the
+/// in-place ones and must not modify the input.
+#[test]
+fn b2b_default() {
+    let cipher = Aes128::new(GenericArray::from_slice(&[0x42; 16]));
+    let (pt, ct) = test_data(4);
+
+    let mut out = Block::default();
+    cipher.encrypt_block_b2b(&pt[0], &mut out);
+    assert_eq!(out, ct[0]);
+    let mut out2 = Block::default();
+    cipher.decrypt_block_b2b(&out, &mut out2);
+    assert_eq!(out2, pt[0]);
+
+    let (pt, ct) = test_data(8);
+    let blocks = GenericArray::<Block, U8>::clone_from_slice(&pt);
+    let mut out = GenericArray::default();
+    cipher.encrypt_blocks_b2b(&blocks, &mut out);
+    assert_eq!(&out[..], &ct[..]);
+    assert_eq!(&blocks[..], &pt[..]);
+    let mut out2 = GenericArray::default();
+    cipher.decrypt_blocks_b2b(&out, &mut out2);
+    assert_eq!(out2, blocks);
+    assert_eq!(&out[..], &ct[..]);
+}
+
+/// Blanket `BlockEncryptMut` and `BlockDecryptMut` implementations must
+/// forward buffer-to-buffer methods to the overridden ones.
+#[test]
+fn b2b_mut_forwarding() {
+    let mut cipher = Counting::new(GenericArray::from_slice(&[0x42; 16]));
+    let (pt, ct) = test_data(4);
+
+    let mut out = Block::default();
+    BlockEncryptMut::encrypt_block_b2b(&mut cipher, &pt[0], &mut out);
+    assert_eq!(out, ct[0]);
+    let mut out2 = Block::default();
+    BlockDecryptMut::decrypt_block_b2b(&mut cipher, &out, &mut out2);
+    assert_eq!(out2, pt[0]);
+    assert_eq!(cipher.b2b_calls(), 2);
+
+    let blocks = ParBlocks::clone_from_slice(&pt);
+    let mut out = ParBlocks::default();
+    BlockEncryptMut::encrypt_blocks_b2b(&mut cipher, &blocks, &mut out);
+    assert_eq!(&out[..], &ct[..]);
+    let mut out2 = ParBlocks::default();
+    BlockDecryptMut::decrypt_blocks_b2b(&mut cipher, &out, &mut out2);
+    assert_eq!(out2, blocks);
+    assert_eq!(cipher.b2b_calls(), 2);
+    assert_eq!(cipher.calls(), (0, 0));
+}
+
+#[test]
+fn dyn_roundtrip() {
+    let ciphers: &[(&str, DynConstructor, usize)] = &[
+        ("aes128", new_boxed::<Aes128>, 16),
+        ("aes", new_boxed::<Aes>, 24),
+        ("blowfish", new_boxed::<Blowfish>, 7),
+    ];
+    let key: Vec<u8> = (0..32).collect();
+    for &(name, new, key_len) in ciphers {
+        let cipher: Box<dyn DynBlockCipher> = new(&key[..key_len]).unwrap();
+        let pt: Vec<u8> = (0..5 * cipher.block_size())
+            .map(|i| i as u8).collect();
+        let mut buf = pt.clone();
+        cipher.encrypt(&mut buf).unwrap();
+        assert_ne!(buf, pt, "{}", name);
+        cipher.decrypt(&mut buf).unwrap();
+        assert_eq!(buf, pt, "{}", name);
+    }
+}
+
+#[test]
+fn dyn_matches_static() {
+    let key = [0x42; 16];
+    let cipher = new_boxed::<Aes128>(&key).unwrap();
+    assert_eq!(cipher.block_size(), 16);
+    assert_eq!(cipher.key_sizes(), KeySizes::fixed(16));
+
+    let (pt, ct) = test_data(5);
+    let mut buf = flatten(&pt);
+    cipher.encrypt(&mut buf).unwrap();
+    assert_eq!(buf, flatten(&ct));
+}
+
+#[test]
+fn dyn_key_sizes() {
+    let key = [0u8; 64];
+    let cipher = new_boxed::<Aes>(&key[..24]).unwrap();
+    assert_eq!(cipher.key_sizes(), KeySizes::List(&[16, 24, 32]));
+    let cipher = new_boxed::<Blowfish>(&key[..4]).unwrap();
+    assert_eq!(cipher.block_size(), 8);
+    assert!(cipher.key_sizes().contains(4));
+    assert!(cipher.key_sizes().contains(56));
+
+    assert!(new_boxed::<Aes128>(&key[..15]).is_err());
+    assert!(new_boxed::<Aes128>(&key[..24]).is_err());
+    assert!(new_boxed::<Aes>(&key[..20]).is_err());
+    assert!(new_boxed::<Blowfish>(&key[..3]).is_err());
+    assert!(new_boxed::<Blowfish>(&key[..57]).is_err());
+}
+
+#[test]
+fn dyn_invalid_length() {
+    let cipher = new_boxed::<Blowfish>(&[0x42; 16]).unwrap();
+    for &len in &[1, 7, 9, 15, 17] {
+        let pt: Vec<u8> = (0..len).map(|i| i as u8).collect();
+        let mut buf = pt.clone();
+        assert_eq!(cipher.encrypt(&mut buf), Err(InvalidLength));
+        assert_eq!(cipher.decrypt(&mut buf), Err(InvalidLength));
+        assert_eq!(buf, pt);
+    }
+    let mut buf = [];
+    assert_eq!(cipher.encrypt(&mut buf), Ok(()));
+}
+
+dispatch_test!(dispatch_aes128, Aes128, Aes128Seq);
+dispatch_test!(dispatch_aes128_rev, Aes128Seq, Aes128);
+dispatch_test!(dispatch_counting, Counting, Aes128);
+
+/// Check encryption and decryption of `pt` with a single call of
+/// `encrypt_bytes` and block by block.
+fn check_cipher<C: BlockEncrypt + BlockDecrypt>(key: &str, pt: &[u8],
+    ct: &str)
+{
+    let mut buf = [0u8; 64];
+    let key = decode_hex(key, &mut buf).to_vec();
+    let ct = decode_hex(ct, &mut buf).to_vec();
+    let cipher = C::new_varkey(&key).unwrap();
+
+    let mut data = pt.to_vec();
+    cipher.encrypt_bytes(&mut data).unwrap();
+    assert_eq!(data, ct);
+    cipher.decrypt_bytes(&mut data).unwrap();
+    assert_eq!(data, pt);
+
+    let bs = C::BlockSize::to_usize();
+    for (block, ct_block) in data.chunks_mut(bs).zip(ct.chunks(bs)) {
+        let block = GenericArray::from_mut_slice(block);
+        cipher.encrypt_block(block);
+        assert_eq!(block.as_slice(), ct_block);
+        cipher.decrypt_block(block);
+    }
+    assert_eq!(data, pt);
+}
+
+// 3-key vector from NIST SP 800-67 with the message typo fixed, other
+// vectors are generated using the OpenSSL Triple DES and AES
+// implementations.
+const DES_KEY: &str = "0123456789abcdef";
+const TDES_PLAINTEXT: &[u8] = b"The quick brown fox jump";
+
+#[test]
+fn ede2_des() {
+    check_cipher::<Ede2<Des, Des>>("0123456789abcdef23456789abcdef01",
+        TDES_PLAINTEXT, "\
+            04a3aaa7954df2419077d0909fa91b884cabd61fc58e0cbb");
+}
+
+#[test]
+fn ede3_des() {
+    check_cipher::<Ede3<Des, Des, Des>>("\
+            0123456789abcdef23456789abcdef01456789abcdef0123",
+        TDES_PLAINTEXT, "\
+            1ccf23869d09333ecce21c8112256fe668d5c05dd9b6b900");
+}
+
+/// EDE with equal keys degenerates to a single encryption.
+#[test]
+fn ede_equal_keys() {
+    let ct = "a3c6e831ad654880167e47ec24f71d632c1a917234425365";
+    check_cipher::<Des>(DES_KEY, TDES_PLAINTEXT, ct);
+    let key2 = DES_KEY.repeat(2);
+    check_cipher::<Ede2<Des, Des>>(&key2, TDES_PLAINTEXT, ct);
+    let key3 = DES_KEY.repeat(3);
+    check_cipher::<Ede3<Des, Des, Des>>(&key3, TDES_PLAINTEXT, ct);
+}
+
+const CASCADE_KEYS: &str = "\
+    000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f\
+    202122232425262728292a2b2c2d2e2f";
+const CASCADE_PLAINTEXT: &[u8] = b"cascade of ciphers, 32 bytes....";
+
+#[test]
+fn cascade_aes128() {
+    check_cipher::<Cascade<Aes128, Aes128>>(&CASCADE_KEYS[..64],
+        CASCADE_PLAINTEXT, "\
+            6ca2599a041636e4134cdef790256cb9c675b268c58cbab9b34d5603eb5d5a27");
+}
+
+#[test]
+fn cascade3_aes128() {
+    check_cipher::<Cascade3<Aes128, Aes128, Aes128>>(CASCADE_KEYS,
+        CASCADE_PLAINTEXT, "\
+            e120b582a518375020084c672a41c62e627ab1698d6752a68ae0014622af2512");
+}
+
+/// Combinators process parallel blocks of the first cipher and slices with
+/// the second one, check that the result does not depend on `ParBlocks` of
+/// the components.
+#[test]
+fn combinators_par_blocks() {
+    fn check<C: BlockEncrypt<BlockSize = U16> + BlockDecrypt>(key: &[u8]) {
+        let reference = Cascade3::<Aes128, Aes128, Aes128>::new_varkey(key)
+            .unwrap();
+        let cipher = C::new_varkey(key).unwrap();
+        for &n in BLOCK_NUMS.iter().chain(&[15, 16, 17]) {
+            let pt = test_data(n).0;
+            let (mut a, mut b) = (pt.clone(), pt.clone());
+            reference.encrypt_slice(&mut a);
+            cipher.encrypt_slice(&mut b);
+            assert_eq!(a, b);
+            cipher.decrypt_slice(&mut b);
+            assert_eq!(b, pt);
+        }
+    }
+
+    let key: Vec<u8> = (0..48).collect();
+    check::<Cascade<Counting, Cascade<Aes128, Aes128Seq>>>(&key);
+    check::<Cascade<Aes128Seq, Cascade<Counting, Aes128>>>(&key);
+    check::<Cascade<Cascade<Aes128, Counting>, Aes128Seq>>(&key);
+}
+
+type RoundKey = [u8; 8];
+
+/// Toy non-linear round function with input size `I` and output size `O`.
+struct Toy<I, O>(PhantomData<(I, O)>);
+
+fn toy_round(key: &RoundKey, input: &[u8], output_len: usize) -> Vec<u8> {
+    (0..output_len).map(|j| {
+        let x = input[j % input.len()];
+        let y = input[(j + 1) % input.len()];
+        (x.wrapping_mul(167) ^ key[j % 8]).rotate_left(3).wrapping_add(y)
+    }).collect()
+}
+
+impl<I, O> RoundFunction for Toy<I, O>
+    where I: ArrayLength<u8>, O: ArrayLength<u8>
+{
+    type RoundKey = RoundKey;
+    type InputSize = I;
+    type OutputSize = O;
+
+    fn round(key: &RoundKey, input: &GenericArray<u8, I>,
+        output: &mut GenericArray<u8, O>)
+    {
+        output.copy_from_slice(&toy_round(key, input, O::to_usize()));
+    }
+}
+
+/// Toy key schedule with 8 byte key and `R` rounds.
+struct ToySchedule<R>(PhantomData<R>);
+
+impl<R: ArrayLength<RoundKey>> KeySchedule for ToySchedule<R> {
+    type KeySize = U8;
+    type RoundKey = RoundKey;
+    type Rounds = R;
+
+    fn expand(key: &GenericArray<u8, U8>) -> GenericArray<RoundKey, R> {
+        let mut keys = GenericArray::<RoundKey, R>::default();
+        for (i, k) in keys.iter_mut().enumerate() {
+            for (j, b) in k.iter_mut().enumerate() {
+                *b = key[(i + j) % 8] ^ (i as u8);
+            }
+        }
+        keys
+    }
+}
+
+fn round_keys(key: &[u8], rounds: usize) -> Vec<RoundKey> {
+    (0..rounds).map(|i| {
+        let mut k = [0u8; 8];
+        for (j, b) in k.iter_mut().enumerate() {
+            *b = key[(i + j) % 8] ^ (i as u8);
+        }
+        k
+    }).collect()
+}
+
+/// Straightforward Feistel network: every round transforms `S || T` into
+/// `(T xor F(K, S)) || S`.
+fn feistel_ref(key: &[u8], rounds: usize, input_len: usize, block: &[u8])
+    -> Vec<u8>
+{
+    let mut block = block.to_vec();
+    for k in round_keys(key, rounds) {
+        let (s, t) = block.split_at(input_len);
+        let f = toy_round(&k, s, t.len());
+        let mut res: Vec<u8> = t.iter().zip(&f).map(|(a, b)| a ^ b).collect();
+        res.extend_from_slice(s);
+        block = res;
+    }
+    block
+}
+
+/// Lai–Massey scheme over 16-bit quarters.
+fn lai_massey_ref(key: &[u8], rounds: usize, block: [u16; 4]) -> [u16; 4] {
+    fn to_bytes(v: &[u16]) -> Vec<u8> {
+        v.iter().flat_map(|x| x.to_be_bytes().to_vec()).collect()
+    }
+
+    let [mut a, mut b, mut c, mut d] = block;
+    for (i, k) in round_keys(key, rounds).iter().enumerate() {
+        let t = toy_round(k, &to_bytes(&[a ^ c, b ^ d]), 4);
+        let t0 = u16::from_be_bytes([t[0], t[1]]);
+        let t1 = u16::from_be_bytes([t[2], t[3]]);
+        a ^= t0;
+        b ^= t1;
+        c ^= t0;
+        d ^= t1;
+        if i + 1 != rounds {
+            // σ(a || b) = b || (a xor b)
+            let t = a;
+            a = b;
+            b ^= t;
+        }
+    }
+    [a, b, c, d]
+}
+
+fn check_network<C, R>(rounds: usize, reference: R)
+    where C: BlockEncrypt<KeySize = U8> + BlockDecrypt,
+        R: Fn(&[u8], &[u8]) -> Vec<u8>
+{
+    for i in 0..8u8 {
+        let key: Vec<u8> = (0..8).map(|j| j * 31 + i).collect();
+        let cipher = C::new(GenericArray::from_slice(&key));
+        let pt: Vec<u8> = (0..C::BlockSize::to_usize() as u8)
+            .map(|j| j.wrapping_mul(73) ^ i)
+            .collect();
+        let mut block = GenericArray::clone_from_slice(&pt);
+        cipher.encrypt_block(&mut block);
+        assert_eq!(block.as_slice(), &reference(&key, &pt)[..],
+            "{} rounds", rounds);
+        cipher.decrypt_block(&mut block);
+        assert_eq!(block.as_slice(), &pt[..]);
+    }
+}
+
+#[test]
+fn feistel_balanced() {
+    type F<R> = Feistel<Toy<U4, U4>, ToySchedule<R>>;
+    check_network::<F<U1>, _>(1, |k, b| feistel_ref(k, 1, 4, b));
+    check_network::<F<U16>, _>(16, |k, b| feistel_ref(k, 16, 4, b));
+}
+
+#[test]
+fn feistel_unbalanced() {
+    // source-heavy and target-heavy networks
+    type F1 = Feistel<Toy<U6, U2>, ToySchedule<U8>>;
+    type F2 = Feistel<Toy<U2, U6>, ToySchedule<U8>>;
+    type F3 = Feistel<Toy<U3, U5>, ToySchedule<U5>>;
+    check_network::<F1, _>(8, |k, b| feistel_ref(k, 8, 6, b));
+    check_network::<F2, _>(8, |k, b| feistel_ref(k, 8, 2, b));
+    check_network::<F3, _>(5, |k, b| feistel_ref(k, 5, 3, b));
+}
+
+#[test]
+fn lai_massey() {
+    fn reference(key: &[u8], rounds: usize, block: &[u8]) -> Vec<u8> {
+        let mut q = [0u16; 4];
+        for (x, c) in q.iter_mut().zip(block.chunks(2)) {
+            *x = u16::from_be_bytes([c[0], c[1]]);
+        }
+        lai_massey_ref(key, rounds, q).iter()
+            .flat_map(|x| x.to_be_bytes().to_vec())
+            .collect()
+    }
+
+    type L<R> = LaiMassey<Toy<U4, U4>, ToySchedule<R>>;
+    check_network::<L<U1>, _>(1, |k, b| reference(k, 1, b));
+    check_network::<L<U2>, _>(2, |k, b| reference(k, 2, b));
+    check_network::<L<U8>, _>(8, |k, b| reference(k, 8, b));
+}
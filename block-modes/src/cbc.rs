@@ -0,0 +1,51 @@
+use block_cipher_trait::BlockCipherMut;
+use utils::{Block, ParBlocks, par_blocks, xor};
+use BlockMode;
+
+/// [Cipher Block Chaining][1] (CBC) block cipher mode instance.
+///
+/// Decryption processes blocks in batches of `C::ParBlocks`.
+///
+/// [1]: https://en.wikipedia.org/wiki/Block_cipher_mode_of_operation#CBC
+pub struct Cbc<C: BlockCipherMut> {
+    cipher: C,
+    iv: Block<C>,
+}
+
+impl<C: BlockCipherMut> BlockMode<C> for Cbc<C> {
+    type IvSize = C::BlockSize;
+
+    fn new(cipher: C, iv: &Block<C>) -> Self {
+        Self { cipher, iv: iv.clone() }
+    }
+
+    fn encrypt_blocks(&mut self, blocks: &mut [Block<C>]) {
+        for block in blocks {
+            xor(block, &self.iv);
+            self.cipher.encrypt_block(block);
+            self.iv = block.clone();
+        }
+    }
+
+    fn decrypt_blocks(&mut self, blocks: &mut [Block<C>]) {
+        let n = par_blocks::<C>();
+        for chunk in blocks.chunks_mut(n) {
+            if chunk.len() == n {
+                let ct = ParBlocks::<C>::clone_from_slice(chunk);
+                self.cipher.decrypt_blocks(ParBlocks::<C>::from_mut_slice(chunk));
+                xor(&mut chunk[0], &self.iv);
+                for i in 1..n {
+                    xor(&mut chunk[i], &ct[i - 1]);
+                }
+                self.iv = ct[n - 1].clone();
+            } else {
+                for block in chunk {
+                    let ct = block.clone();
+                    self.cipher.decrypt_block(block);
+                    xor(block, &self.iv);
+                    self.iv = ct;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use block_cipher_trait::BlockEncryptMut;
+use utils::{Block, ParBlocks, par_blocks, xor};
+use BlockMode;
+
+/// [Cipher feedback][1] (CFB) block cipher mode instance with a full block
+/// feedback.
+///
+/// Decryption processes blocks in batches of `C::ParBlocks`.
+///
+/// [1]: https://en.wikipedia.org/wiki/Block_cipher_mode_of_operation#CFB
+pub struct Cfb<C: BlockEncryptMut> {
+    cipher: C,
+    iv: Block<C>,
+}
+
+impl<C: BlockEncryptMut> BlockMode<C> for Cfb<C> {
+    type IvSize = C::BlockSize;
+
+    fn new(cipher: C, iv: &Block<C>) -> Self {
+        Self { cipher, iv: iv.clone() }
+    }
+
+    fn encrypt_blocks(&mut self, blocks: &mut [Block<C>]) {
+        for block in blocks {
+            self.cipher.encrypt_block(&mut self.iv);
+            xor(block, &self.iv);
+            self.iv = block.clone();
+        }
+    }
+
+    fn decrypt_blocks(&mut self, blocks: &mut [Block<C>]) {
+        let n = par_blocks::<C>();
+        for chunk in blocks.chunks_mut(n) {
+            if chunk.len() == n {
+                let mut ks = ParBlocks::<C>::default();
+                ks[0] = self.iv.clone();
+                ks[1..].clone_from_slice(&chunk[..n - 1]);
+                self.iv = chunk[n - 1].clone();
+                self.cipher.encrypt_blocks(&mut ks);
+                for (block, k) in chunk.iter_mut().zip(ks.iter()) {
+                    xor(block, k);
+                }
+            } else {
+                for block in chunk {
+                    let ct = block.clone();
+                    self.cipher.encrypt_block(&mut self.iv);
+                    xor(block, &self.iv);
+                    self.iv = ct;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use block_cipher_trait::BlockEncryptMut;
+use block_cipher_trait::generic_array::typenum::Unsigned;
+use utils::Block;
+use BlockMode;
+
+/// [Cipher feedback][1] (CFB) block cipher mode instance with 8-bit feedback.
+///
+/// Every byte of the message requires one call of `encrypt_block`, so this
+/// mode is significantly slower than the full block `Cfb`.
+///
+/// [1]: https://en.wikipedia.org/wiki/Block_cipher_mode_of_operation#CFB
+pub struct Cfb8<C: BlockEncryptMut> {
+    cipher: C,
+    iv: Block<C>,
+}
+
+impl<C: BlockEncryptMut> Cfb8<C> {
+    /// Encrypt feedback register and pass first byte of the result to `f`,
+    /// which returns output byte and byte to shift into the register.
+    #[inline(always)]
+    fn process_byte<F: FnOnce(u8) -> (u8, u8)>(&mut self, f: F) -> u8 {
+        let mut ks = self.iv.clone();
+        self.cipher.encrypt_block(&mut ks);
+        let (out, feedback) = f(ks[0]);
+        let n = C::BlockSize::to_usize();
+        for i in 0..n - 1 {
+            self.iv[i] = self.iv[i + 1];
+        }
+        self.iv[n - 1] = feedback;
+        out
+    }
+}
+
+impl<C: BlockEncryptMut> BlockMode<C> for Cfb8<C> {
+    type IvSize = C::BlockSize;
+
+    fn new(cipher: C, iv: &Block<C>) -> Self {
+        Self { cipher, iv: iv.clone() }
+    }
+
+    fn encrypt_blocks(&mut self, blocks: &mut [Block<C>]) {
+        for block in blocks {
+            for b in block.iter_mut() {
+                let pt = *b;
+                *b = self.process_byte(|k| {
+                    let ct = pt ^ k;
+                    (ct, ct)
+                });
+            }
+        }
+    }
+
+    fn decrypt_blocks(&mut self, blocks: &mut [Block<C>]) {
+        for block in blocks {
+            for b in block.iter_mut() {
+                let ct = *b;
+                *b = self.process_byte(|k| (ct ^ k, ct));
+            }
+        }
+    }
+}
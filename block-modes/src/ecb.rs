@@ -0,0 +1,31 @@
+use block_cipher_trait::BlockCipherMut;
+use block_cipher_trait::generic_array::GenericArray;
+use block_cipher_trait::generic_array::typenum::U0;
+use utils::Block;
+use BlockMode;
+
+/// [Electronic Codebook][1] (ECB) block cipher mode instance.
+///
+/// Note that `new` method ignores IV, so during initialization you can
+/// just pass `Default::default()` instead.
+///
+/// [1]: https://en.wikipedia.org/wiki/Block_cipher_mode_of_operation#ECB
+pub struct Ecb<C: BlockCipherMut> {
+    cipher: C,
+}
+
+impl<C: BlockCipherMut> BlockMode<C> for Ecb<C> {
+    type IvSize = U0;
+
+    fn new(cipher: C, _iv: &GenericArray<u8, U0>) -> Self {
+        Self { cipher }
+    }
+
+    fn encrypt_blocks(&mut self, blocks: &mut [Block<C>]) {
+        self.cipher.encrypt_slice(blocks);
+    }
+
+    fn decrypt_blocks(&mut self, blocks: &mut [Block<C>]) {
+        self.cipher.decrypt_slice(blocks);
+    }
+}
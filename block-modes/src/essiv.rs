@@ -0,0 +1,192 @@
+use block_cipher_trait::{
+    BlockCipher, BlockCipherMut, BlockEncrypt, BlockEncryptMut,
+};
+use block_cipher_trait::generic_array::typenum::Unsigned;
+use digest::Digest;
+use utils::{Block, ParBlocks, par_blocks, to_blocks, xor};
+use {BlockModeError, InvalidKeyLength};
+
+/// CBC mode with [Encrypted Salt-Sector Initialization Vector][1] (ESSIV).
+///
+/// IV of the sector is computed by encrypting little endian sector number
+/// padded with zeros using IV cipher `I`, which is usually keyed with a hash
+/// of the data cipher key (see `new_with_digest`). This is compatible with
+/// the `essiv` IV mode of dm-crypt, e.g. `aes-cbc-essiv:sha256`.
+///
+/// Sector decryption processes blocks in batches of `C::ParBlocks`. Since
+/// CBC encryption is sequential, `encrypt_area` processes `C::ParBlocks`
+/// sectors in parallel instead.
+///
+/// [1]: https://en.wikipedia.org/wiki/Disk_encryption_theory#ESSIV
+pub struct Essiv<C, I = C>
+    where C: BlockCipherMut, I: BlockEncryptMut<BlockSize = C::BlockSize>
+{
+    cipher: C,
+    iv_cipher: I,
+}
+
+impl<C, I> Essiv<C, I>
+    where C: BlockCipherMut, I: BlockEncryptMut<BlockSize = C::BlockSize>
+{
+    /// Create new ESSIV instance from data and IV block cipher instances.
+    pub fn new(cipher: C, iv_cipher: I) -> Self {
+        Self { cipher, iv_cipher }
+    }
+
+    /// Compute IV for the sector number.
+    pub fn sector_iv(&mut self, sector_num: u64) -> Block<C> {
+        let mut iv = Block::<C>::default();
+        let n = core::cmp::min(iv.len(), 8);
+        iv[..n].copy_from_slice(&sector_num.to_le_bytes()[..n]);
+        self.iv_cipher.encrypt_block(&mut iv);
+        iv
+    }
+
+    /// Encrypt sector in-place.
+    ///
+    /// Returns `Err(BlockModeError)` if length of the sector is not a
+    /// multiple of block size.
+    pub fn encrypt_sector(&mut self, sector: &mut [u8], sector_num: u64)
+        -> Result<(), BlockModeError>
+    {
+        let bs = self.block_size();
+        if sector.len() % bs != 0 {
+            return Err(BlockModeError);
+        }
+        let mut iv = self.sector_iv(sector_num);
+        for block in to_blocks::<C::BlockSize>(sector) {
+            xor(block, &iv);
+            self.cipher.encrypt_block(block);
+            iv = block.clone();
+        }
+        Ok(())
+    }
+
+    /// Decrypt sector in-place.
+    ///
+    /// Returns `Err(BlockModeError)` if length of the sector is not a
+    /// multiple of block size.
+    pub fn decrypt_sector(&mut self, sector: &mut [u8], sector_num: u64)
+        -> Result<(), BlockModeError>
+    {
+        let bs = self.block_size();
+        if sector.len() % bs != 0 {
+            return Err(BlockModeError);
+        }
+        let n = par_blocks::<C>();
+        let mut iv = self.sector_iv(sector_num);
+        for chunk in to_blocks::<C::BlockSize>(sector).chunks_mut(n) {
+            if chunk.len() == n {
+                let ct = ParBlocks::<C>::clone_from_slice(chunk);
+                self.cipher.decrypt_blocks(ParBlocks::<C>::from_mut_slice(chunk));
+                xor(&mut chunk[0], &iv);
+                for i in 1..n {
+                    xor(&mut chunk[i], &ct[i - 1]);
+                }
+                iv = ct[n - 1].clone();
+            } else {
+                for block in chunk {
+                    let ct = block.clone();
+                    self.cipher.decrypt_block(block);
+                    xor(block, &iv);
+                    iv = ct;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Encrypt consecutive sectors of size `sector_size` in-place, starting
+    /// with sector number `first_sector`.
+    ///
+    /// Returns `Err(BlockModeError)` without processing data if sector size
+    /// is zero or not a multiple of block size, or if length of the data is
+    /// not a multiple of sector size.
+    pub fn encrypt_area(
+        &mut self, data: &mut [u8], sector_size: usize, first_sector: u64,
+    ) -> Result<(), BlockModeError> {
+        self.check_area(data, sector_size)?;
+        let bs = self.block_size();
+        let n = par_blocks::<C>();
+        let mut sector_num = first_sector;
+        for group in data.chunks_mut(n * sector_size) {
+            if group.len() != n * sector_size {
+                for sector in group.chunks_mut(sector_size) {
+                    self.encrypt_sector(sector, sector_num)?;
+                    sector_num = sector_num.wrapping_add(1);
+                }
+                continue;
+            }
+            let mut ivs = ParBlocks::<C>::default();
+            for iv in ivs.iter_mut() {
+                *iv = self.sector_iv(sector_num);
+                sector_num = sector_num.wrapping_add(1);
+            }
+            // encrypt i-th block of all sectors in the group in parallel
+            for offset in (0..sector_size).step_by(bs) {
+                let blocks = group.chunks_mut(sector_size)
+                    .map(|sector| &mut sector[offset..offset + bs]);
+                for (block, iv) in blocks.zip(ivs.iter_mut()) {
+                    xor(iv, block);
+                }
+                self.cipher.encrypt_blocks(&mut ivs);
+                let blocks = group.chunks_mut(sector_size)
+                    .map(|sector| &mut sector[offset..offset + bs]);
+                for (block, iv) in blocks.zip(ivs.iter()) {
+                    block.copy_from_slice(iv);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Decrypt consecutive sectors of size `sector_size` in-place, starting
+    /// with sector number `first_sector`.
+    ///
+    /// Returns `Err(BlockModeError)` without processing data if sector size
+    /// is zero or not a multiple of block size, or if length of the data is
+    /// not a multiple of sector size.
+    pub fn decrypt_area(
+        &mut self, data: &mut [u8], sector_size: usize, first_sector: u64,
+    ) -> Result<(), BlockModeError> {
+        self.check_area(data, sector_size)?;
+        let sectors = data.chunks_mut(sector_size);
+        for (i, sector) in sectors.enumerate() {
+            let sector_num = first_sector.wrapping_add(i as u64);
+            self.decrypt_sector(sector, sector_num)?;
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn block_size(&self) -> usize {
+        C::BlockSize::to_usize()
+    }
+
+    fn check_area(&self, data: &[u8], sector_size: usize)
+        -> Result<(), BlockModeError>
+    {
+        let bs = self.block_size();
+        if sector_size == 0 || sector_size % bs != 0
+            || data.len() % sector_size != 0
+        {
+            Err(BlockModeError)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl<C, I> Essiv<C, I>
+    where C: BlockCipher, I: BlockEncrypt<BlockSize = C::BlockSize>
+{
+    /// Create new ESSIV instance from key with variable size, IV cipher is
+    /// initialized with the hash of the key computed using digest `D`.
+    pub fn new_with_digest<D: Digest>(key: &[u8])
+        -> Result<Self, InvalidKeyLength>
+    {
+        let cipher = C::new_varkey(key)?;
+        let iv_cipher = I::new_varkey(&D::digest(key))?;
+        Ok(Self::new(cipher, iv_cipher))
+    }
+}
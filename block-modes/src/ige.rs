@@ -0,0 +1,59 @@
+use block_cipher_trait::{BlockCipherMut, BlockEncryptMut};
+use block_cipher_trait::generic_array::{GenericArray, ArrayLength};
+use block_cipher_trait::generic_array::typenum::{Sum, Unsigned};
+use core::ops::Add;
+use utils::{Block, xor};
+use BlockMode;
+
+type IgeIvSize<C> =
+    Sum<<C as BlockEncryptMut>::BlockSize, <C as BlockEncryptMut>::BlockSize>;
+
+/// [Infinite Garble Extension][1] (IGE) block cipher mode instance.
+///
+/// IV has size of two blocks: the first half is used as the previous
+/// ciphertext block and the second half as the previous plaintext block,
+/// which is compatible with OpenSSL's `AES_ige_encrypt`.
+///
+/// [1]: https://www.links.org/files/openssl-ige.pdf
+pub struct Ige<C: BlockCipherMut> {
+    cipher: C,
+    x: Block<C>,
+    y: Block<C>,
+}
+
+impl<C: BlockCipherMut> BlockMode<C> for Ige<C>
+    where C::BlockSize: Add, IgeIvSize<C>: ArrayLength<u8>
+{
+    type IvSize = IgeIvSize<C>;
+
+    fn new(cipher: C, iv: &GenericArray<u8, IgeIvSize<C>>) -> Self {
+        let n = C::BlockSize::to_usize();
+        Self {
+            cipher,
+            x: GenericArray::clone_from_slice(&iv[..n]),
+            y: GenericArray::clone_from_slice(&iv[n..]),
+        }
+    }
+
+    fn encrypt_blocks(&mut self, blocks: &mut [Block<C>]) {
+        for block in blocks {
+            let pt = block.clone();
+            xor(block, &self.x);
+            self.cipher.encrypt_block(block);
+            xor(block, &self.y);
+            self.x = block.clone();
+            self.y = pt;
+        }
+    }
+
+    fn decrypt_blocks(&mut self, blocks: &mut [Block<C>]) {
+        for block in blocks {
+            let ct = block.clone();
+            xor(block, &self.y);
+            self.cipher.decrypt_block(block);
+            xor(block, &self.x);
+            self.x = ct;
+            self.y = block.clone();
+        }
+    }
+}
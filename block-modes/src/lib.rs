@@ -0,0 +1,144 @@
+//! This crate provides generic implementations of block cipher modes of
+//! operation over any type which implements `BlockCipherMut` trait, which
+//! includes every `BlockCipher`. Modes which use cipher only in the forward
+//! direction (CFB, CFB8 and OFB) require only the `BlockEncryptMut` trait.
+//!
+//! Modes keep chaining state between calls, so a message can be processed
+//! by several consecutive calls to `encrypt_blocks` or `decrypt_blocks`.
+//! Where mode allows it (ECB, CBC and CFB decryption) blocks are processed
+//! in batches of `BlockEncryptMut::ParBlocks` using `encrypt_blocks` or
+//! `decrypt_blocks` methods of the underlying cipher.
+//!
+//! Messages which length is not a multiple of block size can be processed
+//! with `encrypt_pad` and `decrypt_pad` methods using one of the padding
+//! schemes from the re-exported `block_padding` crate.
+//!
+//! Disk encryption modes `Xts` and `Essiv` do not implement `BlockMode`
+//! trait, since they encrypt every sector independently using sector number
+//! instead of IV. Their constructors from keys require `BlockCipher`.
+//!
+//! `Lrw` and `Xex` turn block cipher into a tweakable one, they implement
+//! `TweakableBlockCipher` trait and process single blocks. Since methods of
+//! this trait take `&self`, they require `BlockCipher` instead of
+//! `BlockCipherMut`.
+#![no_std]
+pub extern crate block_cipher_trait;
+pub extern crate block_padding;
+pub extern crate digest;
+extern crate dbl;
+
+use block_cipher_trait::{BlockEncrypt, BlockEncryptMut};
+use block_padding::Padding;
+use block_cipher_trait::generic_array::{GenericArray, ArrayLength};
+use block_cipher_trait::generic_array::typenum::Unsigned;
+
+mod utils;
+mod ecb;
+mod cbc;
+mod pcbc;
+mod cfb;
+mod cfb8;
+mod ofb;
+mod ige;
+mod xts;
+mod essiv;
+mod lrw;
+mod xex;
+
+pub use block_cipher_trait::InvalidKeyLength;
+pub use ecb::Ecb;
+pub use cbc::Cbc;
+pub use pcbc::Pcbc;
+pub use cfb::Cfb;
+pub use cfb8::Cfb8;
+pub use ofb::Ofb;
+pub use ige::Ige;
+pub use xts::Xts;
+pub use essiv::Essiv;
+pub use lrw::Lrw;
+pub use xex::Xex;
+
+use utils::{Block, to_blocks};
+
+/// Error which signals that buffer length is not a multiple of block size,
+/// that buffer is too small for padding or that padding is malformed
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct BlockModeError;
+
+/// The trait which defines encryption and decryption of a sequence of blocks
+/// using block cipher mode of operation.
+pub trait BlockMode<C: BlockEncryptMut>: core::marker::Sized {
+    /// Size of the initialization vector in bytes
+    type IvSize: ArrayLength<u8>;
+
+    /// Create new block mode instance from initialized block cipher and IV.
+    fn new(cipher: C, iv: &GenericArray<u8, Self::IvSize>) -> Self;
+
+    /// Create new block mode instance from key with variable size and IV.
+    fn new_varkey(key: &[u8], iv: &GenericArray<u8, Self::IvSize>)
+        -> Result<Self, InvalidKeyLength>
+        where C: BlockEncrypt
+    {
+        C::new_varkey(key).map(|cipher| Self::new(cipher, iv))
+    }
+
+    /// Encrypt blocks in-place, updating chaining state of the mode.
+    fn encrypt_blocks(&mut self, blocks: &mut [Block<C>]);
+
+    /// Decrypt blocks in-place, updating chaining state of the mode.
+    fn decrypt_blocks(&mut self, blocks: &mut [Block<C>]);
+
+    /// Encrypt message in-place.
+    ///
+    /// Returns `Err(BlockModeError)` without processing data if length of
+    /// the buffer is not a multiple of block size.
+    fn encrypt_nopad(&mut self, buffer: &mut [u8])
+        -> Result<(), BlockModeError>
+    {
+        if buffer.len() % C::BlockSize::to_usize() != 0 {
+            return Err(BlockModeError);
+        }
+        self.encrypt_blocks(to_blocks(buffer));
+        Ok(())
+    }
+
+    /// Decrypt message in-place.
+    ///
+    /// Returns `Err(BlockModeError)` without processing data if length of
+    /// the buffer is not a multiple of block size.
+    fn decrypt_nopad(&mut self, buffer: &mut [u8])
+        -> Result<(), BlockModeError>
+    {
+        if buffer.len() % C::BlockSize::to_usize() != 0 {
+            return Err(BlockModeError);
+        }
+        self.decrypt_blocks(to_blocks(buffer));
+        Ok(())
+    }
+
+    /// Pad message stored in the first `pos` bytes of the buffer using
+    /// padding scheme `P` and encrypt it in-place.
+    ///
+    /// Returns slice of the buffer which contains ciphertext or
+    /// `Err(BlockModeError)` if buffer is too small for the padded message.
+    fn encrypt_pad<'a, P: Padding>(&mut self, buffer: &'a mut [u8], pos: usize)
+        -> Result<&'a [u8], BlockModeError>
+    {
+        let buf = P::pad::<C::BlockSize>(buffer, pos)
+            .map_err(|_| BlockModeError)?;
+        self.encrypt_blocks(to_blocks(buf));
+        Ok(buf)
+    }
+
+    /// Decrypt message in-place and remove padding added by scheme `P`.
+    ///
+    /// Returns slice of the buffer which contains plaintext or
+    /// `Err(BlockModeError)` if buffer length is not a multiple of block size
+    /// or padding is malformed.
+    fn decrypt_pad<'a, P: Padding>(&mut self, buffer: &'a mut [u8])
+        -> Result<&'a [u8], BlockModeError>
+    {
+        self.decrypt_nopad(buffer)?;
+        P::unpad::<C::BlockSize>(buffer).map_err(|_| BlockModeError)
+    }
+}
@@ -0,0 +1,82 @@
+use block_cipher_trait::{BlockCipher, BlockEncrypt, TweakableBlockCipher};
+use block_cipher_trait::generic_array::{ArrayLength, GenericArray};
+use block_cipher_trait::generic_array::typenum::{Sum, Unsigned};
+use core::ops::Add;
+use dbl::Dbl;
+use utils::{Block, xor};
+
+type LrwKeySize<C> =
+    Sum<<C as BlockEncrypt>::KeySize, <C as BlockEncrypt>::BlockSize>;
+
+/// [Liskov, Rivest and Wagner][1] (LRW) tweakable block cipher over block
+/// cipher `C`.
+///
+/// Block is encrypted as `E(K1, P xor T*K2) xor T*K2`, where `K2` has size
+/// of one block and multiplication is performed in GF(2^n) with blocks
+/// interpreted as big endian numbers, which is compatible with LRW-AES from
+/// IEEE P1619 drafts and the `lrw` template of the Linux kernel. Key is the
+/// concatenation of `K1` and `K2`.
+///
+/// Tweak has size of one block and is usually a big endian block index.
+///
+/// [1]: https://en.wikipedia.org/wiki/Disk_encryption_theory#Liskov,_Rivest,_and_Wagner_(LRW)
+pub struct Lrw<C: BlockCipher> {
+    cipher: C,
+    k2: Block<C>,
+}
+
+impl<C: BlockCipher> Lrw<C> where Block<C>: Dbl {
+    /// Create new LRW instance from block cipher initialized with `K1` and
+    /// tweak key `K2`.
+    pub fn from_cipher(cipher: C, k2: &Block<C>) -> Self {
+        Self { cipher, k2: k2.clone() }
+    }
+
+    /// Compute `T*K2` in constant time.
+    fn mask(&self, tweak: &Block<C>) -> Block<C> {
+        let mut acc = Block::<C>::default();
+        for byte in tweak.iter() {
+            for i in (0..8).rev() {
+                acc = acc.dbl();
+                let bit = ((byte >> i) & 1).wrapping_neg();
+                for (a, k) in acc.iter_mut().zip(self.k2.iter()) {
+                    *a ^= k & bit;
+                }
+            }
+        }
+        acc
+    }
+}
+
+impl<C> TweakableBlockCipher for Lrw<C>
+    where C: BlockCipher, Block<C>: Dbl,
+        C::KeySize: Add<C::BlockSize>, LrwKeySize<C>: ArrayLength<u8>,
+{
+    type KeySize = LrwKeySize<C>;
+    type BlockSize = C::BlockSize;
+    type TweakSize = C::BlockSize;
+
+    fn new(key: &GenericArray<u8, LrwKeySize<C>>) -> Self {
+        let (k1, k2) = key.split_at(C::KeySize::to_usize());
+        Self::from_cipher(C::new(GenericArray::from_slice(k1)),
+            GenericArray::from_slice(k2))
+    }
+
+    fn encrypt_block_with_tweak(&self, tweak: &Block<C>,
+        block: &mut Block<C>)
+    {
+        let mask = self.mask(tweak);
+        xor(block, &mask);
+        self.cipher.encrypt_block(block);
+        xor(block, &mask);
+    }
+
+    fn decrypt_block_with_tweak(&self, tweak: &Block<C>,
+        block: &mut Block<C>)
+    {
+        let mask = self.mask(tweak);
+        xor(block, &mask);
+        self.cipher.decrypt_block(block);
+        xor(block, &mask);
+    }
+}
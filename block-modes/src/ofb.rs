@@ -0,0 +1,32 @@
+use block_cipher_trait::BlockEncryptMut;
+use utils::{Block, xor};
+use BlockMode;
+
+/// [Output feedback][1] (OFB) block cipher mode instance.
+///
+/// Encryption and decryption in this mode are the same operation.
+///
+/// [1]: https://en.wikipedia.org/wiki/Block_cipher_mode_of_operation#OFB
+pub struct Ofb<C: BlockEncryptMut> {
+    cipher: C,
+    iv: Block<C>,
+}
+
+impl<C: BlockEncryptMut> BlockMode<C> for Ofb<C> {
+    type IvSize = C::BlockSize;
+
+    fn new(cipher: C, iv: &Block<C>) -> Self {
+        Self { cipher, iv: iv.clone() }
+    }
+
+    fn encrypt_blocks(&mut self, blocks: &mut [Block<C>]) {
+        for block in blocks {
+            self.cipher.encrypt_block(&mut self.iv);
+            xor(block, &self.iv);
+        }
+    }
+
+    fn decrypt_blocks(&mut self, blocks: &mut [Block<C>]) {
+        self.encrypt_blocks(blocks)
+    }
+}
@@ -0,0 +1,39 @@
+use block_cipher_trait::BlockCipherMut;
+use utils::{Block, xor};
+use BlockMode;
+
+/// [Propagating Cipher Block Chaining][1] (PCBC) block cipher mode instance.
+///
+/// [1]: https://en.wikipedia.org/wiki/Block_cipher_mode_of_operation#PCBC
+pub struct Pcbc<C: BlockCipherMut> {
+    cipher: C,
+    iv: Block<C>,
+}
+
+impl<C: BlockCipherMut> BlockMode<C> for Pcbc<C> {
+    type IvSize = C::BlockSize;
+
+    fn new(cipher: C, iv: &Block<C>) -> Self {
+        Self { cipher, iv: iv.clone() }
+    }
+
+    fn encrypt_blocks(&mut self, blocks: &mut [Block<C>]) {
+        for block in blocks {
+            let pt = block.clone();
+            xor(block, &self.iv);
+            self.cipher.encrypt_block(block);
+            self.iv = pt;
+            xor(&mut self.iv, block);
+        }
+    }
+
+    fn decrypt_blocks(&mut self, blocks: &mut [Block<C>]) {
+        for block in blocks {
+            let ct = block.clone();
+            self.cipher.decrypt_block(block);
+            xor(block, &self.iv);
+            self.iv = ct;
+            xor(&mut self.iv, block);
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use block_cipher_trait::BlockEncryptMut;
+use block_cipher_trait::generic_array::{GenericArray, ArrayLength};
+use block_cipher_trait::generic_array::typenum::Unsigned;
+use core::slice;
+
+pub type Block<C> = GenericArray<u8, <C as BlockEncryptMut>::BlockSize>;
+pub type ParBlocks<C> = 
+    GenericArray<Block<C>, <C as BlockEncryptMut>::ParBlocks>;
+
+#[inline(always)]
+pub fn xor(buf: &mut [u8], key: &[u8]) {
+    debug_assert_eq!(buf.len(), key.len());
+    for (a, b) in buf.iter_mut().zip(key) {
+        *a ^= *b;
+    }
+}
+
+/// Reinterpret byte slice as a slice of blocks. Length of the `data` must be
+/// a multiple of `N`.
+pub fn to_blocks<N>(data: &mut [u8]) -> &mut [GenericArray<u8, N>]
+    where N: ArrayLength<u8>
+{
+    let n = N::to_usize();
+    debug_assert!(data.len() % n == 0);
+    // `GenericArray<u8, N>` has the same layout as `[u8; N]` and alignment 1
+    unsafe {
+        slice::from_raw_parts_mut(
+            data.as_mut_ptr() as *mut GenericArray<u8, N>,
+            data.len() / n,
+        )
+    }
+}
+
+/// Number of blocks processed by `encrypt_blocks` and `decrypt_blocks`
+/// methods of the cipher.
+#[inline(always)]
+pub fn par_blocks<C: BlockEncryptMut>() -> usize {
+    C::ParBlocks::to_usize()
+}
@@ -0,0 +1,76 @@
+use block_cipher_trait::{BlockCipher, BlockEncrypt, TweakableBlockCipher};
+use block_cipher_trait::generic_array::{ArrayLength, GenericArray};
+use block_cipher_trait::generic_array::typenum::{Sum, Unsigned};
+use core::ops::Add;
+use utils::{Block, xor};
+
+type XexKeySize<C, T> =
+    Sum<<C as BlockEncrypt>::KeySize, <T as BlockEncrypt>::KeySize>;
+
+/// [Xor-encrypt-xor][1] (XEX) tweakable block cipher over block cipher `C`
+/// with separate data and tweak keys. Tweak cipher `T` is used only for
+/// encryption and defaults to `C`.
+///
+/// Block is encrypted as `E(K1, P xor D) xor D`, where `D = E(K2, T)`. Key
+/// is the concatenation of `K1` and `K2`.
+///
+/// Tweak has size of one block. With tweak equal to the encoded sector
+/// number result is equal to the first block of the sector encrypted by
+/// `Xts`, which also processes subsequent blocks and partial blocks.
+///
+/// [1]: https://en.wikipedia.org/wiki/Disk_encryption_theory#Xor%E2%80%93encrypt%E2%80%93xor_(XEX)
+pub struct Xex<C, T = C>
+    where C: BlockCipher, T: BlockEncrypt<BlockSize = C::BlockSize>
+{
+    cipher: C,
+    tweak_cipher: T,
+}
+
+impl<C, T> Xex<C, T>
+    where C: BlockCipher, T: BlockEncrypt<BlockSize = C::BlockSize>
+{
+    /// Create new XEX instance from data and tweak block cipher instances.
+    pub fn from_ciphers(cipher: C, tweak_cipher: T) -> Self {
+        Self { cipher, tweak_cipher }
+    }
+
+    #[inline(always)]
+    fn mask(&self, tweak: &Block<C>) -> Block<C> {
+        let mut mask = tweak.clone();
+        self.tweak_cipher.encrypt_block(&mut mask);
+        mask
+    }
+}
+
+impl<C, T> TweakableBlockCipher for Xex<C, T>
+    where C: BlockCipher, T: BlockEncrypt<BlockSize = C::BlockSize>,
+        C::KeySize: Add<T::KeySize>, XexKeySize<C, T>: ArrayLength<u8>,
+{
+    type KeySize = XexKeySize<C, T>;
+    type BlockSize = C::BlockSize;
+    type TweakSize = C::BlockSize;
+
+    fn new(key: &GenericArray<u8, XexKeySize<C, T>>) -> Self {
+        let (k1, k2) = key.split_at(C::KeySize::to_usize());
+        Self::from_ciphers(C::new(GenericArray::from_slice(k1)),
+            T::new(GenericArray::from_slice(k2)))
+    }
+
+    fn encrypt_block_with_tweak(&self, tweak: &Block<C>,
+        block: &mut Block<C>)
+    {
+        let mask = self.mask(tweak);
+        xor(block, &mask);
+        self.cipher.encrypt_block(block);
+        xor(block, &mask);
+    }
+
+    fn decrypt_block_with_tweak(&self, tweak: &Block<C>,
+        block: &mut Block<C>)
+    {
+        let mask = self.mask(tweak);
+        xor(block, &mask);
+        self.cipher.decrypt_block(block);
+        xor(block, &mask);
+    }
+}
@@ -0,0 +1,227 @@
+use block_cipher_trait::{BlockCipher, BlockCipherMut, BlockEncryptMut};
+use block_cipher_trait::generic_array::GenericArray;
+use block_cipher_trait::generic_array::typenum::U16;
+use utils::{ParBlocks, par_blocks, to_blocks, xor};
+use {BlockModeError, InvalidKeyLength};
+
+type Block = GenericArray<u8, U16>;
+
+/// [XEX-based tweaked-codebook mode with ciphertext stealing][1] (XTS)
+/// instance as defined in the IEEE 1619.
+///
+/// Sector is encrypted independently using tweak derived from the sector
+/// number. Sectors which length is not a multiple of block size are
+/// processed using ciphertext stealing, the minimal sector size is one
+/// block. Blocks of a sector are processed in batches of `C::ParBlocks`.
+///
+/// Sector number is encoded as a little endian integer, which is compatible
+/// with the `plain64` IV mode of dm-crypt.
+///
+/// Tweak cipher `T` is used only for encryption and defaults to the data
+/// cipher `C`.
+///
+/// [1]: https://en.wikipedia.org/wiki/Disk_encryption_theory#XTS
+pub struct Xts<C, T = C>
+    where C: BlockCipherMut<BlockSize = U16>,
+        T: BlockEncryptMut<BlockSize = U16>
+{
+    cipher: C,
+    tweak_cipher: T,
+}
+
+impl<C, T> Xts<C, T>
+    where C: BlockCipherMut<BlockSize = U16>,
+        T: BlockEncryptMut<BlockSize = U16>
+{
+    /// Create new XTS instance from data and tweak block cipher instances.
+    pub fn new(cipher: C, tweak_cipher: T) -> Self {
+        Self { cipher, tweak_cipher }
+    }
+
+    /// Encrypt sector in-place.
+    ///
+    /// Returns `Err(BlockModeError)` if sector is smaller than one block.
+    pub fn encrypt_sector(&mut self, sector: &mut [u8], sector_num: u64)
+        -> Result<(), BlockModeError>
+    {
+        self.crypt_sector(sector, &sector_tweak(sector_num), false)
+    }
+
+    /// Decrypt sector in-place.
+    ///
+    /// Returns `Err(BlockModeError)` if sector is smaller than one block.
+    pub fn decrypt_sector(&mut self, sector: &mut [u8], sector_num: u64)
+        -> Result<(), BlockModeError>
+    {
+        self.crypt_sector(sector, &sector_tweak(sector_num), true)
+    }
+
+    /// Encrypt sector in-place using raw (not encrypted) tweak value.
+    pub fn encrypt_sector_with_tweak(
+        &mut self, sector: &mut [u8], tweak: &Block,
+    ) -> Result<(), BlockModeError>
+    {
+        self.crypt_sector(sector, tweak, false)
+    }
+
+    /// Decrypt sector in-place using raw (not encrypted) tweak value.
+    pub fn decrypt_sector_with_tweak(
+        &mut self, sector: &mut [u8], tweak: &Block,
+    ) -> Result<(), BlockModeError>
+    {
+        self.crypt_sector(sector, tweak, true)
+    }
+
+    /// Encrypt consecutive sectors of size `sector_size` in-place, starting
+    /// with sector number `first_sector`.
+    ///
+    /// Returns `Err(BlockModeError)` without processing data if sector size
+    /// is smaller than one block or if length of the data is not a multiple
+    /// of sector size.
+    pub fn encrypt_area(
+        &mut self, data: &mut [u8], sector_size: usize, first_sector: u64,
+    ) -> Result<(), BlockModeError> {
+        self.crypt_area(data, sector_size, first_sector, false)
+    }
+
+    /// Decrypt consecutive sectors of size `sector_size` in-place, starting
+    /// with sector number `first_sector`.
+    ///
+    /// Returns `Err(BlockModeError)` without processing data if sector size
+    /// is smaller than one block or if length of the data is not a multiple
+    /// of sector size.
+    pub fn decrypt_area(
+        &mut self, data: &mut [u8], sector_size: usize, first_sector: u64,
+    ) -> Result<(), BlockModeError> {
+        self.crypt_area(data, sector_size, first_sector, true)
+    }
+
+    fn crypt_area(
+        &mut self, data: &mut [u8], sector_size: usize, first_sector: u64,
+        decrypt: bool,
+    ) -> Result<(), BlockModeError> {
+        if sector_size < 16 || data.len() % sector_size != 0 {
+            return Err(BlockModeError);
+        }
+        let sectors = data.chunks_mut(sector_size);
+        for (i, sector) in sectors.enumerate() {
+            let tweak = sector_tweak(first_sector.wrapping_add(i as u64));
+            self.crypt_sector(sector, &tweak, decrypt)?;
+        }
+        Ok(())
+    }
+
+    fn crypt_sector(
+        &mut self, sector: &mut [u8], tweak: &Block, decrypt: bool,
+    ) -> Result<(), BlockModeError>
+    {
+        if sector.len() < 16 {
+            return Err(BlockModeError);
+        }
+        let mut t = *tweak;
+        self.tweak_cipher.encrypt_block(&mut t);
+        let mut t = u128::from_le_bytes(to_array(&t));
+
+        let rem = sector.len() % 16;
+        let n = sector.len() / 16 - if rem == 0 { 0 } else { 1 };
+        let (head, tail) = sector.split_at_mut(16 * n);
+        self.crypt_blocks(to_blocks(head), &mut t, decrypt);
+        if rem == 0 {
+            return Ok(());
+        }
+
+        // ciphertext stealing, for decryption the last full block is
+        // processed with the tweak of the partial block
+        let (last, partial) = tail.split_at_mut(16);
+        let (t1, t2) = if decrypt { (mul_alpha(t), t) } else { (t, mul_alpha(t)) };
+        let mut block = Block::clone_from_slice(last);
+        self.crypt_block(&mut block, t1, decrypt);
+        let mut stolen = block;
+        stolen[..rem].copy_from_slice(partial);
+        partial.copy_from_slice(&block[..rem]);
+        self.crypt_block(&mut stolen, t2, decrypt);
+        last.copy_from_slice(&stolen);
+        Ok(())
+    }
+
+    /// Process full blocks starting with tweak `t`, after processing `t`
+    /// contains tweak for the next block.
+    fn crypt_blocks(
+        &mut self, blocks: &mut [Block], t: &mut u128, decrypt: bool,
+    ) {
+        let pb = par_blocks::<C>();
+        let mut tweaks = ParBlocks::<C>::default();
+        for chunk in blocks.chunks_mut(pb) {
+            if chunk.len() == pb {
+                for (block, tweak) in chunk.iter_mut().zip(tweaks.iter_mut()) {
+                    tweak.copy_from_slice(&t.to_le_bytes());
+                    xor(block, tweak);
+                    *t = mul_alpha(*t);
+                }
+                let chunk = ParBlocks::<C>::from_mut_slice(chunk);
+                if decrypt {
+                    self.cipher.decrypt_blocks(chunk);
+                } else {
+                    self.cipher.encrypt_blocks(chunk);
+                }
+                for (block, tweak) in chunk.iter_mut().zip(tweaks.iter()) {
+                    xor(block, tweak);
+                }
+            } else {
+                for block in chunk {
+                    self.crypt_block(block, *t, decrypt);
+                    *t = mul_alpha(*t);
+                }
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn crypt_block(&mut self, block: &mut Block, t: u128, decrypt: bool) {
+        let t = t.to_le_bytes();
+        xor(block, &t);
+        if decrypt {
+            self.cipher.decrypt_block(block);
+        } else {
+            self.cipher.encrypt_block(block);
+        }
+        xor(block, &t);
+    }
+}
+
+impl<C> Xts<C> where C: BlockCipher<BlockSize = U16> {
+    /// Create new XTS instance from key with variable size.
+    ///
+    /// The first half of the key is used for data cipher and the second one
+    /// for tweak cipher, e.g. XTS-AES-128 uses 32 byte keys. Instances with
+    /// different data and tweak ciphers have to be created using `new`.
+    pub fn new_varkey(key: &[u8]) -> Result<Self, InvalidKeyLength> {
+        if key.len() % 2 != 0 {
+            return Err(InvalidKeyLength);
+        }
+        let (k1, k2) = key.split_at(key.len() / 2);
+        Ok(Self::new(C::new_varkey(k1)?, C::new_varkey(k2)?))
+    }
+}
+
+/// Tweak value for the sector number.
+#[inline(always)]
+fn sector_tweak(sector_num: u64) -> Block {
+    let mut tweak = Block::default();
+    tweak[..8].copy_from_slice(&sector_num.to_le_bytes());
+    tweak
+}
+
+/// Multiplication by the primitive element in GF(2^128) with little endian
+/// bit order.
+#[inline(always)]
+fn mul_alpha(t: u128) -> u128 {
+    (t << 1) ^ ((t >> 127).wrapping_neg() & 0x87)
+}
+
+#[inline(always)]
+fn to_array(block: &Block) -> [u8; 16] {
+    let mut buf = [0u8; 16];
+    buf.copy_from_slice(block);
+    buf
+}
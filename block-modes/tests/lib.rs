@@ -0,0 +1,377 @@
+extern crate aes;
+#[macro_use(impl_cipher, new_tweak_test)]
+extern crate block_cipher_trait;
+extern crate block_modes;
+extern crate sha2;
+
+use block_cipher_trait::dev::decode_hex;
+use block_cipher_trait::generic_array::GenericArray;
+use block_cipher_trait::BlockEncrypt;
+use block_cipher_trait::generic_array::typenum::{U1, U16, U32};
+use block_modes::{
+    BlockMode, Cbc, Cfb, Cfb8, Ecb, Essiv, Ige, Lrw, Ofb, Pcbc, Xex, Xts,
+};
+use sha2::{Digest, Sha256};
+
+impl_cipher!(Aes128, aes::Aes128, U16, U16);
+impl_cipher!(Aes128Seq, aes::Aes128, U16, U16, U1);
+impl_cipher!(Aes256, aes::Aes256, U32, U16);
+
+// NIST SP 800-38A, Appendix F
+const KEY: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+const IV: &str = "000102030405060708090a0b0c0d0e0f";
+const PLAINTEXT: &str = "\
+    6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+    30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710";
+
+/// Check encryption and decryption of the message both with a single call
+/// and block by block, which tests that chaining state is kept between
+/// calls.
+fn check<M: BlockMode<Aes128>>(key: &str, iv: &str, pt: &str, ct: &str) {
+    let mut buf = [0u8; 64];
+    let key = decode_hex(key, &mut buf).to_vec();
+    let iv = decode_hex(iv, &mut buf).to_vec();
+    let iv = GenericArray::from_slice(&iv);
+    let pt = decode_hex(pt, &mut buf).to_vec();
+    let ct = decode_hex(ct, &mut buf).to_vec();
+
+    let mut data = pt.clone();
+    let mut mode = M::new_varkey(&key, iv).unwrap();
+    mode.encrypt_nopad(&mut data).unwrap();
+    assert_eq!(data, ct);
+    let mut mode = M::new_varkey(&key, iv).unwrap();
+    mode.decrypt_nopad(&mut data).unwrap();
+    assert_eq!(data, pt);
+
+    let mut enc = M::new_varkey(&key, iv).unwrap();
+    let mut dec = M::new_varkey(&key, iv).unwrap();
+    for (block, ct_block) in data.chunks_mut(16).zip(ct.chunks(16)) {
+        enc.encrypt_nopad(block).unwrap();
+        assert_eq!(block, ct_block);
+        dec.decrypt_nopad(block).unwrap();
+    }
+    assert_eq!(data, pt);
+}
+
+#[test]
+fn ecb_aes128() {
+    let ct = "\
+        3ad77bb40d7a3660a89ecaf32466ef97f5d3d58503b9699de785895a96fdbaaf\
+        43b1cd7f598ece23881b00e3ed0306887b0c785e27e8ad3f8223207104725dd4";
+    check::<Ecb<Aes128>>(KEY, "", PLAINTEXT, ct);
+}
+
+#[test]
+fn cbc_aes128() {
+    let ct = "\
+        7649abac8119b246cee98e9b12e9197d5086cb9b507219ee95db113a917678b2\
+        73bed6b8e3c1743b7116e69e222295163ff1caa1681fac09120eca307586e1a7";
+    check::<Cbc<Aes128>>(KEY, IV, PLAINTEXT, ct);
+}
+
+#[test]
+fn cfb_aes128() {
+    let ct = "\
+        3b3fd92eb72dad20333449f8e83cfb4ac8a64537a0b3a93fcde3cdad9f1ce58b\
+        26751f67a3cbb140b1808cf187a4f4dfc04b05357c5d1c0eeac4c66f9ff7f2e6";
+    check::<Cfb<Aes128>>(KEY, IV, PLAINTEXT, ct);
+}
+
+#[test]
+fn cfb8_aes128() {
+    // the first 16 bytes of the 18 byte message from F.3.7
+    let pt = &PLAINTEXT[..32];
+    let ct = "3b79424c9c0dd436bace9e0ed4586a4f";
+    check::<Cfb8<Aes128>>(KEY, IV, pt, ct);
+}
+
+#[test]
+fn ofb_aes128() {
+    let ct = "\
+        3b3fd92eb72dad20333449f8e83cfb4a7789508d16918f03f53c52dac54ed825\
+        9740051e9c5fecf64344f7a82260edcc304c6528f659c77866a510d9c1d6ae5e";
+    check::<Ofb<Aes128>>(KEY, IV, PLAINTEXT, ct);
+}
+
+/// Test vector from the OpenSSL `igetest`
+#[test]
+fn ige_aes128() {
+    let key = "000102030405060708090a0b0c0d0e0f";
+    let iv = "\
+        000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+    let pt = "\
+        0000000000000000000000000000000000000000000000000000000000000000";
+    let ct = "\
+        1a8519a6557be652e9da8e43da4ef4453cf456b4ca488aa383c79c98b34797cb";
+    check::<Ige<Aes128>>(key, iv, pt, ct);
+}
+
+#[test]
+fn pcbc_aes128_roundtrip() {
+    let mut buf = [0u8; 64];
+    let key = decode_hex(KEY, &mut buf).to_vec();
+    let iv = decode_hex(IV, &mut buf).to_vec();
+    let iv = GenericArray::from_slice(&iv);
+    let pt = decode_hex(PLAINTEXT, &mut buf).to_vec();
+
+    let mut data = pt.clone();
+    let mut mode = Pcbc::<Aes128>::new_varkey(&key, iv).unwrap();
+    mode.encrypt_nopad(&mut data).unwrap();
+    let ct = data.clone();
+    // the first block is encrypted exactly as in CBC
+    let mut cbc = pt[..16].to_vec();
+    Cbc::<Aes128>::new_varkey(&key, iv).unwrap()
+        .encrypt_nopad(&mut cbc).unwrap();
+    assert_eq!(ct[..16], cbc[..]);
+
+    let mut mode = Pcbc::<Aes128>::new_varkey(&key, iv).unwrap();
+    mode.decrypt_nopad(&mut data).unwrap();
+    assert_eq!(data, pt);
+
+    let mut enc = Pcbc::<Aes128>::new_varkey(&key, iv).unwrap();
+    for block in data.chunks_mut(16) {
+        enc.encrypt_nopad(block).unwrap();
+    }
+    assert_eq!(data, ct);
+    let mut dec = Pcbc::<Aes128>::new_varkey(&key, iv).unwrap();
+    for block in data.chunks_mut(16) {
+        dec.decrypt_nopad(block).unwrap();
+    }
+    assert_eq!(data, pt);
+}
+
+/// Check that processing of blocks in batches of `ParBlocks` gives the same
+/// result as processing of blocks one by one.
+macro_rules! par_blocks_test {
+    ($name:ident, $mode:ident) => {
+        #[test]
+        fn $name() {
+            let key = [0x42; 16];
+            let iv = Default::default();
+            let mut pt = [0u8; 19*16];
+            for (i, b) in pt.iter_mut().enumerate() { *b = i as u8; }
+
+            let mut ct1 = pt;
+            $mode::<Aes128>::new_varkey(&key, &iv).unwrap()
+                .encrypt_nopad(&mut ct1).unwrap();
+            let mut ct2 = pt;
+            $mode::<Aes128Seq>::new_varkey(&key, &iv).unwrap()
+                .encrypt_nopad(&mut ct2).unwrap();
+            assert_eq!(ct1[..], ct2[..]);
+
+            $mode::<Aes128>::new_varkey(&key, &iv).unwrap()
+                .decrypt_nopad(&mut ct1).unwrap();
+            assert_eq!(ct1[..], pt[..]);
+            $mode::<Aes128Seq>::new_varkey(&key, &iv).unwrap()
+                .decrypt_nopad(&mut ct2).unwrap();
+            assert_eq!(ct2[..], pt[..]);
+        }
+    }
+}
+
+par_blocks_test!(ecb_par_blocks, Ecb);
+par_blocks_test!(cbc_par_blocks, Cbc);
+par_blocks_test!(pcbc_par_blocks, Pcbc);
+par_blocks_test!(cfb_par_blocks, Cfb);
+par_blocks_test!(cfb8_par_blocks, Cfb8);
+par_blocks_test!(ofb_par_blocks, Ofb);
+
+struct SectorTest {
+    key: &'static str,
+    sector: u64,
+    pt: &'static str,
+    ct: &'static str,
+}
+
+/// XTS-AES-128 vectors 1-3 and 15-18 from the IEEE 1619-2007, the last four
+/// vectors cover ciphertext stealing.
+const XTS_AES128: &[SectorTest] = &[
+    SectorTest {
+        key: "\
+            0000000000000000000000000000000000000000000000000000000000000000",
+        sector: 0,
+        pt: "\
+            0000000000000000000000000000000000000000000000000000000000000000",
+        ct: "\
+            917cf69ebd68b2ec9b9fe9a3eadda692cd43d2f59598ed858c02c2652fbf922e",
+    },
+    SectorTest {
+        key: "\
+            1111111111111111111111111111111122222222222222222222222222222222",
+        sector: 0x3333333333,
+        pt: "\
+            4444444444444444444444444444444444444444444444444444444444444444",
+        ct: "\
+            c454185e6a16936e39334038acef838bfb186fff7480adc4289382ecd6d394f0",
+    },
+    SectorTest {
+        key: "\
+            fffefdfcfbfaf9f8f7f6f5f4f3f2f1f022222222222222222222222222222222",
+        sector: 0x3333333333,
+        pt: "\
+            4444444444444444444444444444444444444444444444444444444444444444",
+        ct: "\
+            af85336b597afc1a900b2eb21ec949d292df4c047e0b21532186a5971a227a89",
+    },
+    SectorTest {
+        key: "\
+            fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0bfbebdbcbbbab9b8b7b6b5b4b3b2b1b0",
+        sector: 0x123456789a,
+        pt: "000102030405060708090a0b0c0d0e0f10",
+        ct: "6c1625db4671522d3d7599601de7ca09ed",
+    },
+    SectorTest {
+        key: "\
+            fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0bfbebdbcbbbab9b8b7b6b5b4b3b2b1b0",
+        sector: 0x123456789a,
+        pt: "000102030405060708090a0b0c0d0e0f1011",
+        ct: "d069444b7a7e0cab09e24447d24deb1fedbf",
+    },
+    SectorTest {
+        key: "\
+            fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0bfbebdbcbbbab9b8b7b6b5b4b3b2b1b0",
+        sector: 0x123456789a,
+        pt: "000102030405060708090a0b0c0d0e0f101112",
+        ct: "e5df1351c0544ba1350b3363cd8ef4beedbf9d",
+    },
+    SectorTest {
+        key: "\
+            fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0bfbebdbcbbbab9b8b7b6b5b4b3b2b1b0",
+        sector: 0x123456789a,
+        pt: "000102030405060708090a0b0c0d0e0f10111213",
+        ct: "9d84c813f719aa2c7be3f66171c7c5c2edbf9dac",
+    },
+];
+
+#[test]
+fn xts_aes128() {
+    for t in XTS_AES128 {
+        let mut buf = [0u8; 64];
+        let key = decode_hex(t.key, &mut buf).to_vec();
+        let pt = decode_hex(t.pt, &mut buf).to_vec();
+        let ct = decode_hex(t.ct, &mut buf).to_vec();
+
+        let mut xts = Xts::<Aes128>::new_varkey(&key).unwrap();
+        let mut data = pt.clone();
+        xts.encrypt_sector(&mut data, t.sector).unwrap();
+        assert_eq!(data, ct);
+        xts.decrypt_sector(&mut data, t.sector).unwrap();
+        assert_eq!(data, pt);
+    }
+}
+
+#[test]
+fn xts_errors() {
+    assert!(Xts::<Aes128>::new_varkey(&[0; 31]).is_err());
+    assert!(Xts::<Aes128>::new_varkey(&[0; 48]).is_err());
+    let mut xts = Xts::<Aes128>::new_varkey(&[0; 32]).unwrap();
+    assert!(xts.encrypt_sector(&mut [0; 15], 0).is_err());
+    assert!(xts.encrypt_area(&mut [0; 64], 15, 0).is_err());
+    assert!(xts.encrypt_area(&mut [0; 64], 24, 0).is_err());
+}
+
+/// Check that `encrypt_area` is equivalent to encryption of separate
+/// sectors and that the result does not depend on `ParBlocks`.
+#[test]
+fn xts_area() {
+    let key = [0x42; 32];
+    let mut xts = Xts::<Aes128>::new_varkey(&key).unwrap();
+    let mut xts_seq = Xts::<Aes128Seq>::new_varkey(&key).unwrap();
+    for &sector_size in &[16, 100, 512] {
+        let pt: Vec<u8> = (0..3 * sector_size).map(|i| i as u8).collect();
+        let mut area = pt.clone();
+        xts.encrypt_area(&mut area, sector_size, 7).unwrap();
+        let mut sectors = pt.clone();
+        for (i, sector) in sectors.chunks_mut(sector_size).enumerate() {
+            xts_seq.encrypt_sector(sector, 7 + i as u64).unwrap();
+        }
+        assert_eq!(area, sectors);
+        xts_seq.decrypt_area(&mut area, sector_size, 7).unwrap();
+        assert_eq!(area, pt);
+    }
+}
+
+/// Known answer test for the `aes-cbc-essiv:sha256` mode of dm-crypt, i.e.
+/// AES-128-CBC with IV computed by AES-256 keyed with SHA-256 of the key.
+/// Generated using the OpenSSL AES and SHA-256 implementations.
+const ESSIV_AES128_SHA256: &[SectorTest] = &[
+    SectorTest {
+        key: "000102030405060708090a0b0c0d0e0f",
+        sector: 0,
+        pt: "\
+            00070e151c232a31383f464d545b626970777e858c939aa1a8afb6bdc4cbd2d9",
+        ct: "\
+            6c63ef6998cd1b8af0008648320bfc551f7ac5248b3832c6833bc61466d02305",
+    },
+    SectorTest {
+        key: "000102030405060708090a0b0c0d0e0f",
+        sector: 1,
+        pt: "\
+            01080f161d242b323940474e555c636a71787f868d949ba2a9b0b7bec5ccd3da",
+        ct: "\
+            3ad75206d4d0c64e2de605160e7a7cd401e0ecf0d067664b1b97c158ac8a7d40",
+    },
+    SectorTest {
+        key: "000102030405060708090a0b0c0d0e0f",
+        sector: 0x123456789a,
+        pt: "\
+            9aa1a8afb6bdc4cbd2d9e0e7eef5fc030a11181f262d343b424950575e656c73",
+        ct: "\
+            329f41f6e95a91d296da71ef8076ff89d9600a1d9a90981f3a023ae09f7e65a9",
+    },
+];
+
+fn essiv_aes128_sha256(key: &[u8]) -> Essiv<Aes128, Aes256> {
+    let iv_key = Sha256::digest(key);
+    Essiv::new(
+        Aes128::new(GenericArray::from_slice(key)),
+        Aes256::new(GenericArray::from_slice(&iv_key)),
+    )
+}
+
+#[test]
+fn essiv_aes128() {
+    for t in ESSIV_AES128_SHA256 {
+        let mut buf = [0u8; 64];
+        let key = decode_hex(t.key, &mut buf).to_vec();
+        let pt = decode_hex(t.pt, &mut buf).to_vec();
+        let ct = decode_hex(t.ct, &mut buf).to_vec();
+
+        let mut essiv = essiv_aes128_sha256(&key);
+        let mut data = pt.clone();
+        essiv.encrypt_sector(&mut data, t.sector).unwrap();
+        assert_eq!(data, ct);
+        essiv.decrypt_sector(&mut data, t.sector).unwrap();
+        assert_eq!(data, pt);
+    }
+}
+
+/// `encrypt_area` encrypts groups of `ParBlocks` sectors in parallel, check
+/// that it's equivalent to encryption of separate sectors.
+#[test]
+fn essiv_area() {
+    let mut essiv = essiv_aes128_sha256(&[0x42; 16]);
+    let sector_size = 64;
+    let pt: Vec<u8> = (0..19 * sector_size).map(|i| i as u8).collect();
+    let mut area = pt.clone();
+    essiv.encrypt_area(&mut area, sector_size, 5).unwrap();
+    let mut sectors = pt.clone();
+    for (i, sector) in sectors.chunks_mut(sector_size).enumerate() {
+        essiv.encrypt_sector(sector, 5 + i as u64).unwrap();
+    }
+    assert_eq!(area, sectors);
+    essiv.decrypt_area(&mut area, sector_size, 5).unwrap();
+    assert_eq!(area, pt);
+
+    assert!(essiv.encrypt_sector(&mut [0; 24], 0).is_err());
+    assert!(essiv.encrypt_area(&mut [0; 64], 24, 0).is_err());
+}
+
+// The first two LRW-AES-128 vectors are from the IEEE P1619 drafts, the rest
+// of the vectors are generated using the OpenSSL AES implementation.
+new_tweak_test!(lrw_aes128, "lrw_aes128", Lrw<Aes128>);
+new_tweak_test!(lrw_aes256, "lrw_aes256", Lrw<Aes256>);
+// XEX vectors are the first blocks of XTS-AES sectors: vectors 1-3 from the
+// IEEE 1619-2007 and sectors encrypted by OpenSSL XTS-AES.
+new_tweak_test!(xex_aes128, "xex_aes128", Xex<Aes128>);
+new_tweak_test!(xex_aes256, "xex_aes256", Xex<Aes256>);
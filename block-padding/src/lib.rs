@@ -0,0 +1,285 @@
+//! This crate provides padding schemes for messages which are processed by
+//! block ciphers in blocks of `BlockSize` bytes.
+//!
+//! Unpadding of all schemes except `ZeroPadding` runs in a time which does
+//! not depend on the content of the padded block, so error returned on
+//! invalid padding can not be used as a timing side channel (e.g. to mount
+//! padding oracle attack on CBC mode). Note that unpadding errors still
+//! should not be reported to a remote party in a way which distinguishes
+//! them from other decryption failures.
+#![no_std]
+pub extern crate generic_array;
+
+use generic_array::{GenericArray, ArrayLength};
+
+/// Error for signaling that buffer is too small for padding or that position
+/// of the message end is invalid
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct PadError;
+
+/// Error for signaling that padding is malformed or data length is not
+/// a multiple of block size
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct UnpadError;
+
+/// Trait for padding messages divided into blocks
+pub trait Padding {
+    /// Pad block which contains message data up to `pos` (exclusive).
+    ///
+    /// `pos` must be smaller than the block size, otherwise
+    /// `Err(PadError)` is returned.
+    fn pad_block<N>(block: &mut GenericArray<u8, N>, pos: usize)
+        -> Result<(), PadError>
+        where N: ArrayLength<u8>;
+
+    /// Unpad block and return its message part.
+    fn unpad_block<N>(block: &GenericArray<u8, N>)
+        -> Result<&[u8], UnpadError>
+        where N: ArrayLength<u8>;
+
+    /// Pad message stored in the first `pos` bytes of the buffer and return
+    /// slice which contains padded message.
+    ///
+    /// Returns `Err(PadError)` if buffer is too small for the padded message.
+    fn pad<N>(buf: &mut [u8], pos: usize) -> Result<&mut [u8], PadError>
+        where N: ArrayLength<u8>
+    {
+        let bs = N::to_usize();
+        let start = pos - pos % bs;
+        if pos > buf.len() || buf.len() - start < bs {
+            return Err(PadError);
+        }
+        let block = GenericArray::<u8, N>::from_mut_slice(
+            &mut buf[start..start + bs]);
+        Self::pad_block(block, pos - start)?;
+        Ok(&mut buf[..start + bs])
+    }
+
+    /// Unpad padded message and return message part.
+    ///
+    /// Returns `Err(UnpadError)` if data is empty, its length is not
+    /// a multiple of block size or padding of the last block is malformed.
+    fn unpad<N>(data: &[u8]) -> Result<&[u8], UnpadError>
+        where N: ArrayLength<u8>
+    {
+        let bs = N::to_usize();
+        if data.is_empty() || data.len() % bs != 0 {
+            return Err(UnpadError);
+        }
+        let start = data.len() - bs;
+        let block = GenericArray::<u8, N>::from_slice(&data[start..]);
+        let n = Self::unpad_block(block)?.len();
+        Ok(&data[..start + n])
+    }
+}
+
+/// Pad block with zeros.
+///
+/// Unlike other schemes message which length is a multiple of block size is
+/// not padded, and trailing zeros of a message can not be distinguished from
+/// padding, so it should be used only for messages with a known length or
+/// messages which can not end with a zero byte.
+#[derive(Clone, Copy, Debug)]
+pub enum ZeroPadding {}
+
+impl Padding for ZeroPadding {
+    fn pad_block<N>(block: &mut GenericArray<u8, N>, pos: usize)
+        -> Result<(), PadError>
+        where N: ArrayLength<u8>
+    {
+        if pos >= N::to_usize() { return Err(PadError); }
+        for b in block[pos..].iter_mut() { *b = 0; }
+        Ok(())
+    }
+
+    fn unpad_block<N>(block: &GenericArray<u8, N>)
+        -> Result<&[u8], UnpadError>
+        where N: ArrayLength<u8>
+    {
+        let n = block.iter().rev().take_while(|&&b| b == 0).count();
+        Ok(&block[..block.len() - n])
+    }
+
+    fn pad<N>(buf: &mut [u8], pos: usize) -> Result<&mut [u8], PadError>
+        where N: ArrayLength<u8>
+    {
+        let bs = N::to_usize();
+        if pos % bs == 0 {
+            return if pos > buf.len() { Err(PadError) } else {
+                Ok(&mut buf[..pos])
+            };
+        }
+        let end = pos + bs - pos % bs;
+        if end > buf.len() { return Err(PadError); }
+        for b in buf[pos..end].iter_mut() { *b = 0; }
+        Ok(&mut buf[..end])
+    }
+}
+
+/// Pad block with bytes with value equal to the number of bytes added.
+///
+/// PKCS#7 described in the [RFC 5652](https://tools.ietf.org/html/rfc5652#section-6.3).
+/// Block size must not exceed 255 bytes.
+#[derive(Clone, Copy, Debug)]
+pub enum Pkcs7 {}
+
+impl Padding for Pkcs7 {
+    fn pad_block<N>(block: &mut GenericArray<u8, N>, pos: usize)
+        -> Result<(), PadError>
+        where N: ArrayLength<u8>
+    {
+        let bs = N::to_usize();
+        if pos >= bs || bs > 255 { return Err(PadError); }
+        let n = (bs - pos) as u8;
+        for b in block[pos..].iter_mut() { *b = n; }
+        Ok(())
+    }
+
+    fn unpad_block<N>(block: &GenericArray<u8, N>)
+        -> Result<&[u8], UnpadError>
+        where N: ArrayLength<u8>
+    {
+        let bs = N::to_usize();
+        let n = block[bs - 1];
+        let mut bad = ct_eq(n, 0) | ct_lt(bs, n as usize) | ct_gt_255(bs);
+        for (i, &b) in block.iter().rev().enumerate() {
+            bad |= ct_lt(i, n as usize) & !ct_eq(b, n);
+        }
+        finish(block, n as usize, bad)
+    }
+}
+
+/// Pad block with zeros except the last byte which is set to the number of
+/// bytes added.
+///
+/// ANSI X9.23 padding. Block size must not exceed 255 bytes.
+#[derive(Clone, Copy, Debug)]
+pub enum AnsiX923 {}
+
+impl Padding for AnsiX923 {
+    fn pad_block<N>(block: &mut GenericArray<u8, N>, pos: usize)
+        -> Result<(), PadError>
+        where N: ArrayLength<u8>
+    {
+        let bs = N::to_usize();
+        if pos >= bs || bs > 255 { return Err(PadError); }
+        for b in block[pos..bs - 1].iter_mut() { *b = 0; }
+        block[bs - 1] = (bs - pos) as u8;
+        Ok(())
+    }
+
+    fn unpad_block<N>(block: &GenericArray<u8, N>)
+        -> Result<&[u8], UnpadError>
+        where N: ArrayLength<u8>
+    {
+        let bs = N::to_usize();
+        let n = block[bs - 1];
+        let mut bad = ct_eq(n, 0) | ct_lt(bs, n as usize) | ct_gt_255(bs);
+        for (i, &b) in block.iter().rev().enumerate().skip(1) {
+            bad |= ct_lt(i, n as usize) & !ct_eq(b, 0);
+        }
+        finish(block, n as usize, bad)
+    }
+}
+
+/// Pad block with byte `0x80` followed by zeros.
+///
+/// ISO/IEC 7816-4 padding, also known as padding method 2 of
+/// ISO/IEC 9797-1.
+#[derive(Clone, Copy, Debug)]
+pub enum Iso7816 {}
+
+impl Padding for Iso7816 {
+    fn pad_block<N>(block: &mut GenericArray<u8, N>, pos: usize)
+        -> Result<(), PadError>
+        where N: ArrayLength<u8>
+    {
+        if pos >= N::to_usize() { return Err(PadError); }
+        block[pos] = 0x80;
+        for b in block[pos + 1..].iter_mut() { *b = 0; }
+        Ok(())
+    }
+
+    fn unpad_block<N>(block: &GenericArray<u8, N>)
+        -> Result<&[u8], UnpadError>
+        where N: ArrayLength<u8>
+    {
+        // mask which stays set while only zero bytes were seen
+        let mut zeros = 0xFF;
+        let mut bad = 0;
+        let mut n = 0;
+        for (i, &b) in block.iter().rev().enumerate() {
+            let first = zeros & !ct_eq(b, 0);
+            bad |= first & !ct_eq(b, 0x80);
+            n |= ct_mask(first) & (i + 1);
+            zeros &= ct_eq(b, 0);
+        }
+        finish(block, n, bad | zeros)
+    }
+}
+
+/// Pad block with arbitrary bytes except the last byte which is set to the
+/// number of bytes added.
+///
+/// ISO 10126 padding. Only the last byte of padding is verified during
+/// unpadding. This crate has no access to a source of randomness, so
+/// `pad_block` fills padding with zeros, which produces valid ISO 10126
+/// padding. Block size must not exceed 255 bytes.
+#[derive(Clone, Copy, Debug)]
+pub enum Iso10126 {}
+
+impl Padding for Iso10126 {
+    fn pad_block<N>(block: &mut GenericArray<u8, N>, pos: usize)
+        -> Result<(), PadError>
+        where N: ArrayLength<u8>
+    {
+        AnsiX923::pad_block(block, pos)
+    }
+
+    fn unpad_block<N>(block: &GenericArray<u8, N>)
+        -> Result<&[u8], UnpadError>
+        where N: ArrayLength<u8>
+    {
+        let bs = N::to_usize();
+        let n = block[bs - 1];
+        let bad = ct_eq(n, 0) | ct_lt(bs, n as usize) | ct_gt_255(bs);
+        finish(block, n as usize, bad)
+    }
+}
+
+/// Return message part of the block if `bad` is equal to zero.
+#[inline(always)]
+fn finish(block: &[u8], n: usize, bad: u8) -> Result<&[u8], UnpadError> {
+    if bad != 0 {
+        Err(UnpadError)
+    } else {
+        Ok(&block[..block.len() - n])
+    }
+}
+
+/// Returns `0xFF` if `a == b` and `0` otherwise in constant time.
+#[inline(always)]
+fn ct_eq(a: u8, b: u8) -> u8 {
+    let x = (a ^ b) as u16;
+    (x.wrapping_sub(1) >> 8) as u8
+}
+
+/// Returns `0xFF` if `a < b` and `0` otherwise in constant time. Both values
+/// must be smaller than `2^(usize::BITS - 1)`.
+#[inline(always)]
+fn ct_lt(a: usize, b: usize) -> u8 {
+    let x = a.wrapping_sub(b) >> (8 * core::mem::size_of::<usize>() - 1);
+    (x as u8).wrapping_neg()
+}
+
+/// Returns `0xFF` if block size does not fit into padding byte.
+#[inline(always)]
+fn ct_gt_255(bs: usize) -> u8 {
+    ct_lt(255, bs)
+}
+
+/// Expands `0xFF` or `0` mask to `usize`.
+#[inline(always)]
+fn ct_mask(mask: u8) -> usize {
+    ((mask & 1) as usize).wrapping_neg()
+}
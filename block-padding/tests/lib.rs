@@ -0,0 +1,165 @@
+extern crate block_padding;
+
+use block_padding::{
+    AnsiX923, Iso10126, Iso7816, Padding, PadError, Pkcs7, UnpadError,
+    ZeroPadding,
+};
+use block_padding::generic_array::typenum::U8;
+
+/// Pad messages of all lengths up to three blocks, check that padded
+/// message has expected length and that unpadding restores the message.
+fn roundtrip<P: Padding>(full_block: bool) {
+    let msg: Vec<u8> = (1..=24).collect();
+    for len in 0..=msg.len() {
+        let mut buf = [0xAA; 32];
+        buf[..len].copy_from_slice(&msg[..len]);
+        let padded = P::pad::<U8>(&mut buf, len).unwrap().to_vec();
+        let expected = if len % 8 == 0 && !full_block { len } else {
+            len - len % 8 + 8
+        };
+        assert_eq!(padded.len(), expected);
+        assert_eq!(padded[..len], msg[..len]);
+        if len != 0 || full_block {
+            assert_eq!(P::unpad::<U8>(&padded).unwrap(), &msg[..len]);
+        }
+    }
+}
+
+#[test]
+fn zero_padding_roundtrip() { roundtrip::<ZeroPadding>(false); }
+
+#[test]
+fn pkcs7_roundtrip() { roundtrip::<Pkcs7>(true); }
+
+#[test]
+fn ansi_x923_roundtrip() { roundtrip::<AnsiX923>(true); }
+
+#[test]
+fn iso7816_roundtrip() { roundtrip::<Iso7816>(true); }
+
+#[test]
+fn iso10126_roundtrip() { roundtrip::<Iso10126>(true); }
+
+/// Check padding of 5 and 8 byte messages.
+fn check_pad<P: Padding>(partial: &[u8], full: &[u8]) {
+    let mut buf = [0xAA; 16];
+    buf[..5].copy_from_slice(b"hello");
+    assert_eq!(P::pad::<U8>(&mut buf, 5).unwrap(), partial);
+    let mut buf = [0xAA; 16];
+    buf[..8].copy_from_slice(b"abcdefgh");
+    assert_eq!(P::pad::<U8>(&mut buf, 8).unwrap(), full);
+}
+
+#[test]
+fn pad_blocks() {
+    check_pad::<ZeroPadding>(b"hello\0\0\0", b"abcdefgh");
+    check_pad::<Pkcs7>(
+        b"hello\x03\x03\x03",
+        b"abcdefgh\x08\x08\x08\x08\x08\x08\x08\x08",
+    );
+    check_pad::<AnsiX923>(
+        b"hello\x00\x00\x03",
+        b"abcdefgh\x00\x00\x00\x00\x00\x00\x00\x08",
+    );
+    check_pad::<Iso7816>(
+        b"hello\x80\x00\x00",
+        b"abcdefgh\x80\x00\x00\x00\x00\x00\x00\x00",
+    );
+    check_pad::<Iso10126>(
+        b"hello\x00\x00\x03",
+        b"abcdefgh\x00\x00\x00\x00\x00\x00\x00\x08",
+    );
+}
+
+fn check_pad_errors<P: Padding>() {
+    // no space for the padding block
+    let mut buf = [0u8; 8];
+    assert_eq!(P::pad::<U8>(&mut buf, 8), Err(PadError));
+    let mut buf = [0u8; 12];
+    assert_eq!(P::pad::<U8>(&mut buf, 10), Err(PadError));
+    // message end is outside of the buffer
+    let mut buf = [0u8; 16];
+    assert_eq!(P::pad::<U8>(&mut buf, 17), Err(PadError));
+}
+
+#[test]
+fn pad_errors() {
+    check_pad_errors::<Pkcs7>();
+    check_pad_errors::<AnsiX923>();
+    check_pad_errors::<Iso7816>();
+    check_pad_errors::<Iso10126>();
+
+    let mut buf = [0u8; 12];
+    assert_eq!(ZeroPadding::pad::<U8>(&mut buf, 10), Err(PadError));
+    assert_eq!(ZeroPadding::pad::<U8>(&mut buf, 16), Err(PadError));
+}
+
+fn check_length_errors<P: Padding>() {
+    assert_eq!(P::unpad::<U8>(&[]), Err(UnpadError));
+    assert_eq!(P::unpad::<U8>(&[1; 7]), Err(UnpadError));
+    assert_eq!(P::unpad::<U8>(&[1; 9]), Err(UnpadError));
+}
+
+#[test]
+fn unpad_length_errors() {
+    check_length_errors::<ZeroPadding>();
+    check_length_errors::<Pkcs7>();
+    check_length_errors::<AnsiX923>();
+    check_length_errors::<Iso7816>();
+    check_length_errors::<Iso10126>();
+}
+
+#[test]
+fn pkcs7_unpad_errors() {
+    let unpad = Pkcs7::unpad::<U8>;
+    // zero padding length
+    assert_eq!(unpad(b"abcdefg\x00"), Err(UnpadError));
+    // padding longer than block
+    assert_eq!(unpad(b"abcdefg\x09"), Err(UnpadError));
+    // inconsistent padding bytes
+    assert_eq!(unpad(b"abcde\x03\x02\x03"), Err(UnpadError));
+    assert_eq!(unpad(b"abcde\x02\x03\x03"), Err(UnpadError));
+    // the whole block is padding, but one byte differs
+    assert_eq!(
+        unpad(b"\x07\x08\x08\x08\x08\x08\x08\x08"), Err(UnpadError));
+    assert_eq!(unpad(b"\x08\x08\x08\x08\x08\x08\x08\x08"), Ok(&b""[..]));
+}
+
+#[test]
+fn ansi_x923_unpad_errors() {
+    let unpad = AnsiX923::unpad::<U8>;
+    assert_eq!(unpad(b"abcdefg\x00"), Err(UnpadError));
+    assert_eq!(unpad(b"abcdefg\x09"), Err(UnpadError));
+    // non-zero padding bytes
+    assert_eq!(unpad(b"abcde\x01\x00\x03"), Err(UnpadError));
+    assert_eq!(unpad(b"abcde\x00\x03\x03"), Err(UnpadError));
+    assert_eq!(unpad(b"abcde\x01\x00\x02"), Ok(&b"abcde\x01"[..]));
+}
+
+#[test]
+fn iso7816_unpad_errors() {
+    let unpad = Iso7816::unpad::<U8>;
+    // no marker byte
+    assert_eq!(unpad(&[0; 8]), Err(UnpadError));
+    // last non-zero byte is not a marker
+    assert_eq!(unpad(b"abcde\x01\x00\x00"), Err(UnpadError));
+    assert_eq!(unpad(b"abcde\x80\x00\x01"), Err(UnpadError));
+    assert_eq!(unpad(b"abc\x80\x80\x00\x00\x00"), Ok(&b"abc\x80"[..]));
+}
+
+#[test]
+fn iso10126_unpad_errors() {
+    let unpad = Iso10126::unpad::<U8>;
+    assert_eq!(unpad(b"abcdefg\x00"), Err(UnpadError));
+    assert_eq!(unpad(b"abcdefg\x09"), Err(UnpadError));
+    // only the length byte is verified
+    assert_eq!(unpad(b"abcde\x17\x42\x03"), Ok(&b"abcde"[..]));
+}
+
+#[test]
+fn zero_padding_unpad() {
+    let unpad = ZeroPadding::unpad::<U8>;
+    assert_eq!(unpad(b"abcde\x00\x00\x00"), Ok(&b"abcde"[..]));
+    assert_eq!(unpad(b"abcdefgh"), Ok(&b"abcdefgh"[..]));
+    assert_eq!(unpad(&[0; 8]), Ok(&b""[..]));
+}
@@ -0,0 +1,229 @@
+//! Generic hash functions built from block ciphers using Davies–Meyer,
+//! Matyas–Meyer–Oseas and Miyaguchi–Preneel [compression functions][1].
+//!
+//! `CipherHash` iterates compression function `M` over the message in the
+//! Merkle–Damgård manner. Message is padded with a single `1` bit, zeros and
+//! its length in bits encoded as a 64-bit big endian number (Merkle–Damgård
+//! strengthening). Chaining value has the size of the cipher block and is
+//! returned as the hash value. It is initialized with zeros by default,
+//! other initial values can be used with `CipherHash::with_iv`. Message
+//! block must be at least 8 bytes long, so the encoded length fits into the
+//! last block, thus `FixedOutput` is not implemented for smaller blocks.
+//!
+//! Hashers implement `Input`, `BlockInput` and `FixedOutput` traits from the
+//! `digest` crate and thus the `Digest` trait. `Digest::digest_reader` is
+//! available with enabled `std` feature.
+//!
+//! Note that block ciphers are re-keyed for every message block, so these
+//! constructions are slow and should be used only when required by a format.
+//!
+//! # Usage example
+//! ```rust,ignore
+//! use cipher_hash::{MatyasMeyerOseas, Digest};
+//!
+//! let hash = MatyasMeyerOseas::<Aes128>::digest(b"input message");
+//! ```
+//!
+//! [1]: https://en.wikipedia.org/wiki/One-way_compression_function
+#![no_std]
+pub extern crate block_cipher_trait;
+pub extern crate digest;
+
+use block_cipher_trait::BlockEncrypt;
+use block_cipher_trait::generic_array::{GenericArray, ArrayLength};
+use block_cipher_trait::generic_array::typenum::{
+    IsGreaterOrEqual, True, U8, Unsigned,
+};
+use core::cmp;
+use core::fmt;
+use core::marker::PhantomData;
+
+pub use digest::{BlockInput, Digest, FixedOutput, Input};
+
+type Block<C> = GenericArray<u8, <C as BlockEncrypt>::BlockSize>;
+
+/// Davies–Meyer hash over block cipher `C`.
+pub type DaviesMeyer<C> = CipherHash<C, Dm>;
+/// Matyas–Meyer–Oseas hash over block cipher `C`.
+pub type MatyasMeyerOseas<C> = CipherHash<C, Mmo>;
+/// Miyaguchi–Preneel hash over block cipher `C`.
+pub type MiyaguchiPreneel<C> = CipherHash<C, Mp>;
+
+/// One-way compression function built from block cipher `C`.
+pub trait Compression<C: BlockEncrypt> {
+    /// Size of the message block
+    type BlockSize: ArrayLength<u8>;
+
+    /// Update chaining value `h` with message block `m`.
+    fn compress(h: &mut Block<C>, m: &GenericArray<u8, Self::BlockSize>);
+}
+
+/// Davies–Meyer compression function: `H' = E(M, H) xor H`.
+///
+/// Message block is used as the cipher key, so it has the size of the key.
+#[derive(Clone, Copy, Debug)]
+pub enum Dm {}
+
+impl<C: BlockEncrypt> Compression<C> for Dm {
+    type BlockSize = C::KeySize;
+
+    fn compress(h: &mut Block<C>, m: &GenericArray<u8, C::KeySize>) {
+        let mut t = h.clone();
+        C::new(m).encrypt_block(&mut t);
+        xor(h, &t);
+    }
+}
+
+/// Matyas–Meyer–Oseas compression function: `H' = E(H, M) xor M`.
+///
+/// Chaining value is used as the cipher key, so the cipher must have equal
+/// key and block sizes.
+#[derive(Clone, Copy, Debug)]
+pub enum Mmo {}
+
+impl<C, N> Compression<C> for Mmo
+    where C: BlockEncrypt<KeySize = N, BlockSize = N>, N: ArrayLength<u8>
+{
+    type BlockSize = N;
+
+    fn compress(h: &mut Block<C>, m: &GenericArray<u8, N>) {
+        let mut t = m.clone();
+        C::new(h).encrypt_block(&mut t);
+        xor(&mut t, m);
+        *h = t;
+    }
+}
+
+/// Miyaguchi–Preneel compression function: `H' = E(H, M) xor H xor M`.
+///
+/// Chaining value is used as the cipher key, so the cipher must have equal
+/// key and block sizes.
+#[derive(Clone, Copy, Debug)]
+pub enum Mp {}
+
+impl<C, N> Compression<C> for Mp
+    where C: BlockEncrypt<KeySize = N, BlockSize = N>, N: ArrayLength<u8>
+{
+    type BlockSize = N;
+
+    fn compress(h: &mut Block<C>, m: &GenericArray<u8, N>) {
+        let mut t = m.clone();
+        C::new(h).encrypt_block(&mut t);
+        xor(&mut t, m);
+        xor(h, &t);
+    }
+}
+
+/// Hash function built from block cipher `C` and compression function `M`.
+pub struct CipherHash<C: BlockEncrypt, M: Compression<C>> {
+    iv: Block<C>,
+    state: Block<C>,
+    buffer: GenericArray<u8, M::BlockSize>,
+    /// Number of bytes in `buffer`, always smaller than the block size
+    pos: usize,
+    /// Message length in bytes
+    len: u64,
+    _cipher: PhantomData<C>,
+}
+
+impl<C: BlockEncrypt, M: Compression<C>> CipherHash<C, M> {
+    /// Create new hasher with initial chaining value `iv`.
+    pub fn with_iv(iv: &Block<C>) -> Self {
+        Self {
+            iv: iv.clone(),
+            state: iv.clone(),
+            buffer: Default::default(),
+            pos: 0,
+            len: 0,
+            _cipher: PhantomData,
+        }
+    }
+}
+
+impl<C: BlockEncrypt, M: Compression<C>> Default for CipherHash<C, M> {
+    fn default() -> Self {
+        Self::with_iv(&Default::default())
+    }
+}
+
+impl<C: BlockEncrypt, M: Compression<C>> Clone for CipherHash<C, M> {
+    fn clone(&self) -> Self {
+        Self {
+            iv: self.iv.clone(),
+            state: self.state.clone(),
+            buffer: self.buffer.clone(),
+            pos: self.pos,
+            len: self.len,
+            _cipher: PhantomData,
+        }
+    }
+}
+
+impl<C: BlockEncrypt, M: Compression<C>> fmt::Debug for CipherHash<C, M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("CipherHash { ... }")
+    }
+}
+
+impl<C: BlockEncrypt, M: Compression<C>> BlockInput for CipherHash<C, M> {
+    type BlockSize = M::BlockSize;
+}
+
+impl<C: BlockEncrypt, M: Compression<C>> Input for CipherHash<C, M> {
+    fn process(&mut self, mut data: &[u8]) {
+        self.len = self.len.wrapping_add(data.len() as u64);
+        let bs = M::BlockSize::to_usize();
+
+        if self.pos != 0 {
+            let n = cmp::min(bs - self.pos, data.len());
+            self.buffer[self.pos..self.pos + n].copy_from_slice(&data[..n]);
+            self.pos += n;
+            data = &data[n..];
+            if self.pos < bs {
+                return;
+            }
+            M::compress(&mut self.state, &self.buffer);
+            self.pos = 0;
+        }
+
+        let mut chunks = data.chunks_exact(bs);
+        for block in &mut chunks {
+            M::compress(&mut self.state, GenericArray::from_slice(block));
+        }
+        let rem = chunks.remainder();
+        self.buffer[..rem.len()].copy_from_slice(rem);
+        self.pos = rem.len();
+    }
+}
+
+impl<C, M> FixedOutput for CipherHash<C, M>
+    where C: BlockEncrypt, M: Compression<C>,
+        M::BlockSize: IsGreaterOrEqual<U8, Output = True>
+{
+    type OutputSize = C::BlockSize;
+
+    fn fixed_result(&mut self) -> Block<C> {
+        let bits = self.len.wrapping_mul(8).to_be_bytes();
+        let bs = M::BlockSize::to_usize();
+        // The bound on the block size guarantees that the padding fits
+        // into at most two blocks and the length into the last one
+        self.process(&[0x80]);
+        while (self.pos + bits.len()) % bs != 0 {
+            self.process(&[0]);
+        }
+        self.process(&bits);
+        debug_assert_eq!(self.pos, 0);
+
+        let res = self.state.clone();
+        self.state = self.iv.clone();
+        self.len = 0;
+        res
+    }
+}
+
+#[inline(always)]
+fn xor(buf: &mut [u8], data: &[u8]) {
+    for (a, b) in buf.iter_mut().zip(data) {
+        *a ^= *b;
+    }
+}
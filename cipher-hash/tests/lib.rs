@@ -0,0 +1,144 @@
+extern crate aes;
+#[macro_use(impl_cipher)]
+extern crate block_cipher_trait;
+extern crate cipher_hash;
+extern crate des;
+
+use block_cipher_trait::BlockEncrypt;
+use block_cipher_trait::dev::decode_hex;
+use block_cipher_trait::generic_array::GenericArray;
+use block_cipher_trait::generic_array::typenum::{U8, U16};
+use cipher_hash::{
+    Compression, DaviesMeyer, Digest, Dm, MatyasMeyerOseas, MiyaguchiPreneel,
+    Mmo, Mp,
+};
+
+impl_cipher!(Aes128, aes::Aes128, U16, U16);
+impl_cipher!(Des, des::Des, U8, U8);
+
+/// Messages which are padded into one, two and three blocks of AES.
+const MESSAGES: &[&[u8]] = &[
+    b"",
+    b"abc",
+    b"0123456789abcdefghij",
+    b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0a\x0b\x0c\x0d\x0e\x0f\
+      \x10\x11\x12\x13\x14\x15\x16\x17\x18\x19\x1a\x1b\x1c\x1d\x1e\x1f",
+];
+
+// Generated using the OpenSSL AES implementation, hashes of `MESSAGES`
+const DM_AES128: &[&str] = &[
+    "0edd33d3c621e546455bd8ba1418bec8",
+    "10d540f6e1d7d2b09b47a65e6de29300",
+    "51facf97e3738eee04b85e92c7d243ed",
+    "509be5922f55ea74cd9a91e3328bb2b5",
+];
+const MMO_AES128: &[&str] = &[
+    "bad78e726c1ec02b7ebfe92b23d9ec34",
+    "bd2f2ebd93fadc48bc00174d95422741",
+    "9b8a4899c0b1969680b86e5bc3ca61f3",
+    "6229a71f6157ddb5deba002edaac3752",
+];
+// Miyaguchi–Preneel differs from Matyas–Meyer–Oseas only by the XOR of
+// the chaining value, which is zero for the first block
+const MP_AES128: &[&str] = &[
+    "bad78e726c1ec02b7ebfe92b23d9ec34",
+    "bd2f2ebd93fadc48bc00174d95422741",
+    "bf4e84de9de25233dd9d2db11b51429c",
+    "724b3ce1155afc2a9c3b2fda38277a0a",
+];
+
+fn check<D: Digest>(hashes: &[&str]) {
+    for (msg, hash) in MESSAGES.iter().zip(hashes) {
+        let mut buf = [0u8; 16];
+        let hash = decode_hex(hash, &mut buf);
+        assert_eq!(&D::digest(msg)[..], hash);
+
+        // check that the result does not depend on splitting of the input
+        // and that the hasher is reset after `result`
+        let mut hasher = D::new();
+        for _ in 0..2 {
+            for chunk in msg.chunks(7) { hasher.input(chunk); }
+            assert_eq!(&hasher.result()[..], hash);
+        }
+    }
+}
+
+#[test]
+fn davies_meyer_aes128() {
+    check::<DaviesMeyer<Aes128>>(DM_AES128);
+}
+
+#[test]
+fn matyas_meyer_oseas_aes128() {
+    check::<MatyasMeyerOseas<Aes128>>(MMO_AES128);
+}
+
+#[test]
+fn miyaguchi_preneel_aes128() {
+    check::<MiyaguchiPreneel<Aes128>>(MP_AES128);
+}
+
+/// Davies–Meyer over DES has 8 byte message blocks, i.e. the smallest
+/// supported size, with which padding always takes a separate block.
+#[test]
+fn davies_meyer_des() {
+    let tests: &[(&[u8], &str)] = &[
+        (b"", "a20ca4339f173692"),
+        (b"abc", "f41c4a202dd5ddcc"),
+        (b"0123456789abcdef", "120f58e18e266655"),
+    ];
+    for &(msg, hash) in tests {
+        let mut buf = [0u8; 8];
+        let hash = decode_hex(hash, &mut buf);
+        assert_eq!(&DaviesMeyer::<Des>::digest(msg)[..], hash);
+    }
+}
+
+#[test]
+fn with_iv() {
+    let iv = GenericArray::clone_from_slice(&[
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+    ]);
+    let mut hasher = MatyasMeyerOseas::<Aes128>::with_iv(&iv);
+    hasher.input(b"abc");
+    let mut buf = [0u8; 16];
+    let hash = decode_hex("855dfd8aad0abf800dc7b9af67d71878", &mut buf);
+    assert_eq!(&hasher.result()[..], hash);
+}
+
+/// Compare compression functions with direct computation over one and two
+/// blocks.
+#[test]
+fn compression() {
+    type Block = GenericArray<u8, U16>;
+
+    fn xor(a: &Block, b: &Block) -> Block {
+        a.iter().zip(b.iter()).map(|(a, b)| a ^ b).collect()
+    }
+
+    fn encrypt(key: &Block, block: &Block) -> Block {
+        let mut block = *block;
+        Aes128::new(key).encrypt_block(&mut block);
+        block
+    }
+
+    let iv = Block::clone_from_slice(&[0x42; 16]);
+    let m1 = Block::clone_from_slice(b"first block 0123");
+    let m2 = Block::clone_from_slice(b"second block 456");
+
+    let (mut dm, mut mmo, mut mp) = (iv, iv, iv);
+    let (mut h_dm, mut h_mmo, mut h_mp) = (iv, iv, iv);
+    for m in &[m1, m2] {
+        <Dm as Compression<Aes128>>::compress(&mut dm, m);
+        <Mmo as Compression<Aes128>>::compress(&mut mmo, m);
+        <Mp as Compression<Aes128>>::compress(&mut mp, m);
+
+        h_dm = xor(&encrypt(m, &h_dm), &h_dm);
+        h_mmo = xor(&encrypt(&h_mmo, m), m);
+        h_mp = xor(&xor(&encrypt(&h_mp, m), m), &h_mp);
+
+        assert_eq!(dm, h_dm);
+        assert_eq!(mmo, h_mmo);
+        assert_eq!(mp, h_mp);
+    }
+}
@@ -0,0 +1,151 @@
+//! Generic implementation of [Cipher-based Message Authentication Code][1]
+//! (CMAC), also known as OMAC1.
+//!
+//! CMAC works with any block cipher whose block size is supported by the
+//! `dbl` crate, i.e. with 64, 128 and 256 bit block ciphers. Output size is
+//! equal to the block size.
+//!
+//! `Cmac::from_cipher` accepts ciphers which only implement `BlockEncryptMut`,
+//! while the `Mac` trait implementation requires `BlockEncrypt`.
+//!
+//! # Usage example
+//! ```rust,ignore
+//! use cmac::{Cmac, Mac};
+//!
+//! let mut mac = Cmac::<Aes128>::new_varkey(&key).unwrap();
+//! mac.input(b"input message");
+//! let code = mac.result().code();
+//! ```
+//!
+//! [1]: https://tools.ietf.org/html/rfc4493
+#![no_std]
+pub extern crate block_cipher_trait;
+pub extern crate crypto_mac;
+extern crate dbl;
+
+use block_cipher_trait::{BlockEncrypt, BlockEncryptMut};
+use block_cipher_trait::generic_array::GenericArray;
+use block_cipher_trait::generic_array::typenum::Unsigned;
+use dbl::Dbl;
+
+pub use crypto_mac::{Mac, MacResult, InvalidKeyLength, KeySizes};
+
+type Block<C> = GenericArray<u8, <C as BlockEncryptMut>::BlockSize>;
+
+/// CMAC instance over block cipher `C`.
+#[derive(Clone)]
+pub struct Cmac<C> where C: BlockEncryptMut, Block<C>: Dbl {
+    cipher: C,
+    key1: Block<C>,
+    key2: Block<C>,
+    state: Block<C>,
+    /// Last block of the input, which is processed only in `result` or after
+    /// more data was received
+    buffer: Block<C>,
+    /// Number of bytes in `buffer`
+    pos: usize,
+}
+
+impl<C> Cmac<C> where C: BlockEncryptMut, Block<C>: Dbl {
+    /// Create new CMAC instance from initialized block cipher.
+    pub fn from_cipher(mut cipher: C) -> Self {
+        let mut l = Block::<C>::default();
+        cipher.encrypt_block(&mut l);
+        let key1 = l.dbl();
+        let key2 = key1.clone().dbl();
+        Self {
+            cipher,
+            key1,
+            key2,
+            state: Default::default(),
+            buffer: Default::default(),
+            pos: 0,
+        }
+    }
+
+    fn process_buffer(&mut self) {
+        xor(&mut self.state, &self.buffer);
+        self.cipher.encrypt_block(&mut self.state);
+    }
+
+    fn input_data(&mut self, mut data: &[u8]) {
+        let bs = C::BlockSize::to_usize();
+        let rem = bs - self.pos;
+        if data.len() <= rem {
+            let end = self.pos + data.len();
+            self.buffer[self.pos..end].copy_from_slice(data);
+            self.pos = end;
+            return;
+        }
+
+        let (head, tail) = data.split_at(rem);
+        self.buffer[self.pos..].copy_from_slice(head);
+        self.process_buffer();
+        data = tail;
+
+        // the last block is kept in the buffer even if it's full
+        while data.len() > bs {
+            let (block, tail) = data.split_at(bs);
+            xor(&mut self.state, block);
+            self.cipher.encrypt_block(&mut self.state);
+            data = tail;
+        }
+        self.buffer[..data.len()].copy_from_slice(data);
+        self.pos = data.len();
+    }
+
+    fn finalize(&mut self) -> Block<C> {
+        let bs = C::BlockSize::to_usize();
+        if self.pos == bs {
+            xor(&mut self.buffer, &self.key1);
+        } else {
+            self.buffer[self.pos] = 0x80;
+            for b in self.buffer[self.pos + 1..].iter_mut() {
+                *b = 0;
+            }
+            xor(&mut self.buffer, &self.key2);
+        }
+        self.process_buffer();
+
+        let tag = self.state.clone();
+        self.state = Default::default();
+        self.buffer = Default::default();
+        self.pos = 0;
+        tag
+    }
+}
+
+impl<C> Mac for Cmac<C> where C: BlockEncrypt, Block<C>: Dbl {
+    type OutputSize = C::BlockSize;
+    type KeySize = C::KeySize;
+
+    fn new(key: &GenericArray<u8, C::KeySize>) -> Self {
+        Self::from_cipher(C::new(key))
+    }
+
+    fn new_varkey(key: &[u8]) -> Result<Self, InvalidKeyLength> {
+        let cipher = C::new_varkey(key).map_err(|_| InvalidKeyLength)?;
+        Ok(Self::from_cipher(cipher))
+    }
+
+    fn key_sizes() -> KeySizes {
+        C::key_sizes()
+    }
+
+    #[inline]
+    fn input(&mut self, data: &[u8]) {
+        self.input_data(data);
+    }
+
+    #[inline]
+    fn result(&mut self) -> MacResult<C::BlockSize> {
+        MacResult::new(self.finalize())
+    }
+}
+
+#[inline(always)]
+fn xor(buf: &mut [u8], data: &[u8]) {
+    for (a, b) in buf.iter_mut().zip(data) {
+        *a ^= *b;
+    }
+}
@@ -0,0 +1,59 @@
+extern crate aes;
+#[macro_use(impl_cipher)]
+extern crate block_cipher_trait;
+extern crate cast5;
+extern crate cmac;
+#[macro_use]
+extern crate crypto_mac;
+extern crate des;
+
+use block_cipher_trait::{BlockDecrypt, BlockEncrypt, Ede3, InvalidKeyLength};
+use block_cipher_trait::generic_array::GenericArray;
+use block_cipher_trait::generic_array::typenum::{U8, U16};
+use cmac::{Cmac, KeySizes};
+
+impl_cipher!(Aes128, aes::Aes128, U16, U16);
+impl_cipher!(Des, des::Des, U8, U8);
+
+new_test!(cmac_aes128, "aes128", Cmac<Aes128>);
+new_test!(cmac_tdes, "tdes", Cmac<Ede3<Des, Des, Des>>);
+
+/// CAST5 accepts keys from 5 to 16 bytes.
+#[derive(Clone)]
+struct Cast5(cast5::Cast5);
+
+impl BlockEncrypt for Cast5 {
+    type KeySize = U16;
+    type BlockSize = U8;
+    type ParBlocks = U8;
+
+    fn new(key: &GenericArray<u8, U16>) -> Self {
+        Self::new_varkey(key).unwrap()
+    }
+
+    fn new_varkey(key: &[u8]) -> Result<Self, InvalidKeyLength> {
+        use cast5::cipher::KeyInit;
+        cast5::Cast5::new_from_slice(key)
+            .map(Cast5)
+            .map_err(|_| InvalidKeyLength)
+    }
+
+    fn key_sizes() -> KeySizes {
+        KeySizes::Range { min: 5, max: 16, step: 1 }
+    }
+
+    fn encrypt_block(&self, block: &mut GenericArray<u8, U8>) {
+        use cast5::cipher::BlockEncrypt;
+        self.0.encrypt_block(block.as_mut_slice().into());
+    }
+}
+
+impl BlockDecrypt for Cast5 {
+    fn decrypt_block(&self, block: &mut GenericArray<u8, U8>) {
+        use cast5::cipher::BlockDecrypt;
+        self.0.decrypt_block(block.as_mut_slice().into());
+    }
+}
+
+key_sizes_test!(cmac_aes128_key_sizes, Cmac<Aes128>);
+key_sizes_test!(cmac_cast5_key_sizes, Cmac<Cast5>);
@@ -39,13 +39,11 @@ macro_rules! new_test {
             // u32 (2 bytes); start + end (x2); key, input, tag (x3)
             assert_eq!(index.len() % (2*3*2), 0, "invlaid index length");
             for (i, chunk) in index.chunks(2*3*2).enumerate() {
-                // proper aligment is assumed here
-                let mut idx = unsafe {
-                    *(chunk.as_ptr() as *const [[u16; 2]; 3])
-                };
-                // convert to LE for BE machine
-                for val in idx.iter_mut() {
-                    for i in val.iter_mut() { *i = i.to_le(); }
+                let mut idx = [[0u16; 2]; 3];
+                for (j, val) in idx.iter_mut()
+                    .flat_map(|v| v.iter_mut()).enumerate()
+                {
+                    *val = u16::from_le_bytes([chunk[2*j], chunk[2*j + 1]]);
                 }
                 let key = &keys[(idx[0][0] as usize)..(idx[0][1] as usize)];
                 let input = &inputs[
@@ -69,6 +67,39 @@ macro_rules! new_test {
     }
 }
 
+/// Define test which checks that `new_varkey` accepts exactly the key lengths
+/// declared by `key_sizes`. Lengths are checked up to 1024 bytes or up to the
+/// maximal declared length plus one, whichever is smaller.
+#[macro_export]
+macro_rules! key_sizes_test {
+    ($name:ident, $mac:ty) => {
+        #[test]
+        fn $name() {
+            use crypto_mac::Mac;
+
+            let sizes = <$mac as Mac>::key_sizes();
+            let mut key = [0u8; 1024];
+            for (i, b) in key.iter_mut().enumerate() { *b = i as u8; }
+            let max = if sizes.max() < key.len() {
+                sizes.max() + 1
+            } else {
+                key.len()
+            };
+            for len in 0..max + 1 {
+                let accepted = <$mac as Mac>::new_varkey(&key[..len])
+                    .is_ok();
+                if accepted != sizes.contains(len) {
+                    panic!("\n\
+                        Failed key sizes test for key length {}\n\
+                        declared: {:?}\naccepted: {}\n",
+                        len, sizes, accepted,
+                    );
+                }
+            }
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! bench {
     ($name:ident, $engine:path, $bs:expr) => {
@@ -2,11 +2,14 @@
 #![no_std]
 extern crate constant_time_eq;
 pub extern crate generic_array;
+extern crate key_sizes;
 
 use constant_time_eq::constant_time_eq;
 use generic_array::{GenericArray, ArrayLength};
 use generic_array::typenum::Unsigned;
 
+pub use key_sizes::KeySizes;
+
 #[cfg(feature = "dev")]
 pub mod dev;
 
@@ -29,7 +32,8 @@ pub trait Mac: core::marker::Sized {
     /// Create new MAC instance from key with variable size.
     ///
     /// Default implementation will accept only keys with length equal to
-    /// `KeySize`, but some MACs can accept range of key lengths.
+    /// `KeySize`, but some MACs can accept range of key lengths, in which case
+    /// `key_sizes` must be overridden as well.
     fn new_varkey(key: &[u8]) -> Result<Self, InvalidKeyLength> {
         if key.len() != Self::KeySize::to_usize() {
             Err(InvalidKeyLength)
@@ -38,6 +42,13 @@ pub trait Mac: core::marker::Sized {
         }
     }
 
+    /// Key lengths accepted by `new_varkey`.
+    ///
+    /// Default implementation returns `KeySize` as the only length.
+    fn key_sizes() -> KeySizes {
+        KeySizes::fixed(Self::KeySize::to_usize())
+    }
+
     /// Process input data.
     fn input(&mut self, data: &[u8]);
 
@@ -0,0 +1,351 @@
+//! Test vectors and helpers for testing CTR_DRBG implementations.
+//!
+//! Usage example:
+//!
+//! ```rust,ignore
+//! #[macro_use]
+//! extern crate ctr_drbg;
+//!
+//! new_test!(aes128_df, ctr_drbg::dev::AES128_DF, Aes128, true);
+//! ```
+use super::{CtrDrbg, Error};
+use block_cipher_trait::BlockEncrypt;
+use block_cipher_trait::dev::decode_hex as decode;
+
+/// CTR_DRBG test vector. All fields are hex encoded, empty strings denote
+/// absent inputs.
+///
+/// DRBG is instantiated and optionally reseeded using `entropy_reseed` and
+/// `additional_reseed`, after that two requests with the length of `output`
+/// are made using `additional1` and `additional2` respectively. If
+/// prediction resistance entropy inputs are not empty, they are used for
+/// reseeding before the corresponding requests. Output of the second request
+/// must be equal to `output`.
+pub struct Test {
+    pub entropy: &'static str,
+    pub nonce: &'static str,
+    pub personalization: &'static str,
+    pub entropy_reseed: &'static str,
+    pub additional_reseed: &'static str,
+    pub entropy_pr1: &'static str,
+    pub entropy_pr2: &'static str,
+    pub additional1: &'static str,
+    pub additional2: &'static str,
+    pub output: &'static str,
+}
+
+/// Define test which runs CTR_DRBG test vectors `$tests` for block cipher
+/// `$cipher` with (`$df = true`) or without derivation function using
+/// `run_tests`.
+#[macro_export]
+macro_rules! new_test {
+    ($name:ident, $tests:expr, $cipher:ty, $df:expr) => {
+        #[test]
+        fn $name() {
+            ctr_drbg::dev::run_tests::<$cipher>($tests, $df);
+        }
+    }
+}
+
+/// Run CTR_DRBG tests for block cipher `C` with (`df = true`) or without
+/// derivation function.
+pub fn run_tests<C: BlockEncrypt>(tests: &[Test], df: bool) {
+    for (i, t) in tests.iter().enumerate() {
+        if run_test::<C>(t, df) != Ok(true) {
+            panic!("\nFailed CTR_DRBG test №{}\n\
+                entropy:\t{}\nnonce:\t{}\npersonalization:\t{}\n\
+                expected output:\t{}\n",
+                i, t.entropy, t.nonce, t.personalization, t.output,
+            );
+        }
+    }
+}
+
+fn run_test<C: BlockEncrypt>(t: &Test, df: bool) -> Result<bool, Error> {
+    let mut bufs = [[0u8; 64]; 3];
+    let [b0, b1, b2] = &mut bufs;
+    let entropy = decode(t.entropy, b0);
+    let personalization = decode(t.personalization, b1);
+    let mut drbg = if df {
+        CtrDrbg::<C>::new(entropy, decode(t.nonce, b2), personalization)?
+    } else {
+        CtrDrbg::<C>::new_without_df(entropy, personalization)?
+    };
+    if !t.entropy_reseed.is_empty() {
+        let entropy = decode(t.entropy_reseed, b0);
+        drbg.reseed(entropy, decode(t.additional_reseed, b1))?;
+    }
+
+    let (mut out_buf, mut expected_buf) = ([0u8; 64], [0u8; 64]);
+    let expected = decode(t.output, &mut expected_buf);
+    let out = &mut out_buf[..expected.len()];
+    let requests = [
+        (t.entropy_pr1, t.additional1),
+        (t.entropy_pr2, t.additional2),
+    ];
+    for &(entropy_pr, additional) in requests.iter() {
+        let additional = decode(additional, b1);
+        if entropy_pr.is_empty() {
+            drbg.generate(out, additional)?;
+        } else {
+            let entropy = decode(entropy_pr, b0);
+            drbg.generate_with_prediction_resistance(entropy, out, additional)?;
+        }
+    }
+    Ok(out == expected)
+}
+
+/// CTR_DRBG AES-128 with derivation function vectors from the NIST CAVP
+/// (CAVS 14.3).
+pub const AES128_DF: &[Test] = &[
+    Test {
+        entropy: "890eb067acf7382eff80b0c73bc872c6",
+        nonce: "aad471ef3ef1d203",
+        personalization: "",
+        entropy_reseed: "",
+        additional_reseed: "",
+        entropy_pr1: "",
+        entropy_pr2: "",
+        additional1: "",
+        additional2: "",
+        output: "a5514ed7095f64f3d0d3a5760394ab42\
+            062f373a25072a6ea6bcfd8489e94af6\
+            cf18659fea22ed1ca0a9e33f718b115e\
+            e536b12809c31b72b08ddd8be1910fa3",
+    },
+    Test {
+        entropy: "b408cefb5bc7157d3f26cb95a8b1d7ac",
+        nonce: "026c768fd577b92a",
+        personalization: "",
+        entropy_reseed: "",
+        additional_reseed: "",
+        entropy_pr1: "",
+        entropy_pr2: "",
+        additional1: "5737ef81dee365b6dadb3feebf5d1084",
+        additional2: "3368a516b3431a3daaa60dc8743c8297",
+        output: "4e909ebb24147a0004063a5e47ee044f\
+            ead610d62324bd0f963f756fb91361e8\
+            b87e3a76a398143fe88130fe1b547b66\
+            1a6480c711b739f18a9df3ae51d41bc9",
+    },
+    Test {
+        entropy: "2d2ab564202918c4ef5b102dda385a18",
+        nonce: "259195269ec11af6",
+        personalization: "",
+        entropy_reseed: "",
+        additional_reseed: "",
+        entropy_pr1: "",
+        entropy_pr2: "",
+        additional1: "",
+        additional2: "",
+        output: "2c5cd79ed87622a91b8654c8903d8522\
+            42cd49cb5df2d4b4150584301c59f01f\
+            d95a702ac157c84cc15f42c821133567\
+            2d8ce1291ef9b1def78149a04fa2697c",
+    },
+    Test {
+        entropy: "adf5711f93d8c8997349429ccaedae0a",
+        nonce: "b25716931b6e3cc1",
+        personalization: "",
+        entropy_reseed: "",
+        additional_reseed: "",
+        entropy_pr1: "",
+        entropy_pr2: "",
+        additional1: "abf8cd66dd39758b01d7dbb99ab17dc3",
+        additional2: "4be0f6b2755377c6e881fbb261b56beb",
+        output: "d420604dee6467492db5957c86207a70\
+            8fd242ed67942aed299425335c83b414\
+            37418582f41bc7fc0ef0d6927f34d83a\
+            cd67c70133644fd711dd5a65731f9f02",
+    },
+    Test {
+        entropy: "0f65da13dca407999d4773c2b4a11d85",
+        nonce: "5209e5b4ed82a234",
+        personalization: "",
+        entropy_reseed: "1dea0a12c52bf64339dd291c80d8ca89",
+        additional_reseed: "",
+        entropy_pr1: "",
+        entropy_pr2: "",
+        additional1: "",
+        additional2: "",
+        output: "2859cc468a76b08661ffd23b28547ffd\
+            0997ad526a0f51261b99ed3a37bd407b\
+            f418dbe6c6c3e26ed0ddefcb7474d899\
+            bd99f3655427519fc5b4057bcaf306d4",
+    },
+    Test {
+        entropy: "5d4041942bcf68864a4997d8171f1f9f",
+        nonce: "d4f1f4ae08bcb3e1",
+        personalization: "",
+        entropy_reseed: "",
+        additional_reseed: "",
+        entropy_pr1: "ef55a769b7eaf03fe082029bb32a2b9d",
+        entropy_pr2: "8239e865c0a42e14b964b9c09de85a20",
+        additional1: "",
+        additional2: "",
+        output: "4155320287eedcf7d484c2c2a1e2eb64\
+            b9c9ce77c87202a1ae1616c7a5cfd1c6\
+            87c7a0bfcc85bda48fdd4629fd330c22\
+            d0a76076f88fc7cd04037ee06b7af602",
+    },
+    Test {
+        entropy: "92898f31fa1cff6d182f260643dff818",
+        nonce: "c2a4d972c3b9b697",
+        personalization: "ea65ee60264e7eb60e8268c4373c5c0b",
+        entropy_reseed: "",
+        additional_reseed: "",
+        entropy_pr1: "20728a06f86f8dd441e272b7c42ce810",
+        entropy_pr2: "3db0f094f305503317863e2208f7a501",
+        additional1: "1a40fae3cc6c7ca0f8daba59236dad1d",
+        additional2: "9f72766cc746e5ed2e532012bc59318c",
+        output: "5a3539870f4d22a40924ee71c96fac72\
+            0ad6f08882d0832873ec3f93d8ab4523\
+            f07eac45145e939fb1d676433db6e808\
+            88f6da89087742fe1af43fc423c51f68",
+    },
+];
+
+/// CTR_DRBG AES-256 with derivation function vectors from the NIST CAVP
+/// (CAVS 14.3) and the NIST CTR_DRBG examples.
+pub const AES256_DF: &[Test] = &[
+    Test {
+        entropy: "36401940fa8b1fba91a1661f211d78a0\
+            b9389a74e5bccfece8d766af1a6d3b14",
+        nonce: "496f25b0f1301b4f501be30380a137eb",
+        personalization: "",
+        entropy_reseed: "",
+        additional_reseed: "",
+        entropy_pr1: "",
+        entropy_pr2: "",
+        additional1: "",
+        additional2: "",
+        output: "5862eb38bd558dd978a696e6df164782\
+            ddd887e7e9a6c9f3f1fbafb78941b535\
+            a64912dfd224c6dc7454e5250b3d9716\
+            5e16260c2faf1cc7735cb75fb4f07e1d",
+    },
+    Test {
+        entropy: "8148d65d86513ce7d38923ec2f26b9e7\
+            c677dcc8997e325b7372619e753ed944",
+        nonce: "41c71a24d17d974190982bb7515ce7f5",
+        personalization: "",
+        entropy_reseed: "",
+        additional_reseed: "",
+        entropy_pr1: "",
+        entropy_pr2: "",
+        additional1: "55b446046c2d14bdd0cdba4b71873fd4\
+            762650695a11507949462da8d964ab6a",
+        additional2: "91468f1a097d99ee339462ca916cb4a1\
+            0f63d53850a4f17f598eac490299b02e",
+        output: "54603d1a506132bbfa05b153a04f22a1\
+            d516cc46323cef15111af221f030f38d\
+            6841d4670518b4914a4631af682e7421\
+            dffaac986a38e94d92bfa758e2eb101f",
+    },
+    Test {
+        entropy: "2d4c9f46b981c6a0b2b5d8c69391e569\
+            ff13851437ebc0fc00d616340252fed5",
+        nonce: "0bf814b411f65ec4866be1abb59d3c32",
+        personalization: "",
+        entropy_reseed: "93500fae4fa32b86033b7a7bac9d37e7\
+            10dcc67ca266bc8607d665937766d207",
+        additional_reseed: "",
+        entropy_pr1: "",
+        entropy_pr2: "",
+        additional1: "",
+        additional2: "",
+        output: "322dd28670e75c0ea638f3cb68d6a9d6\
+            e50ddfd052b772a7b1d78263a7b8978b\
+            6740c2b65a9550c3a76325866fa97e16\
+            d74006bc96f26249b9f0a90d076f08e5",
+    },
+    Test {
+        entropy: "16a1f035388cd8d956026e3b0117cb52\
+            4dd3eb563f9a7720bb7dcb0fc6fbe743",
+        nonce: "a2d015f22d854e29de278d910c573de5",
+        personalization: "",
+        entropy_reseed: "",
+        additional_reseed: "",
+        entropy_pr1: "cf140bcd4d7130e7e3ea14046c56442b\
+            57c43b34ad219553e7105c18f6e561af",
+        entropy_pr2: "e27c9f0be60d82d6cc474efb7fc737b1\
+            6a6895d9a3a45b971d19b743c1a4ac8f",
+        additional1: "",
+        additional2: "",
+        output: "b4e8395bcb7503410a94633f70e9904a\
+            5b30e62c35bc6dd2a03496c4a49932e1\
+            84fbffdbcf1de1c72c50d36dc2ae8f04\
+            f40f96aae159c3fb816ca16df99b6c3e",
+    },
+    Test {
+        entropy: "000102030405060708090a0b0c0d0e0f\
+            101112131415161718191a1b1c1d1e1f\
+            202122232425262728292a2b2c2d2e2f",
+        nonce: "202122232425262728292a2b2c2d2e2f",
+        personalization: "",
+        entropy_reseed: "",
+        additional_reseed: "",
+        entropy_pr1: "808182838485868788898a8b8c8d8e8f\
+            909192939495969798999a9b9c9d9e9f\
+            a0a1a2a3a4a5a6a7a8a9aaabacadaeaf",
+        entropy_pr2: "c0c1c2c3c4c5c6c7c8c9cacbcccdcecf\
+            d0d1d2d3d4d5d6d7d8d9dadbdcdddedf\
+            e0e1e2e3e4e5e6e7e8e9eaebecedeeef",
+        additional1: "606162636465666768696a6b6c6d6e6f\
+            707172737475767778797a7b7c7d7e7f\
+            808182838485868788898a8b8c8d8e8f",
+        additional2: "a0a1a2a3a4a5a6a7a8a9aaabacadaeaf\
+            b0b1b2b3b4b5b6b7b8b9babbbcbdbebf\
+            c0c1c2c3c4c5c6c7c8c9cacbcccdcecf",
+        output: "386debbbf091bbf0502957b0329938fb\
+            836b82e594a2f5fdd5eb28d4e35528f4",
+    },
+    Test {
+        entropy: "000102030405060708090a0b0c0d0e0f\
+            101112131415161718191a1b1c1d1e1f\
+            202122232425262728292a2b2c2d2e2f",
+        nonce: "202122232425262728292a2b2c2d2e2f",
+        personalization: "404142434445464748494a4b4c4d4e4f\
+            505152535455565758595a5b5c5d5e5f\
+            606162636465666768696a6b6c6d6e6f",
+        entropy_reseed: "",
+        additional_reseed: "",
+        entropy_pr1: "808182838485868788898a8b8c8d8e8f\
+            909192939495969798999a9b9c9d9e9f\
+            a0a1a2a3a4a5a6a7a8a9aaabacadaeaf",
+        entropy_pr2: "c0c1c2c3c4c5c6c7c8c9cacbcccdcecf\
+            d0d1d2d3d4d5d6d7d8d9dadbdcdddedf\
+            e0e1e2e3e4e5e6e7e8e9eaebecedeeef",
+        additional1: "606162636465666768696a6b6c6d6e6f\
+            707172737475767778797a7b7c7d7e7f\
+            808182838485868788898a8b8c8d8e8f",
+        additional2: "a0a1a2a3a4a5a6a7a8a9aaabacadaeaf\
+            b0b1b2b3b4b5b6b7b8b9babbbcbdbebf\
+            c0c1c2c3c4c5c6c7c8c9cacbcccdcecf",
+        output: "738e99c95af59519aad37ff3d5180986\
+            adebab6e95836725097e50a8d1d0bd28",
+    },
+];
+
+/// CTR_DRBG AES-256 without derivation function vector from the NIST CAVP.
+pub const AES256_NO_DF: &[Test] = &[
+    Test {
+        entropy: "e4bc23c5089a19d86f4119cb3fa08c0a\
+            4991e0a1def17e101e4c14d9c323460a\
+            7c2fb58e0b086c6c57b55f56cae25bad",
+        nonce: "",
+        personalization: "",
+        entropy_reseed: "fd85a836bba85019881e8c6bad23c906\
+            1adc75477659acaea8e4a01dfe07a183\
+            2dad1c136f59d70f8653a5dc118663d6",
+        additional_reseed: "",
+        entropy_pr1: "",
+        entropy_pr2: "",
+        additional1: "",
+        additional2: "",
+        output: "b2cb8905c05e5950ca31895096be29ea\
+            3d5a3b82b269495554eb80fe07de43e1\
+            93b9e7c3ece73b80e062b1c1f68202fb\
+            b1c52a040ea2478864295282234aaada",
+    },
+];
@@ -0,0 +1,431 @@
+//! Generic implementation of the [CTR_DRBG][1] deterministic random bit
+//! generator over block ciphers.
+//!
+//! `CtrDrbg` implements the DRBG mechanism itself: it is instantiated and
+//! reseeded using caller provided entropy input and never obtains entropy on
+//! its own. The DRBG can be used with or without derivation function, the
+//! latter requires full entropy input with length equal to `seedlen`, i.e.
+//! key size plus block size of the cipher.
+//!
+//! `CtrDrbgRng` combines the DRBG with an entropy source and implements
+//! `RngCore` and `CryptoRng` traits from the `rand_core` crate. It reseeds
+//! automatically when the reseed interval is reached and supports prediction
+//! resistance, in which case the DRBG is reseeded before every request.
+//!
+//! Counter field occupies the whole block, request size is limited to
+//! `2^16` bytes for ciphers with 128-bit or larger blocks and to `2^10` bytes
+//! for ciphers with 64-bit blocks (e.g. TDEA).
+//!
+//! # Usage example
+//! ```rust,ignore
+//! use ctr_drbg::CtrDrbg;
+//!
+//! let mut drbg = CtrDrbg::<Aes256>::new(&entropy, &nonce, b"app").unwrap();
+//! let mut buf = [0u8; 32];
+//! drbg.generate(&mut buf, &[]).unwrap();
+//! ```
+//!
+//! [1]: https://csrc.nist.gov/publications/detail/sp/800-90a/rev-1/final
+#![no_std]
+pub extern crate block_cipher_trait;
+pub extern crate rand_core;
+
+use block_cipher_trait::BlockEncrypt;
+use block_cipher_trait::generic_array::GenericArray;
+use block_cipher_trait::generic_array::typenum::Unsigned;
+use core::fmt;
+use core::num::NonZeroU32;
+
+mod rng;
+
+#[cfg(feature = "dev")]
+pub mod dev;
+
+pub use rng::CtrDrbgRng;
+
+type Block<C> = GenericArray<u8, <C as BlockEncrypt>::BlockSize>;
+type Key<C> = GenericArray<u8, <C as BlockEncrypt>::KeySize>;
+type ParBlocks<C> = GenericArray<Block<C>, <C as BlockEncrypt>::ParBlocks>;
+
+/// Error type for CTR_DRBG operations
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// Length of the entropy input is not supported
+    InvalidEntropyLength,
+    /// Nonce is shorter than half of the key size
+    InvalidNonceLength,
+    /// Personalization string or additional input is too long
+    InputTooLong,
+    /// Number of requested bytes exceeds the limit per request
+    RequestTooLarge,
+    /// Reseed counter has exceeded the reseed interval
+    ReseedRequired,
+}
+
+impl Error {
+    fn code(&self) -> u32 {
+        match *self {
+            Error::InvalidEntropyLength => 0,
+            Error::InvalidNonceLength => 1,
+            Error::InputTooLong => 2,
+            Error::RequestTooLarge => 3,
+            Error::ReseedRequired => 4,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Error::InvalidEntropyLength => "invalid entropy input length",
+            Error::InvalidNonceLength => "invalid nonce length",
+            Error::InputTooLong => "input is too long",
+            Error::RequestTooLarge => "request is too large",
+            Error::ReseedRequired => "reseed is required",
+        })
+    }
+}
+
+impl From<Error> for rand_core::Error {
+    fn from(err: Error) -> rand_core::Error {
+        let code = rand_core::Error::CUSTOM_START + err.code();
+        rand_core::Error::from(NonZeroU32::new(code).unwrap())
+    }
+}
+
+/// CTR_DRBG instance over block cipher `C`.
+pub struct CtrDrbg<C: BlockEncrypt> {
+    cipher: C,
+    v: Block<C>,
+    reseed_counter: u64,
+    reseed_interval: u64,
+    df: bool,
+}
+
+impl<C: BlockEncrypt> CtrDrbg<C> {
+    /// Instantiate DRBG which uses derivation function.
+    ///
+    /// Entropy input must be at least as long as the cipher key, nonce must
+    /// be at least half as long as the cipher key.
+    pub fn new(entropy: &[u8], nonce: &[u8], personalization: &[u8])
+        -> Result<Self, Error>
+    {
+        Self::instantiate(&[entropy], nonce, personalization, true)
+    }
+
+    /// Instantiate DRBG which does not use derivation function.
+    ///
+    /// Entropy input must be exactly `seedlen` bytes long, personalization
+    /// string must not be longer than `seedlen` bytes.
+    pub fn new_without_df(entropy: &[u8], personalization: &[u8])
+        -> Result<Self, Error>
+    {
+        Self::instantiate(&[entropy], &[], personalization, false)
+    }
+
+    /// Length of the seed in bytes, i.e. key size plus block size.
+    pub fn seed_len() -> usize {
+        C::KeySize::to_usize() + C::BlockSize::to_usize()
+    }
+
+    /// Maximal number of bytes which can be generated per request.
+    pub fn max_request_len() -> usize {
+        if C::BlockSize::to_usize() >= 16 { 1 << 16 } else { 1 << 10 }
+    }
+
+    /// Maximal number of requests between reseeds.
+    pub fn max_reseed_interval() -> u64 {
+        if C::BlockSize::to_usize() >= 16 { 1 << 48 } else { 1 << 32 }
+    }
+
+    /// Set number of requests after which reseed is required. Values bigger
+    /// than `max_reseed_interval()` are clamped.
+    pub fn set_reseed_interval(&mut self, interval: u64) {
+        self.reseed_interval = core::cmp::min(interval,
+            Self::max_reseed_interval());
+    }
+
+    /// Current value of the reseed counter, i.e. number of requests since
+    /// the last reseed plus one.
+    pub fn reseed_counter(&self) -> u64 {
+        self.reseed_counter
+    }
+
+    /// Returns `true` if DRBG uses derivation function.
+    pub fn uses_df(&self) -> bool {
+        self.df
+    }
+
+    /// Reseed DRBG using entropy input and optional additional input.
+    ///
+    /// Requirements for lengths of the inputs are the same as for the
+    /// instantiation.
+    pub fn reseed(&mut self, entropy: &[u8], additional_input: &[u8])
+        -> Result<(), Error>
+    {
+        self.reseed_parts(&[entropy], additional_input)
+    }
+
+    /// Fill `out` with pseudorandom bytes using optional additional input.
+    ///
+    /// Returns `Error::ReseedRequired` if reseed interval has been reached.
+    pub fn generate(&mut self, out: &mut [u8], additional_input: &[u8])
+        -> Result<(), Error>
+    {
+        if out.len() > Self::max_request_len() {
+            return Err(Error::RequestTooLarge);
+        }
+        if self.reseed_counter > self.reseed_interval {
+            return Err(Error::ReseedRequired);
+        }
+        let mut seed = Seed::<C>::default();
+        if !additional_input.is_empty() {
+            self.seed_material(&mut seed, &[], &[additional_input])?;
+            self.update(&seed);
+        }
+        self.generate_blocks(out);
+        self.update(&seed);
+        self.reseed_counter += 1;
+        Ok(())
+    }
+
+    /// Generate request with prediction resistance: reseed DRBG using fresh
+    /// entropy input and additional input, then fill `out` with pseudorandom
+    /// bytes.
+    pub fn generate_with_prediction_resistance(
+        &mut self, entropy: &[u8], out: &mut [u8], additional_input: &[u8],
+    ) -> Result<(), Error> {
+        if out.len() > Self::max_request_len() {
+            return Err(Error::RequestTooLarge);
+        }
+        self.reseed(entropy, additional_input)?;
+        self.generate(out, &[])
+    }
+
+    /// Instantiate DRBG using concatenation of `entropy` parts.
+    pub(crate) fn instantiate(
+        entropy: &[&[u8]], nonce: &[u8], personalization: &[u8], df: bool,
+    ) -> Result<Self, Error> {
+        let mut drbg = Self {
+            cipher: C::new(&Key::<C>::default()),
+            v: Block::<C>::default(),
+            reseed_counter: 1,
+            reseed_interval: Self::max_reseed_interval(),
+            df,
+        };
+        if df && nonce.len() < (C::KeySize::to_usize() + 1) / 2 {
+            return Err(Error::InvalidNonceLength);
+        }
+        let mut seed = Seed::<C>::default();
+        drbg.seed_material(&mut seed, entropy, &[nonce, personalization])?;
+        drbg.update(&seed);
+        Ok(drbg)
+    }
+
+    /// Reseed DRBG using concatenation of `entropy` parts.
+    pub(crate) fn reseed_parts(
+        &mut self, entropy: &[&[u8]], additional_input: &[u8],
+    ) -> Result<(), Error> {
+        let mut seed = Seed::<C>::default();
+        self.seed_material(&mut seed, entropy, &[additional_input])?;
+        self.update(&seed);
+        self.reseed_counter = 1;
+        Ok(())
+    }
+
+    /// Compute seed material from entropy input parts and other inputs,
+    /// which are concatenated if derivation function is used or xored
+    /// otherwise. Entropy length is not checked if `entropy` is empty, which
+    /// is used for processing of additional input.
+    fn seed_material(
+        &self, seed: &mut Seed<C>, entropy: &[&[u8]], inputs: &[&[u8]],
+    ) -> Result<(), Error> {
+        let entropy_len: usize = entropy.iter().map(|e| e.len()).sum();
+        let inputs_len: usize = inputs.iter().map(|i| i.len()).sum();
+        if self.df {
+            if !entropy.is_empty() && entropy_len < C::KeySize::to_usize() {
+                return Err(Error::InvalidEntropyLength);
+            }
+            // length of the derivation function input is encoded as u32
+            if (entropy_len + inputs_len) as u64 > u32::MAX as u64 {
+                return Err(Error::InputTooLong);
+            }
+            let parts = entropy.iter().chain(inputs.iter()).cloned();
+            derive::<C, _>(parts, entropy_len + inputs_len, seed);
+        } else {
+            if !entropy.is_empty() && entropy_len != Self::seed_len() {
+                return Err(Error::InvalidEntropyLength);
+            }
+            if inputs_len > Self::seed_len() {
+                return Err(Error::InputTooLong);
+            }
+            seed.xor_in(entropy.iter().flat_map(|e| e.iter()));
+            seed.xor_in(inputs.iter().flat_map(|i| i.iter()));
+        }
+        Ok(())
+    }
+
+    /// `CTR_DRBG_Update` function.
+    fn update(&mut self, provided_data: &Seed<C>) {
+        let mut temp = Seed::<C>::default();
+        {
+            let (cipher, v) = (&self.cipher, &mut self.v);
+            temp.fill_with(|| {
+                increment(v);
+                let mut block = v.clone();
+                cipher.encrypt_block(&mut block);
+                block
+            });
+        }
+        temp.xor_in(provided_data.key.iter().chain(provided_data.v.iter()));
+        self.cipher = C::new(&temp.key);
+        self.v = temp.v;
+    }
+
+    /// Fill `out` with encrypted successive values of `V`.
+    fn generate_blocks(&mut self, out: &mut [u8]) {
+        let bs = C::BlockSize::to_usize();
+        let pb = C::ParBlocks::to_usize();
+        for chunk in out.chunks_mut(bs * pb) {
+            if chunk.len() == bs * pb {
+                let mut blocks = ParBlocks::<C>::default();
+                for block in blocks.iter_mut() {
+                    increment(&mut self.v);
+                    *block = self.v.clone();
+                }
+                self.cipher.encrypt_blocks(&mut blocks);
+                for (c, block) in chunk.chunks_mut(bs).zip(blocks.iter()) {
+                    c.copy_from_slice(block);
+                }
+            } else {
+                for c in chunk.chunks_mut(bs) {
+                    increment(&mut self.v);
+                    let mut block = self.v.clone();
+                    self.cipher.encrypt_block(&mut block);
+                    c.copy_from_slice(&block[..c.len()]);
+                }
+            }
+        }
+    }
+}
+
+/// Seed material of `seedlen` bytes, which is split into key and block
+/// parts, so its size can be expressed without type-level arithmetic.
+pub(crate) struct Seed<C: BlockEncrypt> {
+    pub(crate) key: Key<C>,
+    pub(crate) v: Block<C>,
+}
+
+impl<C: BlockEncrypt> Default for Seed<C> {
+    fn default() -> Self {
+        Self { key: Default::default(), v: Default::default() }
+    }
+}
+
+impl<C: BlockEncrypt> Seed<C> {
+    /// Xor `data` into the seed starting from its first byte.
+    fn xor_in<'a, I: IntoIterator<Item = &'a u8>>(&mut self, data: I) {
+        let bytes = self.key.iter_mut().chain(self.v.iter_mut());
+        for (a, b) in bytes.zip(data) {
+            *a ^= *b;
+        }
+    }
+
+    /// Fill seed with the leftmost `seedlen` bytes of concatenated blocks
+    /// produced by `f`.
+    fn fill_with<F: FnMut() -> Block<C>>(&mut self, mut f: F) {
+        let bs = C::BlockSize::to_usize();
+        let n = (C::KeySize::to_usize() + 2 * bs - 1) / bs;
+        let mut bytes = self.key.iter_mut().chain(self.v.iter_mut());
+        for _ in 0..n {
+            let block = f();
+            // block goes first, so no extra byte is taken from `bytes`
+            for (b, a) in block.iter().zip(bytes.by_ref()) {
+                *a = *b;
+            }
+        }
+    }
+}
+
+/// `Block_Cipher_df` derivation function which writes `seedlen` bytes
+/// derived from the concatenation of `parts` with total length `len` into
+/// `out`.
+fn derive<'a, C, I>(parts: I, len: usize, out: &mut Seed<C>)
+    where C: BlockEncrypt, I: Iterator<Item = &'a [u8]> + Clone
+{
+    let mut key = Key::<C>::default();
+    for (i, k) in key.iter_mut().enumerate() {
+        *k = i as u8;
+    }
+    let cipher = C::new(&key);
+    let l = (len as u32).to_be_bytes();
+    let n = (CtrDrbg::<C>::seed_len() as u32).to_be_bytes();
+
+    // S = L || N || input_string || 0x80, padded with zeros
+    let mut temp = Seed::<C>::default();
+    let mut i = 0u32;
+    temp.fill_with(|| {
+        let mut iv = Block::<C>::default();
+        iv[..4].copy_from_slice(&i.to_be_bytes());
+        i += 1;
+        let mut bcc = Bcc::new(&cipher, iv);
+        bcc.update(&l);
+        bcc.update(&n);
+        for part in parts.clone() {
+            bcc.update(part);
+        }
+        bcc.update(&[0x80]);
+        bcc.finalize()
+    });
+
+    let cipher = C::new(&temp.key);
+    let mut x = temp.v;
+    out.fill_with(|| {
+        cipher.encrypt_block(&mut x);
+        x.clone()
+    });
+}
+
+/// `BCC` function, which is CBC-MAC with zero IV over zero padded data.
+struct Bcc<'a, C: 'a + BlockEncrypt> {
+    cipher: &'a C,
+    state: Block<C>,
+    pos: usize,
+}
+
+impl<'a, C: BlockEncrypt> Bcc<'a, C> {
+    /// Create new instance and process the first block of data.
+    fn new(cipher: &'a C, mut block: Block<C>) -> Self {
+        cipher.encrypt_block(&mut block);
+        Self { cipher, state: block, pos: 0 }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for &b in data {
+            self.state[self.pos] ^= b;
+            self.pos += 1;
+            if self.pos == self.state.len() {
+                self.cipher.encrypt_block(&mut self.state);
+                self.pos = 0;
+            }
+        }
+    }
+
+    fn finalize(mut self) -> Block<C> {
+        if self.pos != 0 {
+            self.cipher.encrypt_block(&mut self.state);
+        }
+        self.state
+    }
+}
+
+/// Increment block as a big endian integer modulo `2^blocklen`.
+#[inline(always)]
+fn increment(block: &mut [u8]) {
+    for b in block.iter_mut().rev() {
+        *b = b.wrapping_add(1);
+        if *b != 0 {
+            break;
+        }
+    }
+}
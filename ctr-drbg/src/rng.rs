@@ -0,0 +1,115 @@
+use block_cipher_trait::BlockEncrypt;
+use block_cipher_trait::generic_array::typenum::Unsigned;
+use rand_core::{CryptoRng, Error, RngCore, impls};
+use {CtrDrbg, Key, Seed};
+
+/// CTR_DRBG over block cipher `C` combined with entropy source `E`.
+///
+/// Entropy input is obtained from the source during instantiation, when the
+/// reseed interval is reached, on explicit `reseed` calls and before every
+/// request if prediction resistance is enabled. With derivation function
+/// entropy input is as long as the cipher key, without derivation function
+/// it has length of `seedlen`, so the source must provide full entropy.
+pub struct CtrDrbgRng<C: BlockEncrypt, E: RngCore> {
+    drbg: CtrDrbg<C>,
+    source: E,
+    prediction_resistance: bool,
+}
+
+impl<C: BlockEncrypt, E: RngCore> CtrDrbgRng<C, E> {
+    /// Instantiate DRBG which uses derivation function, nonce is obtained
+    /// from the entropy source as well.
+    pub fn new(mut source: E, personalization: &[u8]) -> Result<Self, Error> {
+        let mut entropy = Key::<C>::default();
+        source.try_fill_bytes(&mut entropy)?;
+        let mut nonce = Key::<C>::default();
+        let nonce = &mut nonce[..(C::KeySize::to_usize() + 1) / 2];
+        source.try_fill_bytes(nonce)?;
+        let drbg = CtrDrbg::new(&entropy, nonce, personalization)?;
+        Ok(Self { drbg, source, prediction_resistance: false })
+    }
+
+    /// Instantiate DRBG which does not use derivation function.
+    pub fn new_without_df(mut source: E, personalization: &[u8])
+        -> Result<Self, Error>
+    {
+        let mut entropy = Seed::<C>::default();
+        source.try_fill_bytes(&mut entropy.key)?;
+        source.try_fill_bytes(&mut entropy.v)?;
+        let parts: &[&[u8]] = &[&entropy.key, &entropy.v];
+        let drbg = CtrDrbg::instantiate(parts, &[], personalization, false)?;
+        Ok(Self { drbg, source, prediction_resistance: false })
+    }
+
+    /// Enable or disable prediction resistance. If enabled DRBG is reseeded
+    /// before every request.
+    pub fn set_prediction_resistance(&mut self, enabled: bool) {
+        self.prediction_resistance = enabled;
+    }
+
+    /// Set number of requests after which DRBG is reseeded automatically.
+    pub fn set_reseed_interval(&mut self, interval: u64) {
+        self.drbg.set_reseed_interval(interval);
+    }
+
+    /// Reseed DRBG using entropy input from the source and optional
+    /// additional input.
+    pub fn reseed(&mut self, additional_input: &[u8]) -> Result<(), Error> {
+        let mut entropy = Seed::<C>::default();
+        self.source.try_fill_bytes(&mut entropy.key)?;
+        if self.drbg.uses_df() {
+            self.drbg.reseed_parts(&[&entropy.key], additional_input)?;
+        } else {
+            self.source.try_fill_bytes(&mut entropy.v)?;
+            let parts: &[&[u8]] = &[&entropy.key, &entropy.v];
+            self.drbg.reseed_parts(parts, additional_input)?;
+        }
+        Ok(())
+    }
+
+    /// Fill `out` with pseudorandom bytes using optional additional input.
+    ///
+    /// If reseed is required or prediction resistance is enabled, additional
+    /// input is used for reseeding instead.
+    pub fn generate(&mut self, out: &mut [u8], additional_input: &[u8])
+        -> Result<(), Error>
+    {
+        if out.len() > CtrDrbg::<C>::max_request_len() {
+            return Err(::Error::RequestTooLarge.into());
+        }
+        if !self.prediction_resistance {
+            match self.drbg.generate(out, additional_input) {
+                Err(::Error::ReseedRequired) => (),
+                res => return res.map_err(Into::into),
+            }
+        }
+        self.reseed(additional_input)?;
+        self.drbg.generate(out, &[])?;
+        Ok(())
+    }
+}
+
+impl<C: BlockEncrypt, E: RngCore> RngCore for CtrDrbgRng<C, E> {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        impls::next_u64_via_fill(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).expect("CTR_DRBG failure");
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        for chunk in dest.chunks_mut(CtrDrbg::<C>::max_request_len()) {
+            self.generate(chunk, &[])?;
+        }
+        Ok(())
+    }
+}
+
+impl<C, E> CryptoRng for CtrDrbgRng<C, E>
+    where C: BlockEncrypt, E: RngCore + CryptoRng
+{}
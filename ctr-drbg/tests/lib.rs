@@ -0,0 +1,14 @@
+extern crate aes;
+#[macro_use(impl_cipher)]
+extern crate block_cipher_trait;
+#[macro_use]
+extern crate ctr_drbg;
+
+use block_cipher_trait::generic_array::typenum::{U16, U32};
+
+impl_cipher!(Aes128, aes::Aes128, U16, U16);
+impl_cipher!(Aes256, aes::Aes256, U32, U16);
+
+new_test!(aes128_df, ctr_drbg::dev::AES128_DF, Aes128, true);
+new_test!(aes256_df, ctr_drbg::dev::AES256_DF, Aes256, true);
+new_test!(aes256_no_df, ctr_drbg::dev::AES256_NO_DF, Aes256, false);
@@ -0,0 +1,93 @@
+//! Counter flavors which define how counter block is derived from nonce
+//! and block index.
+use block_cipher_trait::generic_array::{GenericArray, ArrayLength};
+use block_cipher_trait::generic_array::typenum::U16;
+
+/// Trait implemented by CTR flavors for blocks of size `N`.
+pub trait CtrFlavor<N: ArrayLength<u8>> {
+    /// Number of keystream blocks after which counter wraps around or `None`
+    /// if it's greater than `u64::MAX`.
+    fn max_blocks() -> Option<u64>;
+
+    /// Write counter block with index `n` derived from `nonce` into `block`.
+    fn counter_block(
+        nonce: &GenericArray<u8, N>, n: u64, block: &mut GenericArray<u8, N>,
+    );
+}
+
+/// 128-bit big endian counter which spans the whole block.
+#[derive(Clone, Copy, Debug)]
+pub enum Ctr128BE {}
+
+/// 128-bit little endian counter which spans the whole block.
+#[derive(Clone, Copy, Debug)]
+pub enum Ctr128LE {}
+
+/// 32-bit big endian counter stored in the last 4 bytes of the block.
+///
+/// This flavor is used by GCM.
+#[derive(Clone, Copy, Debug)]
+pub enum Ctr32BE {}
+
+/// 32-bit little endian counter stored in the first 4 bytes of the block.
+///
+/// This flavor is used by GCM-SIV.
+#[derive(Clone, Copy, Debug)]
+pub enum Ctr32LE {}
+
+/// Add `n` to the little endian number stored in `buf` modulo `2^(8*len)`.
+#[inline(always)]
+fn add_le<'a, I>(buf: I, mut n: u64) where I: Iterator<Item=&'a mut u8> {
+    let mut carry = 0u16;
+    for b in buf {
+        let sum = *b as u16 + (n & 0xFF) as u16 + carry;
+        *b = sum as u8;
+        carry = sum >> 8;
+        n >>= 8;
+    }
+}
+
+impl CtrFlavor<U16> for Ctr128BE {
+    fn max_blocks() -> Option<u64> { None }
+
+    fn counter_block(
+        nonce: &GenericArray<u8, U16>, n: u64, block: &mut GenericArray<u8, U16>,
+    ) {
+        block.clone_from(nonce);
+        add_le(block.iter_mut().rev(), n);
+    }
+}
+
+impl CtrFlavor<U16> for Ctr128LE {
+    fn max_blocks() -> Option<u64> { None }
+
+    fn counter_block(
+        nonce: &GenericArray<u8, U16>, n: u64, block: &mut GenericArray<u8, U16>,
+    ) {
+        block.clone_from(nonce);
+        add_le(block.iter_mut(), n);
+    }
+}
+
+impl<N: ArrayLength<u8>> CtrFlavor<N> for Ctr32BE {
+    fn max_blocks() -> Option<u64> { Some(1 << 32) }
+
+    fn counter_block(
+        nonce: &GenericArray<u8, N>, n: u64, block: &mut GenericArray<u8, N>,
+    ) {
+        block.clone_from(nonce);
+        let len = block.len();
+        add_le(block[len - 4..].iter_mut().rev(), n & 0xFFFF_FFFF);
+    }
+}
+
+impl<N: ArrayLength<u8>> CtrFlavor<N> for Ctr32LE {
+    fn max_blocks() -> Option<u64> { Some(1 << 32) }
+
+    fn counter_block(
+        nonce: &GenericArray<u8, N>, n: u64, block: &mut GenericArray<u8, N>,
+    ) {
+        block.clone_from(nonce);
+        add_le(block[..4].iter_mut(), n & 0xFFFF_FFFF);
+    }
+}
@@ -0,0 +1,216 @@
+//! Generic implementation of the [Counter (CTR)][1] mode which turns any
+//! block cipher into a synchronous stream cipher.
+//!
+//! Counter block layout is defined by a flavor from the `flavors` module.
+//! Keystream is generated in batches of `BlockEncryptMut::ParBlocks` counter
+//! blocks using `encrypt_blocks` method of the cipher. Ciphers which only
+//! implement `BlockEncryptMut` can be used with the `Ctr::from_cipher`
+//! constructor.
+//!
+//! # Usage example
+//! ```rust,ignore
+//! use ctr::Ctr128BE;
+//! use ctr::stream_cipher::{NewStreamCipher, SyncStreamCipher};
+//!
+//! let mut cipher = Ctr128BE::<Aes128>::new(&key, &nonce);
+//! cipher.apply_keystream(&mut data);
+//! ```
+//!
+//! [1]: https://en.wikipedia.org/wiki/Block_cipher_mode_of_operation#Counter_(CTR)
+#![no_std]
+pub extern crate block_cipher_trait;
+pub extern crate stream_cipher;
+
+use block_cipher_trait::{BlockEncrypt, BlockEncryptMut};
+use block_cipher_trait::generic_array::GenericArray;
+use block_cipher_trait::generic_array::typenum::Unsigned;
+use stream_cipher::{
+    NewStreamCipher, SyncStreamCipher, SyncStreamCipherSeek,
+    InvalidKeyNonceLength, LoopError,
+};
+use core::marker::PhantomData;
+
+pub mod flavors;
+
+use flavors::CtrFlavor;
+
+type Block<C> = GenericArray<u8, <C as BlockEncryptMut>::BlockSize>;
+type ParBlocks<C> = 
+    GenericArray<Block<C>, <C as BlockEncryptMut>::ParBlocks>;
+
+/// CTR mode with 128-bit big endian counter.
+pub type Ctr128BE<C> = Ctr<C, flavors::Ctr128BE>;
+/// CTR mode with 128-bit little endian counter.
+pub type Ctr128LE<C> = Ctr<C, flavors::Ctr128LE>;
+/// CTR mode with 32-bit big endian counter.
+pub type Ctr32BE<C> = Ctr<C, flavors::Ctr32BE>;
+/// CTR mode with 32-bit little endian counter.
+pub type Ctr32LE<C> = Ctr<C, flavors::Ctr32LE>;
+
+/// CTR mode instance over block cipher `C` with counter flavor `F`.
+pub struct Ctr<C, F>
+    where C: BlockEncryptMut, F: CtrFlavor<C::BlockSize>
+{
+    cipher: C,
+    nonce: Block<C>,
+    /// Index of the next keystream block to be generated
+    counter: u64,
+    /// Keystream block which was partially used
+    buffer: Block<C>,
+    /// Number of used bytes in `buffer`, zero if there is no buffered block
+    pos: usize,
+    _flavor: PhantomData<F>,
+}
+
+impl<C, F> Ctr<C, F>
+    where C: BlockEncryptMut, F: CtrFlavor<C::BlockSize>
+{
+    /// Create new CTR mode instance from initialized block cipher and
+    /// initial counter block.
+    pub fn from_cipher(cipher: C, nonce: &Block<C>) -> Self {
+        Self {
+            cipher,
+            nonce: nonce.clone(),
+            counter: 0,
+            buffer: Default::default(),
+            pos: 0,
+            _flavor: PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    fn block_size() -> u64 {
+        C::BlockSize::to_u64()
+    }
+
+    /// Number of keystream bytes left or `None` if it's greater than
+    /// `u64::MAX`.
+    fn remaining(&self) -> Option<u64> {
+        let max = F::max_blocks()?;
+        max.checked_mul(Self::block_size())
+            .map(|max| max - self.current_pos())
+    }
+
+    fn generate_block(&mut self, block: &mut Block<C>) {
+        F::counter_block(&self.nonce, self.counter, block);
+        self.cipher.encrypt_block(block);
+        self.counter = self.counter.wrapping_add(1);
+    }
+
+    fn apply_blocks(&mut self, data: &mut [u8]) {
+        let bs = C::BlockSize::to_usize();
+        let pb = C::ParBlocks::to_usize();
+
+        let mut ks = ParBlocks::<C>::default();
+        for chunk in data.chunks_mut(bs * pb) {
+            if chunk.len() == bs * pb {
+                for block in ks.iter_mut() {
+                    F::counter_block(&self.nonce, self.counter, block);
+                    self.counter = self.counter.wrapping_add(1);
+                }
+                self.cipher.encrypt_blocks(&mut ks);
+                for (block, k) in chunk.chunks_mut(bs).zip(ks.iter()) {
+                    xor(block, k);
+                }
+            } else {
+                for block in chunk.chunks_mut(bs) {
+                    let mut k = Block::<C>::default();
+                    self.generate_block(&mut k);
+                    xor(block, &k[..block.len()]);
+                    if block.len() != bs {
+                        self.buffer = k;
+                        self.pos = block.len();
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<C, F> NewStreamCipher for Ctr<C, F>
+    where C: BlockEncrypt, F: CtrFlavor<C::BlockSize>
+{
+    type KeySize = C::KeySize;
+    type NonceSize = C::BlockSize;
+
+    fn new(key: &GenericArray<u8, C::KeySize>, nonce: &Block<C>) -> Self {
+        Self::from_cipher(C::new(key), nonce)
+    }
+
+    fn new_var(key: &[u8], nonce: &[u8])
+        -> Result<Self, InvalidKeyNonceLength>
+    {
+        if nonce.len() != C::BlockSize::to_usize() {
+            return Err(InvalidKeyNonceLength);
+        }
+        let cipher = C::new_varkey(key).map_err(|_| InvalidKeyNonceLength)?;
+        Ok(Self::from_cipher(cipher, GenericArray::from_slice(nonce)))
+    }
+}
+
+impl<C, F> SyncStreamCipher for Ctr<C, F>
+    where C: BlockEncryptMut, F: CtrFlavor<C::BlockSize>
+{
+    fn try_apply_keystream(&mut self, mut data: &mut [u8])
+        -> Result<(), LoopError>
+    {
+        if let Some(rem) = self.remaining() {
+            if data.len() as u64 > rem {
+                return Err(LoopError);
+            }
+        }
+
+        if self.pos != 0 {
+            let bs = C::BlockSize::to_usize();
+            let n = core::cmp::min(bs - self.pos, data.len());
+            xor(&mut data[..n], &self.buffer[self.pos..self.pos + n]);
+            self.pos = (self.pos + n) % bs;
+            data = &mut data[n..];
+        }
+        self.apply_blocks(data);
+        Ok(())
+    }
+}
+
+impl<C, F> SyncStreamCipherSeek for Ctr<C, F>
+    where C: BlockEncryptMut, F: CtrFlavor<C::BlockSize>
+{
+    fn current_pos(&self) -> u64 {
+        // buffered block was generated with the previous counter value
+        let (block, offset) = if self.pos == 0 {
+            (self.counter, 0)
+        } else {
+            (self.counter.wrapping_sub(1), self.pos as u64)
+        };
+        block.checked_mul(Self::block_size())
+            .and_then(|pos| pos.checked_add(offset))
+            .expect("keystream position does not fit into u64")
+    }
+
+    fn try_seek(&mut self, pos: u64) -> Result<(), LoopError> {
+        let bs = Self::block_size();
+        let (block, offset) = (pos / bs, pos % bs);
+        if let Some(max) = F::max_blocks() {
+            if block > max || (block == max && offset != 0) {
+                return Err(LoopError);
+            }
+        }
+        self.counter = block;
+        self.pos = 0;
+        if offset != 0 {
+            let mut k = Block::<C>::default();
+            self.generate_block(&mut k);
+            self.buffer = k;
+            self.pos = offset as usize;
+        }
+        Ok(())
+    }
+}
+
+#[inline(always)]
+fn xor(buf: &mut [u8], key: &[u8]) {
+    debug_assert_eq!(buf.len(), key.len());
+    for (a, b) in buf.iter_mut().zip(key) {
+        *a ^= *b;
+    }
+}
@@ -0,0 +1,145 @@
+extern crate aes;
+#[macro_use(impl_cipher)]
+extern crate block_cipher_trait;
+extern crate ctr;
+
+use block_cipher_trait::dev::decode_hex;
+use block_cipher_trait::generic_array::GenericArray;
+use block_cipher_trait::generic_array::typenum::{U16, U32};
+use ctr::{Ctr128BE, Ctr128LE, Ctr32BE, Ctr32LE};
+use ctr::stream_cipher::{
+    LoopError, NewStreamCipher, SyncStreamCipher, SyncStreamCipherSeek,
+};
+
+impl_cipher!(Aes128, aes::Aes128, U16, U16);
+impl_cipher!(Aes256, aes::Aes256, U32, U16);
+
+// NIST SP 800-38A, F.5
+const NONCE: &str = "f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff";
+const PLAINTEXT: &str = "\
+    6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+    30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710";
+
+fn check<C: NewStreamCipher + SyncStreamCipher>(key: &str, ct: &str) {
+    let mut buf = [0u8; 64];
+    let key = decode_hex(key, &mut buf).to_vec();
+    let nonce = decode_hex(NONCE, &mut buf).to_vec();
+    let pt = decode_hex(PLAINTEXT, &mut buf).to_vec();
+    let ct = decode_hex(ct, &mut buf).to_vec();
+
+    let mut data = pt.clone();
+    C::new_var(&key, &nonce).unwrap().apply_keystream(&mut data);
+    assert_eq!(data, ct);
+
+    // process data in chunks which are not aligned to block boundaries
+    let mut cipher = C::new_var(&key, &nonce).unwrap();
+    for chunk in data.chunks_mut(7) {
+        cipher.apply_keystream(chunk);
+    }
+    assert_eq!(data, pt);
+}
+
+#[test]
+fn ctr_aes128() {
+    let key = "2b7e151628aed2a6abf7158809cf4f3c";
+    let ct = "\
+        874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff\
+        5ae4df3edbd5d35e5b4f09020db03eab1e031dda2fbe03d1792170a0f3009cee";
+    check::<Ctr128BE<Aes128>>(key, ct);
+}
+
+#[test]
+fn ctr_aes256() {
+    let key = "\
+        603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4";
+    let ct = "\
+        601ec313775789a5b7a7f504bbf3d228f443e3ca4d62b59aca84e990cacaf5c5\
+        2b0930daa23de94ce87017ba2d84988ddfc9c58db67aada613c2dd08457941a6";
+    check::<Ctr128BE<Aes256>>(key, ct);
+}
+
+/// Check that seeking to any position and applying keystream from it gives
+/// the same keystream as processing data from the beginning, and that
+/// `current_pos` agrees with the number of processed bytes.
+fn check_seek<C>(nonce: &[u8; 16])
+    where C: NewStreamCipher + SyncStreamCipher + SyncStreamCipherSeek
+{
+    let key = [0x42; 16];
+    let mut keystream = [0u8; 20*16];
+    let mut cipher = C::new_var(&key, nonce).unwrap();
+    assert_eq!(cipher.current_pos(), 0);
+    cipher.apply_keystream(&mut keystream);
+    assert_eq!(cipher.current_pos(), keystream.len() as u64);
+
+    for pos in 0..keystream.len() {
+        let mut cipher = C::new_var(&key, nonce).unwrap();
+        cipher.seek(pos as u64);
+        assert_eq!(cipher.current_pos(), pos as u64);
+        let mut buf = [0u8; 20*16];
+        let buf = &mut buf[pos..];
+        // chunk sizes which cross block boundaries at different offsets
+        let mut consumed = pos;
+        for chunk in buf.chunks_mut(1 + pos % 37) {
+            cipher.apply_keystream(chunk);
+            consumed += chunk.len();
+            assert_eq!(cipher.current_pos(), consumed as u64);
+        }
+        assert_eq!(buf, &keystream[pos..]);
+    }
+}
+
+#[test]
+fn ctr128be_seek() {
+    check_seek::<Ctr128BE<Aes128>>(&[0xFF; 16]);
+}
+
+#[test]
+fn ctr128le_seek() {
+    check_seek::<Ctr128LE<Aes128>>(&[0xFF; 16]);
+}
+
+#[test]
+fn ctr32be_seek() {
+    let mut nonce = [0x24; 16];
+    nonce[12..].copy_from_slice(&[0xFF; 4]);
+    check_seek::<Ctr32BE<Aes128>>(&nonce);
+}
+
+#[test]
+fn ctr32le_seek() {
+    let mut nonce = [0x24; 16];
+    nonce[..4].copy_from_slice(&[0xFF; 4]);
+    check_seek::<Ctr32LE<Aes128>>(&nonce);
+}
+
+/// Check handling of the end of keystream of the 32-bit counter flavors.
+fn check_keystream_end<C>()
+    where C: NewStreamCipher + SyncStreamCipher + SyncStreamCipherSeek
+{
+    let end = 16 << 32;
+    let key = GenericArray::default();
+    let nonce = GenericArray::default();
+    let mut cipher = C::new(&key, &nonce);
+    assert_eq!(cipher.try_seek(end + 1), Err(LoopError));
+    assert_eq!(cipher.try_seek(end), Ok(()));
+    assert_eq!(cipher.current_pos(), end);
+    assert_eq!(cipher.try_apply_keystream(&mut [0]), Err(LoopError));
+
+    cipher.seek(end - 20);
+    let mut buf = [0u8; 21];
+    assert_eq!(cipher.try_apply_keystream(&mut buf), Err(LoopError));
+    assert_eq!(buf, [0u8; 21]);
+    assert_eq!(cipher.current_pos(), end - 20);
+    cipher.apply_keystream(&mut buf[..20]);
+    assert_eq!(cipher.current_pos(), end);
+}
+
+#[test]
+fn ctr32be_keystream_end() {
+    check_keystream_end::<Ctr32BE<Aes128>>();
+}
+
+#[test]
+fn ctr32le_keystream_end() {
+    check_keystream_end::<Ctr32LE<Aes128>>();
+}
@@ -0,0 +1,120 @@
+//! Double and inverse double over Galois Field GF(2^n).
+//!
+//! This is an operation used by block cipher modes and MACs like CMAC, PMAC,
+//! SIV, EAX and OCB. Blocks are interpreted as big endian numbers and
+//! multiplied by `x` (or `x^-1`) modulo the lexicographically first
+//! irreducible primitive polynomial of degree `n` with a minimal number of
+//! non-zero coefficients. All operations are performed in constant time.
+#![no_std]
+pub extern crate generic_array;
+
+use generic_array::GenericArray;
+use generic_array::typenum::{U8, U16, U32};
+
+/// Double and inverse double over GF(2^n).
+pub trait Dbl {
+    /// Multiply by `x`.
+    fn dbl(self) -> Self;
+
+    /// Divide by `x`.
+    fn inv_dbl(self) -> Self;
+}
+
+const C64: u64 = 0b1_1011;
+const C128: u64 = 0b1000_0111;
+const C256: u64 = 0b100_0010_0101;
+
+impl Dbl for GenericArray<u8, U8> {
+    #[inline]
+    fn dbl(self) -> Self {
+        let mut val = to_u64(&self);
+        let mask = (val >> 63).wrapping_neg();
+        val <<= 1;
+        val ^= mask & C64;
+        GenericArray::clone_from_slice(&val.to_be_bytes())
+    }
+
+    #[inline]
+    fn inv_dbl(self) -> Self {
+        let mut val = to_u64(&self);
+        let mask = (val & 1).wrapping_neg();
+        val ^= mask & C64;
+        val >>= 1;
+        val |= mask & (1 << 63);
+        GenericArray::clone_from_slice(&val.to_be_bytes())
+    }
+}
+
+impl Dbl for GenericArray<u8, U16> {
+    #[inline]
+    fn dbl(self) -> Self {
+        let mut val = [to_u64(&self[..8]), to_u64(&self[8..])];
+        let mask = (val[0] >> 63).wrapping_neg();
+        val[0] = (val[0] << 1) | (val[1] >> 63);
+        val[1] <<= 1;
+        val[1] ^= mask & C128;
+        from_u64s(&val)
+    }
+
+    #[inline]
+    fn inv_dbl(self) -> Self {
+        let mut val = [to_u64(&self[..8]), to_u64(&self[8..])];
+        let mask = (val[1] & 1).wrapping_neg();
+        val[1] ^= mask & C128;
+        val[1] = (val[1] >> 1) | (val[0] << 63);
+        val[0] >>= 1;
+        val[0] |= mask & (1 << 63);
+        from_u64s(&val)
+    }
+}
+
+impl Dbl for GenericArray<u8, U32> {
+    #[inline]
+    fn dbl(self) -> Self {
+        let mut val = [0u64; 4];
+        for (v, chunk) in val.iter_mut().zip(self.chunks(8)) {
+            *v = to_u64(chunk);
+        }
+        let mask = (val[0] >> 63).wrapping_neg();
+        for i in 0..3 {
+            val[i] = (val[i] << 1) | (val[i + 1] >> 63);
+        }
+        val[3] <<= 1;
+        val[3] ^= mask & C256;
+        from_u64s(&val)
+    }
+
+    #[inline]
+    fn inv_dbl(self) -> Self {
+        let mut val = [0u64; 4];
+        for (v, chunk) in val.iter_mut().zip(self.chunks(8)) {
+            *v = to_u64(chunk);
+        }
+        let mask = (val[3] & 1).wrapping_neg();
+        val[3] ^= mask & C256;
+        for i in (1..4).rev() {
+            val[i] = (val[i] >> 1) | (val[i - 1] << 63);
+        }
+        val[0] >>= 1;
+        val[0] |= mask & (1 << 63);
+        from_u64s(&val)
+    }
+}
+
+#[inline(always)]
+fn to_u64(buf: &[u8]) -> u64 {
+    let mut tmp = [0u8; 8];
+    tmp.copy_from_slice(buf);
+    u64::from_be_bytes(tmp)
+}
+
+#[inline(always)]
+fn from_u64s<N>(val: &[u64]) -> GenericArray<u8, N>
+    where N: generic_array::ArrayLength<u8>
+{
+    let mut res = GenericArray::default();
+    for (chunk, v) in res.chunks_mut(8).zip(val) {
+        chunk.copy_from_slice(&v.to_be_bytes());
+    }
+    res
+}
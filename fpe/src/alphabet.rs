@@ -0,0 +1,65 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use {Error, MAX_RADIX, MIN_RADIX};
+
+/// Mapping between characters and numerals.
+///
+/// Numeral of a character is its position in the alphabet string, so radix
+/// is equal to the number of characters.
+#[derive(Clone, Debug)]
+pub struct Alphabet {
+    chars: Vec<char>,
+    /// Characters and their numerals sorted by characters
+    index: Vec<(char, u16)>,
+}
+
+impl Alphabet {
+    /// Create new alphabet from string of characters.
+    ///
+    /// Returns error if alphabet contains duplicate characters or if number
+    /// of characters is not a supported radix.
+    pub fn new(chars: &str) -> Result<Self, Error> {
+        let chars: Vec<char> = chars.chars().collect();
+        let radix = chars.len() as u64;
+        if radix < MIN_RADIX as u64 || radix > MAX_RADIX as u64 {
+            return Err(Error::InvalidRadix);
+        }
+        let mut index: Vec<(char, u16)> = chars.iter()
+            .enumerate()
+            .map(|(i, &c)| (c, i as u16))
+            .collect();
+        index.sort_unstable();
+        if index.windows(2).any(|w| w[0].0 == w[1].0) {
+            return Err(Error::InvalidAlphabet);
+        }
+        Ok(Self { chars, index })
+    }
+
+    /// Number of characters in the alphabet.
+    pub fn radix(&self) -> u32 {
+        self.chars.len() as u32
+    }
+
+    /// Convert string into numeral string.
+    ///
+    /// Returns `Error::InvalidNumeral` if string contains characters which
+    /// are not in the alphabet.
+    pub fn to_numerals(&self, s: &str) -> Result<Vec<u16>, Error> {
+        s.chars()
+            .map(|c| {
+                self.index.binary_search_by_key(&c, |&(c, _)| c)
+                    .map(|i| self.index[i].1)
+                    .map_err(|_| Error::InvalidNumeral)
+            })
+            .collect()
+    }
+
+    /// Convert numeral string into string.
+    ///
+    /// Returns `Error::InvalidNumeral` if numeral is not smaller than radix.
+    pub fn to_string(&self, x: &[u16]) -> Result<String, Error> {
+        x.iter()
+            .map(|&d| self.chars.get(d as usize).ok_or(Error::InvalidNumeral))
+            .collect()
+    }
+}
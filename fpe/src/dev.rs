@@ -0,0 +1,223 @@
+//! Test vectors and helpers for testing format-preserving encryption
+//! implementations.
+//!
+//! Usage example:
+//!
+//! ```rust,ignore
+//! #[macro_use]
+//! extern crate fpe;
+//!
+//! new_ff1_test!(ff1_aes128, fpe::dev::FF1_AES128, Aes128);
+//! new_ff3_1_test!(ff3_1_aes128, fpe::dev::FF3_1_AES128, Aes128);
+//! ```
+use block_cipher_trait::BlockEncrypt;
+use block_cipher_trait::dev::decode_hex as decode;
+use block_cipher_trait::generic_array::typenum::U16;
+use super::{Alphabet, Error, Ff1, Ff3_1, Fpe};
+
+/// FPE test vector. Key and tweak are hex encoded, plaintext and ciphertext
+/// consist of characters from the alphabet.
+pub struct Test {
+    pub key: &'static str,
+    pub tweak: &'static str,
+    pub alphabet: &'static str,
+    pub pt: &'static str,
+    pub ct: &'static str,
+}
+
+/// Define test which runs FF1 test vectors `$tests` for block cipher
+/// `$cipher` using `run_ff1_tests`.
+#[macro_export]
+macro_rules! new_ff1_test {
+    ($name:ident, $tests:expr, $cipher:ty) => {
+        #[test]
+        fn $name() {
+            fpe::dev::run_ff1_tests::<$cipher>($tests);
+        }
+    }
+}
+
+/// Define test which runs FF3-1 test vectors `$tests` for block cipher
+/// `$cipher` using `run_ff3_1_tests`.
+#[macro_export]
+macro_rules! new_ff3_1_test {
+    ($name:ident, $tests:expr, $cipher:ty) => {
+        #[test]
+        fn $name() {
+            fpe::dev::run_ff3_1_tests::<$cipher>($tests);
+        }
+    }
+}
+
+/// Run FF1 tests for block cipher `C`.
+pub fn run_ff1_tests<C: BlockEncrypt<BlockSize = U16>>(tests: &[Test]) {
+    run_tests("FF1", tests, Ff1::<C>::new_varkey);
+}
+
+/// Run FF3-1 tests for block cipher `C`.
+pub fn run_ff3_1_tests<C: BlockEncrypt<BlockSize = U16>>(tests: &[Test]) {
+    run_tests("FF3-1", tests, Ff3_1::<C>::new_varkey);
+}
+
+/// Besides encryption and decryption it checks that numeral strings which
+/// are too short or too long are rejected.
+fn run_tests<F, N>(name: &str, tests: &[Test], new: N)
+    where F: Fpe, N: Fn(&[u8], u32) -> Result<F, Error>
+{
+    for (i, t) in tests.iter().enumerate() {
+        let (mut kb, mut tb) = ([0u8; 32], [0u8; 32]);
+        let alphabet = Alphabet::new(t.alphabet).unwrap();
+        let fpe = new(decode(t.key, &mut kb), alphabet.radix()).unwrap();
+        let tweak = decode(t.tweak, &mut tb);
+
+        let res = fpe.encrypt_str(&alphabet, tweak, t.pt);
+        if res.as_ref().map(|ct| ct.as_str()) != Ok(t.ct) {
+            panic!("\nFailed {} encryption test №{}\n\
+                key:\t{}\ntweak:\t{}\nplaintext:\t{}\n\
+                expected ciphertext:\t{}\nresult:\t{:?}\n",
+                name, i, t.key, t.tweak, t.pt, t.ct, res,
+            );
+        }
+        let res = fpe.decrypt_str(&alphabet, tweak, t.ct);
+        if res.as_ref().map(|pt| pt.as_str()) != Ok(t.pt) {
+            panic!("\nFailed {} decryption test №{}\n", name, i);
+        }
+
+        let short = vec![0; fpe.min_len() - 1];
+        let mut rejected = fpe.encrypt(tweak, &short)
+            == Err(Error::InvalidLength);
+        // FF1 maximal length is too big to be checked
+        if fpe.max_len() < 1 << 16 {
+            let long = vec![0; fpe.max_len() + 1];
+            rejected &= fpe.encrypt(tweak, &long) == Err(Error::InvalidLength);
+        }
+        if !rejected {
+            panic!("\nFailed {} length check test №{}\n", name, i);
+        }
+    }
+}
+
+const DIGITS: &str = "0123456789";
+const BASE36: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// FF1-AES128 samples 1-3 from the NIST examples for SP 800-38G.
+pub const FF1_AES128: &[Test] = &[
+    Test {
+        key: "2B7E151628AED2A6ABF7158809CF4F3C",
+        tweak: "",
+        alphabet: DIGITS,
+        pt: "0123456789",
+        ct: "2433477484",
+    },
+    Test {
+        key: "2B7E151628AED2A6ABF7158809CF4F3C",
+        tweak: "39383736353433323130",
+        alphabet: DIGITS,
+        pt: "0123456789",
+        ct: "6124200773",
+    },
+    Test {
+        key: "2B7E151628AED2A6ABF7158809CF4F3C",
+        tweak: "3737373770717273373737",
+        alphabet: BASE36,
+        pt: "0123456789abcdefghi",
+        ct: "a9tv40mll9kdu509eum",
+    },
+];
+
+/// FF1-AES192 samples 4-6 from the NIST examples for SP 800-38G.
+pub const FF1_AES192: &[Test] = &[
+    Test {
+        key: "2B7E151628AED2A6ABF7158809CF4F3CEF4359D8D580AA4F",
+        tweak: "",
+        alphabet: DIGITS,
+        pt: "0123456789",
+        ct: "2830668132",
+    },
+    Test {
+        key: "2B7E151628AED2A6ABF7158809CF4F3CEF4359D8D580AA4F",
+        tweak: "39383736353433323130",
+        alphabet: DIGITS,
+        pt: "0123456789",
+        ct: "2496655549",
+    },
+    Test {
+        key: "2B7E151628AED2A6ABF7158809CF4F3CEF4359D8D580AA4F",
+        tweak: "3737373770717273373737",
+        alphabet: BASE36,
+        pt: "0123456789abcdefghi",
+        ct: "xbj3kv35jrawxv32ysr",
+    },
+];
+
+/// FF1-AES256 samples 7-9 from the NIST examples for SP 800-38G.
+pub const FF1_AES256: &[Test] = &[
+    Test {
+        key: "2B7E151628AED2A6ABF7158809CF4F3C\
+            EF4359D8D580AA4F7F036D6F04FC6A94",
+        tweak: "",
+        alphabet: DIGITS,
+        pt: "0123456789",
+        ct: "6657667009",
+    },
+    Test {
+        key: "2B7E151628AED2A6ABF7158809CF4F3C\
+            EF4359D8D580AA4F7F036D6F04FC6A94",
+        tweak: "39383736353433323130",
+        alphabet: DIGITS,
+        pt: "0123456789",
+        ct: "1001623463",
+    },
+    Test {
+        key: "2B7E151628AED2A6ABF7158809CF4F3C\
+            EF4359D8D580AA4F7F036D6F04FC6A94",
+        tweak: "3737373770717273373737",
+        alphabet: BASE36,
+        pt: "0123456789abcdefghi",
+        ct: "xs8a0azh2avyalyzuwd",
+    },
+];
+
+/// FF3-1-AES128 vectors. The first one is the FF3 sample 4 from the NIST
+/// examples for SP 800-38G, which uses zero tweak and so is a valid FF3-1
+/// vector, the second one is an ACVP vector.
+pub const FF3_1_AES128: &[Test] = &[
+    Test {
+        key: "EF4359D8D580AA4F7F036D6F04FC6A94",
+        tweak: "00000000000000",
+        alphabet: DIGITS,
+        pt: "89012123456789000000789000000",
+        ct: "34695224821734535122613701434",
+    },
+    Test {
+        key: "2DE79D232DF5585D68CE47882AE256D6",
+        tweak: "CBD09280979564",
+        alphabet: DIGITS,
+        pt: "3992520240",
+        ct: "8901801106",
+    },
+];
+
+/// FF3-1-AES192 vectors, FF3 sample 9 from the NIST examples for SP 800-38G.
+pub const FF3_1_AES192: &[Test] = &[
+    Test {
+        key: "EF4359D8D580AA4F7F036D6F04FC6A942B7E151628AED2A6",
+        tweak: "00000000000000",
+        alphabet: DIGITS,
+        pt: "89012123456789000000789000000",
+        ct: "98083802678820389295041483512",
+    },
+];
+
+/// FF3-1-AES256 vectors, FF3 sample 14 from the NIST examples for
+/// SP 800-38G.
+pub const FF3_1_AES256: &[Test] = &[
+    Test {
+        key: "EF4359D8D580AA4F7F036D6F04FC6A94\
+            2B7E151628AED2A6ABF7158809CF4F3C",
+        tweak: "00000000000000",
+        alphabet: DIGITS,
+        pt: "89012123456789000000789000000",
+        ct: "30859239999374053872365555822",
+    },
+];
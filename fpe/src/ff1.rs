@@ -0,0 +1,168 @@
+use alloc::vec::Vec;
+use block_cipher_trait::BlockEncrypt;
+use block_cipher_trait::generic_array::GenericArray;
+use block_cipher_trait::generic_array::typenum::U16;
+use core::mem;
+use utils::{add_mod, byte_len, num_radix, str_radix, sub_mod};
+use {Error, Fpe, check_numerals, min_len};
+
+type Block = GenericArray<u8, U16>;
+
+/// Number of Feistel rounds
+const ROUNDS: u8 = 10;
+
+/// FF1 instance over block cipher `C` with the given radix.
+///
+/// Tweak can have any length up to `2^32 - 1` bytes, length of numeral
+/// strings must be in the range from `min_len()` to `2^32 - 1`, where
+/// `min_len()` is the smallest length for which domain has at least one
+/// million elements.
+pub struct Ff1<C: BlockEncrypt<BlockSize = U16>> {
+    cipher: C,
+    radix: u32,
+    min_len: usize,
+}
+
+impl<C: BlockEncrypt<BlockSize = U16>> Ff1<C> {
+    /// Create new FF1 instance from initialized block cipher.
+    ///
+    /// Returns error if radix is not in the range from 2 to `2^16`.
+    pub fn from_cipher(cipher: C, radix: u32) -> Result<Self, Error> {
+        let min_len = min_len(radix)?;
+        Ok(Self { cipher, radix, min_len })
+    }
+
+    /// Create new FF1 instance from key with fixed size.
+    pub fn new(key: &GenericArray<u8, C::KeySize>, radix: u32)
+        -> Result<Self, Error>
+    {
+        Self::from_cipher(C::new(key), radix)
+    }
+
+    /// Create new FF1 instance from key with variable size.
+    pub fn new_varkey(key: &[u8], radix: u32) -> Result<Self, Error> {
+        let cipher = C::new_varkey(key).map_err(|_| Error::InvalidKeyLength)?;
+        Self::from_cipher(cipher, radix)
+    }
+
+    fn crypt(&self, tweak: &[u8], x: &mut [u16], decrypt: bool)
+        -> Result<(), Error>
+    {
+        check_numerals(self, x)?;
+        if tweak.len() as u64 > u32::MAX as u64 {
+            return Err(Error::InvalidTweak);
+        }
+        let n = x.len();
+        let u = n / 2;
+        let b = byte_len(self.radix, n - u);
+        let d = 4 * ((b + 3) / 4) + 4;
+
+        let mut p = Block::default();
+        p[..3].copy_from_slice(&[1, 2, 1]);
+        p[3..6].copy_from_slice(&self.radix.to_be_bytes()[1..]);
+        p[6] = 10;
+        p[7] = u as u8;
+        p[8..12].copy_from_slice(&(n as u32).to_be_bytes());
+        p[12..].copy_from_slice(&(tweak.len() as u32).to_be_bytes());
+        self.cipher.encrypt_block(&mut p);
+
+        // Q = T || [0]^((-t-b-1) mod 16) || [i]^1 || [NUM_radix(B)]^b
+        let mut q = vec![0u8; (tweak.len() + b + 16) / 16 * 16];
+        q[..tweak.len()].copy_from_slice(tweak);
+        let mut round = Round { p, q, b, d };
+
+        let mut a = x[..u].to_vec();
+        let mut b = x[u..].to_vec();
+        let mut y = vec![0u16; b.len()];
+        if decrypt {
+            for i in (0..ROUNDS).rev() {
+                let y = &mut y[..b.len()];
+                self.round_value(&mut round, i, &a, y);
+                sub_mod(&mut b, y, self.radix);
+                mem::swap(&mut a, &mut b);
+            }
+        } else {
+            for i in 0..ROUNDS {
+                let y = &mut y[..a.len()];
+                self.round_value(&mut round, i, &b, y);
+                add_mod(&mut a, y, self.radix);
+                mem::swap(&mut a, &mut b);
+            }
+        }
+        x[..u].copy_from_slice(&a);
+        x[u..].copy_from_slice(&b);
+        Ok(())
+    }
+
+    /// Compute value `y` of the round `i` for the round function input `x`
+    /// and write it into `y` as numeral string.
+    fn round_value(&self, round: &mut Round, i: u8, x: &[u16], y: &mut [u16]) {
+        let (b, q_len) = (round.b, round.q.len());
+        round.q[q_len - b - 1] = i;
+        round.q[q_len - b..].copy_from_slice(&num_radix(x, self.radix, b));
+
+        // R = PRF(P || Q), where PRF is CBC-MAC
+        let mut r = round.p;
+        for chunk in round.q.chunks(16) {
+            xor(&mut r, chunk);
+            self.cipher.encrypt_block(&mut r);
+        }
+
+        // S = R || CIPH(R xor [1]^16) || CIPH(R xor [2]^16) || ...
+        let mut s = vec![0u8; (round.d + 15) / 16 * 16];
+        s[..16].copy_from_slice(&r);
+        for (j, chunk) in s.chunks_mut(16).enumerate().skip(1) {
+            let mut block = r;
+            xor(&mut block[8..], &(j as u64).to_be_bytes());
+            self.cipher.encrypt_block(&mut block);
+            chunk.copy_from_slice(&block);
+        }
+        str_radix(&s[..round.d], self.radix, y);
+    }
+}
+
+/// Values which are fixed during processing of a numeral string.
+struct Round {
+    /// Encrypted block `P`
+    p: Block,
+    /// Buffer for `Q`, which already contains tweak and padding
+    q: Vec<u8>,
+    /// Length of `NUM_radix(B)` in bytes
+    b: usize,
+    /// Length of `S` in bytes
+    d: usize,
+}
+
+impl<C: BlockEncrypt<BlockSize = U16>> Fpe for Ff1<C> {
+    fn radix(&self) -> u32 {
+        self.radix
+    }
+
+    fn min_len(&self) -> usize {
+        self.min_len
+    }
+
+    fn max_len(&self) -> usize {
+        u32::MAX as usize
+    }
+
+    fn encrypt_in_place(&self, tweak: &[u8], x: &mut [u16])
+        -> Result<(), Error>
+    {
+        self.crypt(tweak, x, false)
+    }
+
+    fn decrypt_in_place(&self, tweak: &[u8], x: &mut [u16])
+        -> Result<(), Error>
+    {
+        self.crypt(tweak, x, true)
+    }
+}
+
+#[inline(always)]
+fn xor(buf: &mut [u8], key: &[u8]) {
+    debug_assert_eq!(buf.len(), key.len());
+    for (a, b) in buf.iter_mut().zip(key) {
+        *a ^= *b;
+    }
+}
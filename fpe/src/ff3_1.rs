@@ -0,0 +1,161 @@
+use alloc::vec::Vec;
+use block_cipher_trait::BlockEncrypt;
+use block_cipher_trait::generic_array::GenericArray;
+use block_cipher_trait::generic_array::typenum::U16;
+use core::mem;
+use {Error, Fpe, check_numerals, min_len};
+
+type Block = GenericArray<u8, U16>;
+
+/// Number of Feistel rounds
+const ROUNDS: u8 = 8;
+/// Size of the tweak in bytes
+const TWEAK_SIZE: usize = 7;
+
+/// FF3-1 instance over block cipher `C` with the given radix.
+///
+/// Tweak must be exactly 7 bytes (56 bits) long. Length of numeral strings
+/// must be in the range from `min_len()` to `max_len()`, where `min_len()`
+/// is the smallest length for which domain has at least one million
+/// elements and `max_len()` is `2 * floor(log_radix(2^96))`.
+///
+/// FF3-1 uses block cipher with byte-reversed key, which is handled by the
+/// `new` and `new_varkey` constructors.
+pub struct Ff3_1<C: BlockEncrypt<BlockSize = U16>> {
+    cipher: C,
+    radix: u32,
+    min_len: usize,
+    max_len: usize,
+}
+
+impl<C: BlockEncrypt<BlockSize = U16>> Ff3_1<C> {
+    /// Create new FF3-1 instance from block cipher initialized with
+    /// byte-reversed key.
+    ///
+    /// Returns error if radix is not in the range from 2 to `2^16`.
+    pub fn from_cipher(cipher: C, radix: u32) -> Result<Self, Error> {
+        let min_len = min_len(radix)?;
+        // maximal half length is the biggest `k` with `radix^k <= 2^96`
+        let (mut k, mut size) = (0, radix as u128);
+        while size <= 1 << 96 {
+            k += 1;
+            size *= radix as u128;
+        }
+        Ok(Self { cipher, radix, min_len, max_len: 2 * k })
+    }
+
+    /// Create new FF3-1 instance from key with fixed size.
+    pub fn new(key: &GenericArray<u8, C::KeySize>, radix: u32)
+        -> Result<Self, Error>
+    {
+        let mut key = key.clone();
+        key.reverse();
+        Self::from_cipher(C::new(&key), radix)
+    }
+
+    /// Create new FF3-1 instance from key with variable size.
+    pub fn new_varkey(key: &[u8], radix: u32) -> Result<Self, Error> {
+        let key: Vec<u8> = key.iter().rev().cloned().collect();
+        let cipher = C::new_varkey(&key).map_err(|_| Error::InvalidKeyLength)?;
+        Self::from_cipher(cipher, radix)
+    }
+
+    fn crypt(&self, tweak: &[u8], x: &mut [u16], decrypt: bool)
+        -> Result<(), Error>
+    {
+        check_numerals(self, x)?;
+        if tweak.len() != TWEAK_SIZE {
+            return Err(Error::InvalidTweak);
+        }
+        // T_L = T[0..27] || 0^4, T_R = T[32..55] || T[28..31] || 0^4
+        let t_l = [tweak[0], tweak[1], tweak[2], tweak[3] & 0xf0];
+        let t_r = [tweak[4], tweak[5], tweak[6], tweak[3] << 4];
+
+        let n = x.len();
+        let u = (n + 1) / 2;
+        let (a, b) = x.split_at_mut(u);
+        let (mut a, mut b) = (&mut a[..], &mut b[..]);
+        if decrypt {
+            for i in (0..ROUNDS).rev() {
+                let w = if i % 2 == 0 { &t_r } else { &t_l };
+                let y = self.round_value(w, i, a);
+                let m = self.radix_pow(b.len());
+                let c = (num_rev(b, self.radix) + m - y % m) % m;
+                str_rev(c, self.radix, b);
+                mem::swap(&mut a, &mut b);
+            }
+        } else {
+            for i in 0..ROUNDS {
+                let w = if i % 2 == 0 { &t_r } else { &t_l };
+                let y = self.round_value(w, i, b);
+                let m = self.radix_pow(a.len());
+                let c = (num_rev(a, self.radix) + y % m) % m;
+                str_rev(c, self.radix, a);
+                mem::swap(&mut a, &mut b);
+            }
+        }
+        Ok(())
+    }
+
+    /// Compute value `y` of the round `i` for the round function input `x`.
+    fn round_value(&self, w: &[u8; 4], i: u8, x: &[u16]) -> u128 {
+        // P = W xor [i]^4 || [NUM_radix(REV(X))]^12
+        let mut p = Block::default();
+        p[..4].copy_from_slice(w);
+        p[3] ^= i;
+        p[4..].copy_from_slice(&num_rev(x, self.radix).to_be_bytes()[4..]);
+        // S = REVB(CIPH(REVB(P)))
+        p.reverse();
+        self.cipher.encrypt_block(&mut p);
+        p.reverse();
+        let mut s = [0u8; 16];
+        s.copy_from_slice(&p);
+        u128::from_be_bytes(s)
+    }
+
+    #[inline(always)]
+    fn radix_pow(&self, m: usize) -> u128 {
+        (self.radix as u128).pow(m as u32)
+    }
+}
+
+impl<C: BlockEncrypt<BlockSize = U16>> Fpe for Ff3_1<C> {
+    fn radix(&self) -> u32 {
+        self.radix
+    }
+
+    fn min_len(&self) -> usize {
+        self.min_len
+    }
+
+    fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    fn encrypt_in_place(&self, tweak: &[u8], x: &mut [u16])
+        -> Result<(), Error>
+    {
+        self.crypt(tweak, x, false)
+    }
+
+    fn decrypt_in_place(&self, tweak: &[u8], x: &mut [u16])
+        -> Result<(), Error>
+    {
+        self.crypt(tweak, x, true)
+    }
+}
+
+/// Compute `NUM_radix(REV(x))`, result is smaller than `2^96`.
+#[inline(always)]
+fn num_rev(x: &[u16], radix: u32) -> u128 {
+    x.iter().rev().fold(0, |acc, &d| acc * radix as u128 + d as u128)
+}
+
+/// Write `REV(STR^m_radix(c))` into `out` of length `m`.
+#[inline(always)]
+fn str_rev(mut c: u128, radix: u32, out: &mut [u16]) {
+    for d in out.iter_mut() {
+        *d = (c % radix as u128) as u16;
+        c /= radix as u128;
+    }
+}
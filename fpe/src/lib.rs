@@ -0,0 +1,159 @@
+//! Generic implementation of the format-preserving encryption modes
+//! [FF1 and FF3-1][1] over block ciphers with 128-bit block size.
+//!
+//! Format-preserving encryption maps a string of numerals in the given radix
+//! to a string of numerals of the same length and radix, e.g. a 16 digit
+//! card number is encrypted into another 16 digit number. Numerals are
+//! represented as `u16` values, radix can be in the range from 2 to `2^16`.
+//! Strings of arbitrary characters can be processed using `Alphabet`, which
+//! maps characters to numerals.
+//!
+//! Both modes support tweaks, which are public values used to select one
+//! of the permutations of the domain. Length of numeral strings is limited
+//! by the mode and radix, strings which are too short or too long are
+//! rejected with `Error::InvalidLength`.
+//!
+//! # Usage example
+//! ```rust,ignore
+//! use fpe::{Alphabet, Ff1, Fpe};
+//!
+//! let ff1 = Ff1::<Aes128>::new(&key, 10).unwrap();
+//! let alphabet = Alphabet::new("0123456789").unwrap();
+//! let ct = ff1.encrypt_str(&alphabet, b"tweak", "4111111111111111").unwrap();
+//! let pt = ff1.decrypt_str(&alphabet, b"tweak", &ct).unwrap();
+//! ```
+//!
+//! [1]: https://csrc.nist.gov/publications/detail/sp/800-38g/rev-1/draft
+#![no_std]
+pub extern crate block_cipher_trait;
+#[macro_use]
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+mod alphabet;
+mod ff1;
+mod ff3_1;
+mod utils;
+
+#[cfg(feature = "dev")]
+pub mod dev;
+
+pub use alphabet::Alphabet;
+pub use ff1::Ff1;
+pub use ff3_1::Ff3_1;
+
+/// Minimal supported radix
+pub const MIN_RADIX: u32 = 2;
+/// Maximal supported radix
+pub const MAX_RADIX: u32 = 1 << 16;
+
+/// Error type for format-preserving encryption
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// Key length is not supported by the block cipher
+    InvalidKeyLength,
+    /// Radix is not supported or does not match radix of the alphabet
+    InvalidRadix,
+    /// Length of the numeral string is outside of the supported range
+    InvalidLength,
+    /// Numeral is not smaller than radix or character is not in the alphabet
+    InvalidNumeral,
+    /// Length of the tweak is not supported by the mode
+    InvalidTweak,
+    /// Alphabet contains duplicate characters
+    InvalidAlphabet,
+}
+
+/// Format-preserving encryption mode
+pub trait Fpe {
+    /// Radix of numeral strings processed by this instance.
+    fn radix(&self) -> u32;
+
+    /// Minimal supported length of numeral strings.
+    fn min_len(&self) -> usize;
+
+    /// Maximal supported length of numeral strings.
+    fn max_len(&self) -> usize;
+
+    /// Encrypt numeral string `x` in-place using `tweak`.
+    ///
+    /// On error `x` is left unmodified.
+    fn encrypt_in_place(&self, tweak: &[u8], x: &mut [u16])
+        -> Result<(), Error>;
+
+    /// Decrypt numeral string `x` in-place using `tweak`.
+    ///
+    /// On error `x` is left unmodified.
+    fn decrypt_in_place(&self, tweak: &[u8], x: &mut [u16])
+        -> Result<(), Error>;
+
+    /// Encrypt numeral string `x` using `tweak` and return result.
+    fn encrypt(&self, tweak: &[u8], x: &[u16]) -> Result<Vec<u16>, Error> {
+        let mut buf = x.to_vec();
+        self.encrypt_in_place(tweak, &mut buf)?;
+        Ok(buf)
+    }
+
+    /// Decrypt numeral string `x` using `tweak` and return result.
+    fn decrypt(&self, tweak: &[u8], x: &[u16]) -> Result<Vec<u16>, Error> {
+        let mut buf = x.to_vec();
+        self.decrypt_in_place(tweak, &mut buf)?;
+        Ok(buf)
+    }
+
+    /// Encrypt string `s` consisting of characters from `alphabet`.
+    ///
+    /// Size of the alphabet must be equal to radix of this instance.
+    fn encrypt_str(&self, alphabet: &Alphabet, tweak: &[u8], s: &str)
+        -> Result<String, Error>
+    {
+        if alphabet.radix() != self.radix() {
+            return Err(Error::InvalidRadix);
+        }
+        let mut buf = alphabet.to_numerals(s)?;
+        self.encrypt_in_place(tweak, &mut buf)?;
+        alphabet.to_string(&buf)
+    }
+
+    /// Decrypt string `s` consisting of characters from `alphabet`.
+    ///
+    /// Size of the alphabet must be equal to radix of this instance.
+    fn decrypt_str(&self, alphabet: &Alphabet, tweak: &[u8], s: &str)
+        -> Result<String, Error>
+    {
+        if alphabet.radix() != self.radix() {
+            return Err(Error::InvalidRadix);
+        }
+        let mut buf = alphabet.to_numerals(s)?;
+        self.decrypt_in_place(tweak, &mut buf)?;
+        alphabet.to_string(&buf)
+    }
+}
+
+/// Check that radix is supported and compute minimal length of numeral
+/// strings, which is the smallest `minlen >= 2` with `radix^minlen >= 10^6`.
+fn min_len(radix: u32) -> Result<usize, Error> {
+    if !(MIN_RADIX..=MAX_RADIX).contains(&radix) {
+        return Err(Error::InvalidRadix);
+    }
+    let (mut len, mut size) = (2, (radix as u64) * (radix as u64));
+    while size < 1_000_000 {
+        len += 1;
+        size *= radix as u64;
+    }
+    Ok(len)
+}
+
+/// Check length of the numeral string and its numerals.
+fn check_numerals<F: Fpe + ?Sized>(f: &F, x: &[u16]) -> Result<(), Error> {
+    if x.len() < f.min_len() || x.len() > f.max_len() {
+        return Err(Error::InvalidLength);
+    }
+    let radix = f.radix();
+    if x.iter().any(|&d| d as u32 >= radix) {
+        return Err(Error::InvalidNumeral);
+    }
+    Ok(())
+}
@@ -0,0 +1,86 @@
+//! Arithmetic on numeral strings. Big integers are represented as vectors of
+//! little endian 32-bit limbs without leading zero limbs.
+use alloc::vec::Vec;
+
+/// Compute `NUM_radix(x)` as big integer.
+fn to_limbs(x: &[u16], radix: u32) -> Vec<u32> {
+    let mut limbs = Vec::with_capacity(x.len() / 2 + 1);
+    for &d in x {
+        let mut carry = d as u64;
+        for limb in limbs.iter_mut() {
+            let t = (*limb as u64) * (radix as u64) + carry;
+            *limb = t as u32;
+            carry = t >> 32;
+        }
+        if carry != 0 {
+            limbs.push(carry as u32);
+        }
+    }
+    limbs
+}
+
+/// Compute `NUM_radix(x)` as big endian byte string of length `len`.
+///
+/// Length must be big enough to hold the result.
+pub fn num_radix(x: &[u16], radix: u32, len: usize) -> Vec<u8> {
+    let limbs = to_limbs(x, radix);
+    let mut buf = vec![0; len];
+    let bytes = limbs.iter().flat_map(|limb| limb.to_le_bytes());
+    for (b, v) in buf.iter_mut().rev().zip(bytes) {
+        *b = v;
+    }
+    buf
+}
+
+/// Length in bytes of the biggest number represented by numeral string of
+/// length `n`, i.e. `ceil(bitlen(radix^n - 1) / 8)`.
+pub fn byte_len(radix: u32, n: usize) -> usize {
+    let max = vec![(radix - 1) as u16; n];
+    let limbs = to_limbs(&max, radix);
+    match limbs.last() {
+        Some(top) => 4 * limbs.len() - (top.leading_zeros() / 8) as usize,
+        None => 0,
+    }
+}
+
+/// Compute `STR^m_radix(NUM(s) mod radix^m)` and write result into `out`
+/// of length `m`, `s` is a big endian byte string.
+pub fn str_radix(s: &[u8], radix: u32, out: &mut [u16]) {
+    let mut limbs: Vec<u32> = s.rchunks(4)
+        .map(|c| c.iter().fold(0, |acc, &b| (acc << 8) | b as u32))
+        .collect();
+    for d in out.iter_mut().rev() {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        let mut rem = 0u64;
+        for limb in limbs.iter_mut().rev() {
+            let t = (rem << 32) | *limb as u64;
+            *limb = (t / radix as u64) as u32;
+            rem = t % radix as u64;
+        }
+        *d = rem as u16;
+    }
+}
+
+/// Compute `x = (x + y) mod radix^m`, where `m` is length of `x` and `y`.
+pub fn add_mod(x: &mut [u16], y: &[u16], radix: u32) {
+    debug_assert_eq!(x.len(), y.len());
+    let mut carry = 0;
+    for (a, &b) in x.iter_mut().zip(y).rev() {
+        let t = *a as u32 + b as u32 + carry;
+        carry = (t >= radix) as u32;
+        *a = (t - carry * radix) as u16;
+    }
+}
+
+/// Compute `x = (x - y) mod radix^m`, where `m` is length of `x` and `y`.
+pub fn sub_mod(x: &mut [u16], y: &[u16], radix: u32) {
+    debug_assert_eq!(x.len(), y.len());
+    let mut borrow = 0;
+    for (a, &b) in x.iter_mut().zip(y).rev() {
+        let t = radix + *a as u32 - b as u32 - borrow;
+        borrow = (t < radix) as u32;
+        *a = (t - (1 - borrow) * radix) as u16;
+    }
+}
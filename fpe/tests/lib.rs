@@ -0,0 +1,18 @@
+extern crate aes;
+#[macro_use(impl_cipher)]
+extern crate block_cipher_trait;
+#[macro_use]
+extern crate fpe;
+
+use block_cipher_trait::generic_array::typenum::{U16, U24, U32};
+
+impl_cipher!(Aes128, aes::Aes128, U16, U16);
+impl_cipher!(Aes192, aes::Aes192, U24, U16);
+impl_cipher!(Aes256, aes::Aes256, U32, U16);
+
+new_ff1_test!(ff1_aes128, fpe::dev::FF1_AES128, Aes128);
+new_ff1_test!(ff1_aes192, fpe::dev::FF1_AES192, Aes192);
+new_ff1_test!(ff1_aes256, fpe::dev::FF1_AES256, Aes256);
+new_ff3_1_test!(ff3_1_aes128, fpe::dev::FF3_1_AES128, Aes128);
+new_ff3_1_test!(ff3_1_aes192, fpe::dev::FF3_1_AES192, Aes192);
+new_ff3_1_test!(ff3_1_aes256, fpe::dev::FF3_1_AES256, Aes256);
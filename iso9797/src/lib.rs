@@ -0,0 +1,451 @@
+//! MAC algorithms and padding methods defined by [ISO/IEC 9797-1][1].
+//!
+//! The following MAC algorithms are implemented generically over block
+//! ciphers:
+//!
+//! - `CbcMac`: MAC Algorithm 1, i.e. CBC-MAC with zero IV. With DES and
+//!   padding method 1 it is also known as ANSI X9.9 MAC.
+//! - `RetailMac`: MAC Algorithm 3, CBC-MAC with an additional decryption
+//!   and encryption of the last block using the second and the first key
+//!   respectively. With DES it is also known as ANSI X9.19 "Retail MAC".
+//! - `TruncatedCmac`: MAC Algorithm 5, i.e. CMAC (see the `cmac` crate).
+//!
+//! Algorithms 1 and 3 are parametrized by one of the padding methods from
+//! the `padding` module. All algorithms are parametrized by the output size
+//! `T`, which defaults to the block size and must not exceed it. Truncated
+//! tag is the leftmost `T` bytes of the full tag.
+//!
+//! Padding method 3 prepends the message with its length, so MACs which use
+//! it do not implement the `Mac` trait. Instead they are created for a
+//! message with the given length and provide fallible `input` and `result`
+//! methods.
+//!
+//! # Usage example
+//! ```rust,ignore
+//! use iso9797::{CbcMac, RetailMac, Mac};
+//! use iso9797::padding::{Padding1, Padding3};
+//!
+//! // 16 byte key: K followed by K'
+//! let mut mac = RetailMac::<Des, Padding1, U4>::new_varkey(&key).unwrap();
+//! mac.input(b"input message");
+//! let code = mac.result().code();
+//!
+//! // padding method 3 requires message length in advance
+//! let msg = b"input message";
+//! let mut mac = CbcMac::<Des, Padding3>::new_with_len(&key, msg.len() as u64)
+//!     .unwrap();
+//! mac.input(msg).unwrap();
+//! let code = mac.result().unwrap().code();
+//! ```
+//!
+//! [1]: https://www.iso.org/standard/50375.html
+#![no_std]
+pub extern crate block_cipher_trait;
+extern crate cmac;
+pub extern crate crypto_mac;
+extern crate dbl;
+
+use block_cipher_trait::{
+    BlockCipher, BlockCipherMut, BlockEncrypt, BlockEncryptMut,
+};
+use block_cipher_trait::generic_array::{GenericArray, ArrayLength};
+use block_cipher_trait::generic_array::typenum::{
+    IsLessOrEqual, Sum, True, Unsigned,
+};
+use cmac::Cmac;
+use dbl::Dbl;
+use core::cmp;
+use core::marker::PhantomData;
+use core::ops::Add;
+
+pub use crypto_mac::{Mac, MacResult, InvalidKeyLength, KeySizes};
+
+pub mod padding;
+
+use padding::{OnlinePadding, Padding, Padding3};
+
+type Block<C> = GenericArray<u8, <C as BlockEncryptMut>::BlockSize>;
+type RetailKeySize<C> =
+    Sum<<C as BlockEncrypt>::KeySize, <C as BlockEncrypt>::KeySize>;
+
+/// Error type for signaling that message length is too big for padding
+/// method 3 or differs from the length declared on MAC creation
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct InvalidMessageLength;
+
+/// CBC-MAC computation shared by algorithms 1 and 3.
+#[derive(Clone)]
+struct Core<C: BlockEncryptMut, P: Padding> {
+    cipher: C,
+    state: Block<C>,
+    buffer: Block<C>,
+    /// Number of bytes in `buffer`, always smaller than the block size
+    pos: usize,
+    /// Number of processed message bytes
+    len: u64,
+    /// Message length declared for padding method 3
+    declared: u64,
+    _padding: PhantomData<P>,
+}
+
+impl<C: BlockEncryptMut, P: Padding> Core<C, P> {
+    fn new(cipher: C) -> Self {
+        Self {
+            cipher,
+            state: Default::default(),
+            buffer: Default::default(),
+            pos: 0,
+            len: 0,
+            declared: 0,
+            _padding: PhantomData,
+        }
+    }
+
+    fn input(&mut self, mut data: &[u8]) {
+        self.len += data.len() as u64;
+        let bs = C::BlockSize::to_usize();
+
+        if self.pos != 0 {
+            let n = cmp::min(bs - self.pos, data.len());
+            self.buffer[self.pos..self.pos + n].copy_from_slice(&data[..n]);
+            self.pos += n;
+            data = &data[n..];
+            if self.pos < bs {
+                return;
+            }
+            xor(&mut self.state, &self.buffer);
+            self.cipher.encrypt_block(&mut self.state);
+            self.pos = 0;
+        }
+
+        let mut chunks = data.chunks_exact(bs);
+        for block in &mut chunks {
+            xor(&mut self.state, block);
+            self.cipher.encrypt_block(&mut self.state);
+        }
+        let rem = chunks.remainder();
+        self.buffer[..rem.len()].copy_from_slice(rem);
+        self.pos = rem.len();
+    }
+
+    /// Process padding, return the full CBC-MAC output and reset state.
+    fn finalize(&mut self) -> Block<C> {
+        if P::pad_block(&mut self.buffer, self.len) {
+            xor(&mut self.state, &self.buffer);
+            self.cipher.encrypt_block(&mut self.state);
+        }
+
+        let tag = self.state.clone();
+        self.state = Default::default();
+        self.pos = 0;
+        self.len = 0;
+        tag
+    }
+}
+
+impl<C: BlockEncryptMut> Core<C, Padding3> {
+    /// Create new instance for a message with length `len` bytes and process
+    /// the length block.
+    fn with_len(mut cipher: C, len: u64) -> Result<Self, InvalidMessageLength> {
+        let mut state = Block::<C>::default();
+        padding::length_block(&mut state, len)?;
+        cipher.encrypt_block(&mut state);
+        Ok(Self { state, declared: len, ..Self::new(cipher) })
+    }
+
+    fn checked_input(&mut self, data: &[u8])
+        -> Result<(), InvalidMessageLength>
+    {
+        match self.len.checked_add(data.len() as u64) {
+            Some(len) if len <= self.declared => {
+                self.input(data);
+                Ok(())
+            },
+            _ => Err(InvalidMessageLength),
+        }
+    }
+
+    fn checked_finalize(&mut self) -> Result<Block<C>, InvalidMessageLength> {
+        if self.len != self.declared {
+            return Err(InvalidMessageLength);
+        }
+        Ok(self.finalize())
+    }
+}
+
+/// MAC Algorithm 1 (CBC-MAC) over block cipher `C` with padding method `P`
+/// and output size `T`.
+#[derive(Clone)]
+pub struct CbcMac<C, P, T = <C as BlockEncryptMut>::BlockSize>
+    where C: BlockEncryptMut, P: Padding,
+        T: ArrayLength<u8> + IsLessOrEqual<C::BlockSize, Output = True>
+{
+    core: Core<C, P>,
+    _size: PhantomData<T>,
+}
+
+impl<C, P, T> CbcMac<C, P, T>
+    where C: BlockEncryptMut, P: OnlinePadding,
+        T: ArrayLength<u8> + IsLessOrEqual<C::BlockSize, Output = True>
+{
+    /// Create new CBC-MAC instance from initialized block cipher.
+    pub fn from_cipher(cipher: C) -> Self {
+        Self { core: Core::new(cipher), _size: PhantomData }
+    }
+}
+
+impl<C, T> CbcMac<C, Padding3, T>
+    where C: BlockEncryptMut,
+        T: ArrayLength<u8> + IsLessOrEqual<C::BlockSize, Output = True>
+{
+    /// Create new CBC-MAC instance for a message with length `len` bytes from
+    /// initialized block cipher.
+    ///
+    /// Returns error if the length in bits does not fit into one block.
+    pub fn from_cipher_with_len(cipher: C, len: u64)
+        -> Result<Self, InvalidMessageLength>
+    {
+        Ok(Self { core: Core::with_len(cipher, len)?, _size: PhantomData })
+    }
+
+    /// Process input data.
+    ///
+    /// Returns error without processing data if the total length of input
+    /// exceeds the declared message length.
+    pub fn input(&mut self, data: &[u8]) -> Result<(), InvalidMessageLength> {
+        self.core.checked_input(data)
+    }
+
+    /// Obtain the result of MAC computation.
+    ///
+    /// Returns error if the length of input differs from the declared
+    /// message length.
+    pub fn result(mut self) -> Result<MacResult<T>, InvalidMessageLength> {
+        let tag = self.core.checked_finalize()?;
+        Ok(MacResult::new(truncate(&tag)))
+    }
+}
+
+impl<C, T> CbcMac<C, Padding3, T>
+    where C: BlockEncrypt,
+        T: ArrayLength<u8> + IsLessOrEqual<C::BlockSize, Output = True>
+{
+    /// Create new CBC-MAC instance for a message with length `len` bytes.
+    ///
+    /// Returns error if the length in bits does not fit into one block.
+    pub fn new_with_len(key: &GenericArray<u8, C::KeySize>, len: u64)
+        -> Result<Self, InvalidMessageLength>
+    {
+        Self::from_cipher_with_len(C::new(key), len)
+    }
+}
+
+impl<C, P, T> Mac for CbcMac<C, P, T>
+    where C: BlockEncrypt, P: OnlinePadding,
+        T: ArrayLength<u8> + IsLessOrEqual<C::BlockSize, Output = True>
+{
+    type OutputSize = T;
+    type KeySize = C::KeySize;
+
+    fn new(key: &GenericArray<u8, C::KeySize>) -> Self {
+        Self::from_cipher(C::new(key))
+    }
+
+    fn new_varkey(key: &[u8]) -> Result<Self, InvalidKeyLength> {
+        let cipher = C::new_varkey(key).map_err(|_| InvalidKeyLength)?;
+        Ok(Self::from_cipher(cipher))
+    }
+
+    fn key_sizes() -> KeySizes {
+        C::key_sizes()
+    }
+
+    #[inline]
+    fn input(&mut self, data: &[u8]) {
+        self.core.input(data);
+    }
+
+    #[inline]
+    fn result(&mut self) -> MacResult<T> {
+        MacResult::new(truncate(&self.core.finalize()))
+    }
+}
+
+/// MAC Algorithm 3 ("Retail MAC") over block cipher `C` with padding method
+/// `P` and output size `T`.
+///
+/// Output of CBC-MAC with the first key `K` is decrypted using the second
+/// key `K'` and encrypted again using `K`. Key is the concatenation of `K`
+/// and `K'`.
+#[derive(Clone)]
+pub struct RetailMac<C, P, T = <C as BlockEncryptMut>::BlockSize>
+    where C: BlockCipherMut, P: Padding,
+        T: ArrayLength<u8> + IsLessOrEqual<C::BlockSize, Output = True>
+{
+    core: Core<C, P>,
+    cipher2: C,
+    _size: PhantomData<T>,
+}
+
+impl<C, P, T> RetailMac<C, P, T>
+    where C: BlockCipherMut, P: Padding,
+        T: ArrayLength<u8> + IsLessOrEqual<C::BlockSize, Output = True>
+{
+    /// Apply the final decryption and encryption to CBC-MAC output.
+    fn output_transform(&mut self, mut tag: Block<C>) -> Block<C> {
+        self.cipher2.decrypt_block(&mut tag);
+        self.core.cipher.encrypt_block(&mut tag);
+        tag
+    }
+}
+
+impl<C, P, T> RetailMac<C, P, T>
+    where C: BlockCipherMut, P: OnlinePadding,
+        T: ArrayLength<u8> + IsLessOrEqual<C::BlockSize, Output = True>
+{
+    /// Create new Retail MAC instance from block ciphers initialized with
+    /// keys `K` and `K'`.
+    pub fn from_ciphers(cipher: C, cipher2: C) -> Self {
+        Self { core: Core::new(cipher), cipher2, _size: PhantomData }
+    }
+}
+
+impl<C, T> RetailMac<C, Padding3, T>
+    where C: BlockCipherMut,
+        T: ArrayLength<u8> + IsLessOrEqual<C::BlockSize, Output = True>
+{
+    /// Create new Retail MAC instance for a message with length `len` bytes
+    /// from block ciphers initialized with keys `K` and `K'`.
+    ///
+    /// Returns error if the length in bits does not fit into one block.
+    pub fn from_ciphers_with_len(cipher: C, cipher2: C, len: u64)
+        -> Result<Self, InvalidMessageLength>
+    {
+        let core = Core::with_len(cipher, len)?;
+        Ok(Self { core, cipher2, _size: PhantomData })
+    }
+
+    /// Process input data.
+    ///
+    /// Returns error without processing data if the total length of input
+    /// exceeds the declared message length.
+    pub fn input(&mut self, data: &[u8]) -> Result<(), InvalidMessageLength> {
+        self.core.checked_input(data)
+    }
+
+    /// Obtain the result of MAC computation.
+    ///
+    /// Returns error if the length of input differs from the declared
+    /// message length.
+    pub fn result(mut self) -> Result<MacResult<T>, InvalidMessageLength> {
+        let tag = self.core.checked_finalize()?;
+        Ok(MacResult::new(truncate(&self.output_transform(tag))))
+    }
+}
+
+impl<C, T> RetailMac<C, Padding3, T>
+    where C: BlockCipher,
+        C::KeySize: Add, RetailKeySize<C>: ArrayLength<u8>,
+        T: ArrayLength<u8> + IsLessOrEqual<C::BlockSize, Output = True>
+{
+    /// Create new Retail MAC instance for a message with length `len` bytes.
+    /// Key is the concatenation of `K` and `K'`.
+    ///
+    /// Returns error if the length in bits does not fit into one block.
+    pub fn new_with_len(key: &GenericArray<u8, RetailKeySize<C>>, len: u64)
+        -> Result<Self, InvalidMessageLength>
+    {
+        let (k1, k2) = key.split_at(C::KeySize::to_usize());
+        Self::from_ciphers_with_len(C::new(GenericArray::from_slice(k1)),
+            C::new(GenericArray::from_slice(k2)), len)
+    }
+}
+
+impl<C, P, T> Mac for RetailMac<C, P, T>
+    where C: BlockCipher, P: OnlinePadding,
+        C::KeySize: Add, RetailKeySize<C>: ArrayLength<u8>,
+        T: ArrayLength<u8> + IsLessOrEqual<C::BlockSize, Output = True>
+{
+    type OutputSize = T;
+    type KeySize = RetailKeySize<C>;
+
+    fn new(key: &GenericArray<u8, RetailKeySize<C>>) -> Self {
+        let (k1, k2) = key.split_at(C::KeySize::to_usize());
+        Self::from_ciphers(C::new(GenericArray::from_slice(k1)),
+            C::new(GenericArray::from_slice(k2)))
+    }
+
+    #[inline]
+    fn input(&mut self, data: &[u8]) {
+        self.core.input(data);
+    }
+
+    #[inline]
+    fn result(&mut self) -> MacResult<T> {
+        let tag = self.core.finalize();
+        MacResult::new(truncate(&self.output_transform(tag)))
+    }
+}
+
+/// MAC Algorithm 5 (CMAC) over block cipher `C` with output size `T`.
+#[derive(Clone)]
+pub struct TruncatedCmac<C, T = <C as BlockEncryptMut>::BlockSize>
+    where C: BlockEncryptMut, Block<C>: Dbl,
+        T: ArrayLength<u8> + IsLessOrEqual<C::BlockSize, Output = True>
+{
+    mac: Cmac<C>,
+    _size: PhantomData<T>,
+}
+
+impl<C, T> TruncatedCmac<C, T>
+    where C: BlockEncryptMut, Block<C>: Dbl,
+        T: ArrayLength<u8> + IsLessOrEqual<C::BlockSize, Output = True>
+{
+    /// Create new CMAC instance from initialized block cipher.
+    pub fn from_cipher(cipher: C) -> Self {
+        Self { mac: Cmac::from_cipher(cipher), _size: PhantomData }
+    }
+}
+
+impl<C, T> Mac for TruncatedCmac<C, T>
+    where C: BlockEncrypt, Block<C>: Dbl,
+        T: ArrayLength<u8> + IsLessOrEqual<C::BlockSize, Output = True>
+{
+    type OutputSize = T;
+    type KeySize = C::KeySize;
+
+    fn new(key: &GenericArray<u8, C::KeySize>) -> Self {
+        Self { mac: Cmac::new(key), _size: PhantomData }
+    }
+
+    fn new_varkey(key: &[u8]) -> Result<Self, InvalidKeyLength> {
+        let mac = Cmac::new_varkey(key)?;
+        Ok(Self { mac, _size: PhantomData })
+    }
+
+    fn key_sizes() -> KeySizes {
+        Cmac::<C>::key_sizes()
+    }
+
+    #[inline]
+    fn input(&mut self, data: &[u8]) {
+        self.mac.input(data);
+    }
+
+    #[inline]
+    fn result(&mut self) -> MacResult<T> {
+        MacResult::new(truncate(&self.mac.result().code()))
+    }
+}
+
+#[inline(always)]
+fn truncate<N: ArrayLength<u8>>(tag: &[u8]) -> GenericArray<u8, N> {
+    GenericArray::clone_from_slice(&tag[..N::to_usize()])
+}
+
+#[inline(always)]
+fn xor(buf: &mut [u8], data: &[u8]) {
+    for (a, b) in buf.iter_mut().zip(data) {
+        *a ^= *b;
+    }
+}
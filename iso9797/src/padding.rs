@@ -0,0 +1,112 @@
+use block_cipher_trait::generic_array::{GenericArray, ArrayLength};
+use core::cmp;
+use InvalidMessageLength;
+
+/// Padding method defined by ISO/IEC 9797-1.
+pub trait Padding {
+    /// Pad the last block of the message with length `msg_len` bytes.
+    ///
+    /// The block contains `msg_len % block_size` bytes of the message and
+    /// garbage after them. Returns `false` if the block must not be
+    /// processed, i.e. if the message was fully processed without it.
+    fn pad_block<N>(block: &mut GenericArray<u8, N>, msg_len: u64) -> bool
+        where N: ArrayLength<u8>;
+}
+
+/// Padding method which does not require message length to be known before
+/// its input. MACs with such padding methods implement the `Mac` trait.
+pub trait OnlinePadding: Padding {}
+
+/// Padding method 1: the message is padded with zeros up to the next block
+/// boundary, empty message is padded to one block.
+///
+/// Trailing zeros of the message can not be distinguished from padding, so
+/// it should be used only for messages with a known length.
+#[derive(Clone, Copy, Debug)]
+pub enum Padding1 {}
+
+impl Padding for Padding1 {
+    fn pad_block<N>(block: &mut GenericArray<u8, N>, msg_len: u64) -> bool
+        where N: ArrayLength<u8>
+    {
+        let pos = (msg_len % N::to_u64()) as usize;
+        if pos == 0 && msg_len != 0 {
+            return false;
+        }
+        zeroize(&mut block[pos..]);
+        true
+    }
+}
+
+impl OnlinePadding for Padding1 {}
+
+/// Padding method 2: the message is padded with a single `1` bit followed by
+/// zeros, a full block of padding is added if message length is a multiple
+/// of the block size.
+///
+/// It's the same scheme as ISO/IEC 7816-4 padding.
+#[derive(Clone, Copy, Debug)]
+pub enum Padding2 {}
+
+impl Padding for Padding2 {
+    fn pad_block<N>(block: &mut GenericArray<u8, N>, msg_len: u64) -> bool
+        where N: ArrayLength<u8>
+    {
+        let pos = (msg_len % N::to_u64()) as usize;
+        block[pos] = 0x80;
+        zeroize(&mut block[pos + 1..]);
+        true
+    }
+}
+
+impl OnlinePadding for Padding2 {}
+
+/// Padding method 3: the message is padded with zeros up to the next block
+/// boundary (empty message is not padded) and prepended with a block which
+/// contains message length in bits encoded as a big endian number.
+///
+/// Since the length block is processed first, MACs with this padding method
+/// do not implement the `Mac` trait and must be created with the message
+/// length using `new_with_len` or `from_cipher_with_len` methods.
+#[derive(Clone, Copy, Debug)]
+pub enum Padding3 {}
+
+impl Padding for Padding3 {
+    fn pad_block<N>(block: &mut GenericArray<u8, N>, msg_len: u64) -> bool
+        where N: ArrayLength<u8>
+    {
+        let pos = (msg_len % N::to_u64()) as usize;
+        if pos == 0 {
+            return false;
+        }
+        zeroize(&mut block[pos..]);
+        true
+    }
+}
+
+/// Encode message length in bits into the block used by padding method 3.
+///
+/// Returns error if the length does not fit into the block.
+pub(crate) fn length_block<N>(block: &mut GenericArray<u8, N>, msg_len: u64)
+    -> Result<(), InvalidMessageLength>
+    where N: ArrayLength<u8>
+{
+    let bits = (msg_len as u128) << 3;
+    let bits = bits.to_be_bytes();
+    let bs = N::to_usize();
+    let n = cmp::min(bs, bits.len());
+    let (head, tail) = bits.split_at(bits.len() - n);
+    if head.iter().any(|&b| b != 0) {
+        return Err(InvalidMessageLength);
+    }
+    zeroize(block);
+    block[bs - n..].copy_from_slice(tail);
+    Ok(())
+}
+
+#[inline(always)]
+fn zeroize(buf: &mut [u8]) {
+    for b in buf.iter_mut() {
+        *b = 0;
+    }
+}
@@ -0,0 +1,107 @@
+#[macro_use(impl_cipher)]
+extern crate block_cipher_trait;
+#[macro_use]
+extern crate crypto_mac;
+extern crate des;
+extern crate iso9797;
+
+use block_cipher_trait::Ede3;
+use block_cipher_trait::generic_array::GenericArray;
+use block_cipher_trait::generic_array::typenum::{U4, U8};
+use iso9797::{CbcMac, RetailMac, TruncatedCmac};
+use iso9797::padding::{Padding1, Padding2, Padding3};
+
+impl_cipher!(Des, des::Des, U8, U8);
+
+/// Same as `new_test!`, but for MACs with padding method 3 which require
+/// message length on creation
+macro_rules! padding3_test {
+    ($name:ident, $test_name:expr, $mac:ty) => {
+        #[test]
+        fn $name() {
+            fn run_test(key: &[u8], input: &[u8], tag: &[u8]) -> bool {
+                let key = GenericArray::from_slice(key);
+                let len = input.len() as u64;
+                let mut mac = <$mac>::new_with_len(key, len).unwrap();
+                mac.input(input).unwrap();
+                if mac.result().unwrap().code().as_slice() != tag {
+                    return false;
+                }
+
+                // test reading byte by byte
+                let mut mac = <$mac>::new_with_len(key, len).unwrap();
+                for i in 0..input.len() {
+                    mac.input(&input[i..i + 1]).unwrap();
+                }
+                if mac.result().unwrap().code().as_slice() != tag {
+                    return false;
+                }
+
+                // input must have exactly the declared length
+                let mut mac = <$mac>::new_with_len(key, len).unwrap();
+                mac.input(input).unwrap();
+                if mac.input(&[0]).is_ok() {
+                    return false;
+                }
+                let mut mac = <$mac>::new_with_len(key, len + 1).unwrap();
+                mac.input(input).unwrap();
+                mac.result().is_err()
+            }
+
+            let keys = include_bytes!(
+                concat!("data/", $test_name, ".keys.bin"));
+            let inputs = include_bytes!(
+                concat!("data/", $test_name, ".inputs.bin"));
+            let tags = include_bytes!(
+                concat!("data/", $test_name, ".tags.bin"));
+            let index = include_bytes!(
+                concat!("data/", $test_name, ".index.bin"));
+
+            for (i, chunk) in index.chunks(2*3*2).enumerate() {
+                let mut idx = [0usize; 6];
+                for (j, val) in idx.iter_mut().enumerate() {
+                    let b = [chunk[2*j], chunk[2*j + 1]];
+                    *val = u16::from_le_bytes(b) as usize;
+                }
+                let key = &keys[idx[0]..idx[1]];
+                let input = &inputs[idx[2]..idx[3]];
+                let tag = &tags[idx[4]..idx[5]];
+                if !run_test(key, input, tag) {
+                    panic!("\nFailed test №{}\n", i);
+                }
+            }
+        }
+    }
+}
+
+// Vectors include examples from ISO/IEC 9797-1 Annex B. The first vector of
+// `alg3_des_p1_t8` is the ANSI X9.19 reference: K = 0123456789ABCDEF,
+// K' = FEDCBA9876543210, "Now is the time for all ".
+new_test!(alg1_des_p1, "alg1_des_p1_t8", CbcMac<Des, Padding1>);
+new_test!(alg1_des_p1_t4, "alg1_des_p1_t4", CbcMac<Des, Padding1, U4>);
+new_test!(alg1_des_p2, "alg1_des_p2_t8", CbcMac<Des, Padding2>);
+new_test!(alg1_des_p2_t4, "alg1_des_p2_t4", CbcMac<Des, Padding2, U4>);
+padding3_test!(alg1_des_p3, "alg1_des_p3_t8", CbcMac<Des, Padding3>);
+padding3_test!(alg1_des_p3_t4, "alg1_des_p3_t4", CbcMac<Des, Padding3, U4>);
+
+new_test!(alg3_des_p1, "alg3_des_p1_t8", RetailMac<Des, Padding1>);
+new_test!(alg3_des_p1_t4, "alg3_des_p1_t4", RetailMac<Des, Padding1, U4>);
+new_test!(alg3_des_p2, "alg3_des_p2_t8", RetailMac<Des, Padding2>);
+new_test!(alg3_des_p2_t4, "alg3_des_p2_t4", RetailMac<Des, Padding2, U4>);
+padding3_test!(alg3_des_p3, "alg3_des_p3_t8", RetailMac<Des, Padding3>);
+padding3_test!(alg3_des_p3_t4, "alg3_des_p3_t4",
+    RetailMac<Des, Padding3, U4>);
+
+new_test!(alg5_tdes, "alg5_tdes_t8", TruncatedCmac<Ede3<Des, Des, Des>>);
+new_test!(alg5_tdes_t4, "alg5_tdes_t4",
+    TruncatedCmac<Ede3<Des, Des, Des>, U4>);
+
+key_sizes_test!(alg1_des_key_sizes, CbcMac<Des, Padding1>);
+key_sizes_test!(alg3_des_key_sizes, RetailMac<Des, Padding1>);
+
+#[test]
+fn padding3_too_long() {
+    let key = GenericArray::default();
+    assert!(CbcMac::<Des, Padding3>::new_with_len(&key, 1 << 61).is_err());
+    assert!(CbcMac::<Des, Padding3>::new_with_len(&key, !0 >> 3).is_ok());
+}
@@ -0,0 +1,48 @@
+//! This crate defines the set of key lengths accepted by an algorithm. It is
+//! shared by the `block-cipher-trait` and `crypto-mac` crates, which
+//! re-export it, so algorithms built on top of block ciphers can forward key
+//! sizes of the underlying cipher.
+#![no_std]
+
+/// Set of key lengths in bytes which are accepted by `new_varkey`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum KeySizes {
+    /// Lengths from `min` to `max` inclusive which differ from `min` by a
+    /// multiple of `step`
+    Range { min: usize, max: usize, step: usize },
+    /// Explicit list of lengths
+    List(&'static [usize]),
+}
+
+impl KeySizes {
+    /// Set which contains only one key length.
+    pub fn fixed(len: usize) -> Self {
+        KeySizes::Range { min: len, max: len, step: 1 }
+    }
+
+    /// Check if key length is in the set.
+    pub fn contains(&self, len: usize) -> bool {
+        match *self {
+            KeySizes::Range { min, max, step } => {
+                len >= min && len <= max && (len - min) % step == 0
+            },
+            KeySizes::List(list) => list.contains(&len),
+        }
+    }
+
+    /// Minimal key length, zero for an empty list.
+    pub fn min(&self) -> usize {
+        match *self {
+            KeySizes::Range { min, .. } => min,
+            KeySizes::List(list) => list.iter().cloned().min().unwrap_or(0),
+        }
+    }
+
+    /// Maximal key length, zero for an empty list.
+    pub fn max(&self) -> usize {
+        match *self {
+            KeySizes::Range { max, .. } => max,
+            KeySizes::List(list) => list.iter().cloned().max().unwrap_or(0),
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Test vectors and helpers for testing key wrap implementations.
+//!
+//! Usage example:
+//!
+//! ```rust,ignore
+//! #[macro_use]
+//! extern crate key_wrap;
+//!
+//! new_kw_test!(kw_aes128, key_wrap::dev::KW_AES128, Aes128);
+//! new_kwp_test!(kwp_aes128, key_wrap::dev::KWP_AES128, Aes128);
+//! ```
+use super::{Kek, Error};
+use block_cipher_trait::BlockCipher;
+use block_cipher_trait::dev::decode_hex as decode;
+use block_cipher_trait::generic_array::typenum::U16;
+
+/// Key wrap test vector. All fields are hex encoded.
+pub struct Test {
+    pub kek: &'static str,
+    pub input: &'static str,
+    pub output: &'static str,
+}
+
+/// Define test which runs KW test vectors `$tests` for block cipher
+/// `$cipher` using `run_kw_tests`.
+#[macro_export]
+macro_rules! new_kw_test {
+    ($name:ident, $tests:expr, $cipher:ty) => {
+        #[test]
+        fn $name() {
+            key_wrap::dev::run_kw_tests::<$cipher>($tests);
+        }
+    }
+}
+
+/// Define test which runs KWP test vectors `$tests` for block cipher
+/// `$cipher` using `run_kwp_tests`.
+#[macro_export]
+macro_rules! new_kwp_test {
+    ($name:ident, $tests:expr, $cipher:ty) => {
+        #[test]
+        fn $name() {
+            key_wrap::dev::run_kwp_tests::<$cipher>($tests);
+        }
+    }
+}
+
+/// Run KW tests for block cipher `C`.
+///
+/// Besides wrapping and unwrapping it checks that modified wrapped keys are
+/// rejected.
+pub fn run_kw_tests<C: BlockCipher<BlockSize = U16>>(tests: &[Test]) {
+    for (i, t) in tests.iter().enumerate() {
+        let (mut kb, mut ib, mut ob) = ([0u8; 32], [0u8; 64], [0u8; 72]);
+        let kek = Kek::<C>::new_varkey(decode(t.kek, &mut kb)).unwrap();
+        let input = decode(t.input, &mut ib);
+        let output = decode(t.output, &mut ob);
+
+        let mut buf = [0u8; 72];
+        let res = kek.wrap(input, &mut buf[..output.len()]);
+        if res.is_err() || &buf[..output.len()] != output {
+            panic!("\nFailed KW wrap test №{}\n\
+                kek:\t{}\ninput:\t{}\nexpected output:\t{}\n",
+                i, t.kek, t.input, t.output,
+            );
+        }
+        let res = kek.unwrap(output, &mut buf[..input.len()]);
+        if res.is_err() || &buf[..input.len()] != input {
+            panic!("\nFailed KW unwrap test №{}\n", i);
+        }
+        check_modified(i, output, |data| {
+            kek.unwrap(data, &mut buf[..input.len()])
+        });
+    }
+}
+
+/// Run KWP tests for block cipher `C`.
+///
+/// Besides wrapping and unwrapping it checks that modified wrapped keys are
+/// rejected.
+pub fn run_kwp_tests<C: BlockCipher<BlockSize = U16>>(tests: &[Test]) {
+    for (i, t) in tests.iter().enumerate() {
+        let (mut kb, mut ib, mut ob) = ([0u8; 32], [0u8; 64], [0u8; 72]);
+        let kek = Kek::<C>::new_varkey(decode(t.kek, &mut kb)).unwrap();
+        let input = decode(t.input, &mut ib);
+        let output = decode(t.output, &mut ob);
+
+        let mut buf = [0u8; 72];
+        let res = kek.wrap_with_padding(input, &mut buf[..output.len()]);
+        if res.is_err() || &buf[..output.len()] != output {
+            panic!("\nFailed KWP wrap test №{}\n\
+                kek:\t{}\ninput:\t{}\nexpected output:\t{}\n",
+                i, t.kek, t.input, t.output,
+            );
+        }
+        let res = kek.unwrap_with_padding(output, &mut buf);
+        if res != Ok(input) {
+            panic!("\nFailed KWP unwrap test №{}\n", i);
+        }
+        check_modified(i, output, |data| {
+            kek.unwrap_with_padding(data, &mut buf).map(|_| ())
+        });
+    }
+}
+
+/// Check that `unwrap` rejects data with a flipped bit in every byte.
+fn check_modified<F>(i: usize, output: &[u8], mut unwrap: F)
+    where F: FnMut(&[u8]) -> Result<(), Error>
+{
+    let mut data = [0u8; 72];
+    let data = &mut data[..output.len()];
+    data.copy_from_slice(output);
+    for j in 0..data.len() {
+        data[j] ^= 1;
+        if unwrap(data) != Err(Error::IntegrityCheckFailed) {
+            panic!("\nModified data was not rejected in test №{}\n", i);
+        }
+        data[j] ^= 1;
+    }
+}
+
+/// Test vectors from the section 4 of the RFC 3394 for AES-128 KEK.
+pub const KW_AES128: &[Test] = &[
+    Test {
+        kek: "000102030405060708090a0b0c0d0e0f",
+        input: "00112233445566778899aabbccddeeff",
+        output: "1fa68b0a8112b447aef34bd8fb5a7b829d3e862371d2cfe5",
+    },
+];
+
+/// Test vectors from the section 4 of the RFC 3394 for AES-192 KEK.
+pub const KW_AES192: &[Test] = &[
+    Test {
+        kek: "000102030405060708090a0b0c0d0e0f1011121314151617",
+        input: "00112233445566778899aabbccddeeff",
+        output: "96778b25ae6ca435f92b5b97c050aed2468ab8a17ad84e5d",
+    },
+    Test {
+        kek: "000102030405060708090a0b0c0d0e0f1011121314151617",
+        input: "00112233445566778899aabbccddeeff0001020304050607",
+        output: "031d33264e15d33268f24ec260743edce1c6c7ddee725a936ba814915c6762d2",
+    },
+];
+
+/// Test vectors from the section 4 of the RFC 3394 for AES-256 KEK.
+pub const KW_AES256: &[Test] = &[
+    Test {
+        kek: "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        input: "00112233445566778899aabbccddeeff",
+        output: "64e8c3f9ce0f5ba263e9777905818a2a93c8191e7d6e8ae7",
+    },
+    Test {
+        kek: "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        input: "00112233445566778899aabbccddeeff0001020304050607",
+        output: "a8f9bc1612c68b3ff6e6f4fbe30e71e4769c8b80a32cb8958cd5d17d6b254da1",
+    },
+    Test {
+        kek: "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+        input: "00112233445566778899aabbccddeeff000102030405060708090a0b0c0d0e0f",
+        output: "28c9f404c4b810f4cbccb35cfb87f8263f5786e2d80ed326cbc7f0e71a99f43b\
+            fb988b9b7a02dd21",
+    },
+];
+
+/// Test vectors obtained using NIST ACVP for KWP with AES-128 KEK.
+pub const KWP_AES128: &[Test] = &[
+    Test {
+        kek: "af83ae6624fc006da13b3c37b8a5933b",
+        input: "13126a",
+        output: "a661f530339c9f344fa4755ad4cc3558",
+    },
+    Test {
+        kek: "d19c43011c2a0242a38bd58b8d76456d",
+        input: "4202c90d7298cb4b",
+        output: "65befaeaacbb4620d1a5d64e7b57a760",
+    },
+    Test {
+        kek: "ebee1b9211aadefd06d258605f7134fb",
+        input: "4029f7da4f8c29e4bb951a6f9d7f5305",
+        output: "634194eaca80d77a21d11dd3e739dc5aa3feca2ce0990507",
+    },
+    Test {
+        kek: "83696b21d199c224415370f2c9857e67",
+        input: "8d6220459626a496036389df998b45029ce7",
+        output: "c255c96564c96f0a381a8a8091389d654357ab826c9f1acf16ea8e1db2f820e9",
+    },
+];
+
+/// Test vectors from the section 6 of the RFC 5649 and obtained using
+/// NIST ACVP for KWP with AES-192 KEK.
+pub const KWP_AES192: &[Test] = &[
+    Test {
+        kek: "5840df6e29b02af1ab493b705bf16ea1ae8338f4dcc176a8",
+        input: "c37b7e6492584340bed12207808941155068f738",
+        output: "138bdeaa9b8fa7fc61f97742e72248ee5ae6ae5360d1ae6a5f54f373fa543b6a",
+    },
+    Test {
+        kek: "5840df6e29b02af1ab493b705bf16ea1ae8338f4dcc176a8",
+        input: "466f7250617369",
+        output: "afbeb0f07dfbf5419200f2ccb50bb24f",
+    },
+    Test {
+        kek: "ba0cfc260103ddd629fa8826982f5547d245f5ab0711f10f",
+        input: "c01990",
+        output: "91e3b5e73a25ec91e91d337d0485b960",
+    },
+    Test {
+        kek: "d65980b811b696a44afb3de6ddca07910fab2a4c898b51af",
+        input: "e63d206e6321cbca",
+        output: "7f3b9764d9b28aa7d2e4eda430afba21",
+    },
+    Test {
+        kek: "029194f464dcf06c0e7ca8f05927874a3ac4aa93262459fc",
+        input: "d45e4b35d47f2f559ee2b78d71e73c23",
+        output: "2519d224f9cab21c69ed5758f41beb4d145fc68a3387badf",
+    },
+    Test {
+        kek: "2f65e32f3bc3f0f3ea7e74e86ed66162a7447e723d30e72f",
+        input: "cb4be52bab46b64322fffff30d1a39d17359",
+        output: "4c27bae9e7a7814b78946a6f06902a14c51da65344524eaa645be30f14c400d5",
+    },
+];
+
+/// Test vectors obtained using NIST ACVP for KWP with AES-256 KEK.
+pub const KWP_AES256: &[Test] = &[
+    Test {
+        kek: "6d60c0d0941cf3750b864c6f1fa580ae074c00edeb386f9fc299178a70fcccd1",
+        input: "6b54a0",
+        output: "24255140b4a9f8a9e35b9da2bfa0e0c3",
+    },
+    Test {
+        kek: "eb950b844b97145a594b7f91aa81844045874aaa46db522cf91144f63a6fed37",
+        input: "a4ce3f7d7c49b11a",
+        output: "f5939d472407e28ee6d7269fa75dac88",
+    },
+    Test {
+        kek: "314a549913256a71c6348eaab9b85efc755fe736568f0dbc9f6f8bc3ca3d12ee",
+        input: "3b700e9682275d8dbe61ca7c1ec900e8",
+        output: "70c684c49112ad8b8c3e13b99992127b58dcb9b59ce5c3fd",
+    },
+    Test {
+        kek: "f2882a99e67fd1f0e024d2e973ee55bf2ae94d6798bc3b3a7ef94bfc9197a7f6",
+        input: "13cdd6837c4c40fde0b9ec150093713771ac",
+        output: "d096d3702ea4252da0d36666d01f1f450bcd26c87814a8041f8eefd229ec4828",
+    },
+];
@@ -0,0 +1,302 @@
+//! Generic implementation of the [AES Key Wrap][1] (KW) and
+//! [AES Key Wrap with Padding][2] (KWP) algorithms over block ciphers with
+//! 128-bit block size.
+//!
+//! KW wraps keys which length is a multiple of 8 bytes and is at least 16
+//! bytes, while KWP can wrap keys of any non-zero length. Wrapped key is 8
+//! bytes longer than the original one (plus padding in the KWP case).
+//!
+//! Methods which return `Vec<u8>` are available with enabled `alloc` feature.
+//!
+//! # Usage example
+//! ```rust,ignore
+//! use key_wrap::Kek;
+//!
+//! let kek = Kek::<Aes128>::new(&kek_bytes);
+//! let mut wrapped = [0u8; 24];
+//! kek.wrap(&key, &mut wrapped).unwrap();
+//! let mut unwrapped = [0u8; 16];
+//! kek.unwrap(&wrapped, &mut unwrapped).unwrap();
+//! ```
+//!
+//! [1]: https://tools.ietf.org/html/rfc3394
+//! [2]: https://tools.ietf.org/html/rfc5649
+#![no_std]
+pub extern crate block_cipher_trait;
+#[cfg(feature = "alloc")]
+#[macro_use]
+extern crate alloc;
+
+use block_cipher_trait::BlockCipher;
+use block_cipher_trait::generic_array::GenericArray;
+use block_cipher_trait::generic_array::typenum::U16;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(feature = "dev")]
+pub mod dev;
+
+pub use block_cipher_trait::InvalidKeyLength;
+
+/// Size of the semiblock in bytes
+const SEMIBLOCK: usize = 8;
+/// Default initial value defined in the RFC 3394
+const IV: [u8; 8] = [0xA6; 8];
+/// Prefix of the alternative initial value defined in the RFC 5649
+const KWP_IV_PREFIX: [u8; 4] = [0xA6, 0x59, 0x59, 0xA6];
+
+/// Error type for key wrap operations
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// Length of the input data is not supported by the algorithm
+    InvalidDataSize,
+    /// Length of the output buffer does not match length of the result
+    InvalidOutputSize,
+    /// Integrity check of the unwrapped data has failed
+    IntegrityCheckFailed,
+}
+
+/// Key encryption key over block cipher `C`.
+pub struct Kek<C: BlockCipher<BlockSize = U16>> {
+    cipher: C,
+}
+
+impl<C: BlockCipher<BlockSize = U16>> Kek<C> {
+    /// Create new KEK from initialized block cipher.
+    pub fn from_cipher(cipher: C) -> Self {
+        Self { cipher }
+    }
+
+    /// Create new KEK from key with fixed size.
+    pub fn new(key: &GenericArray<u8, C::KeySize>) -> Self {
+        Self::from_cipher(C::new(key))
+    }
+
+    /// Create new KEK from key with variable size.
+    pub fn new_varkey(key: &[u8]) -> Result<Self, InvalidKeyLength> {
+        C::new_varkey(key).map(Self::from_cipher)
+    }
+
+    /// Wrap `data` using KW algorithm and write result into `out`.
+    ///
+    /// Length of `data` must be a multiple of 8 and be at least 16 bytes,
+    /// length of `out` must be equal to `data.len() + 8`.
+    pub fn wrap(&self, data: &[u8], out: &mut [u8]) -> Result<(), Error> {
+        if data.len() < 2 * SEMIBLOCK
+            || data.len() % SEMIBLOCK != 0
+        {
+            return Err(Error::InvalidDataSize);
+        }
+        if out.len() != data.len() + SEMIBLOCK {
+            return Err(Error::InvalidOutputSize);
+        }
+        let (a, r) = out.split_at_mut(SEMIBLOCK);
+        a.copy_from_slice(&IV);
+        r.copy_from_slice(data);
+        self.w(a, r);
+        Ok(())
+    }
+
+    /// Unwrap `data` using KW algorithm, verify its integrity and write
+    /// result into `out`.
+    ///
+    /// Length of `out` must be equal to `data.len() - 8`. On integrity check
+    /// failure `out` is filled with zeros.
+    pub fn unwrap(&self, data: &[u8], out: &mut [u8]) -> Result<(), Error> {
+        if data.len() < 3 * SEMIBLOCK
+            || data.len() % SEMIBLOCK != 0
+        {
+            return Err(Error::InvalidDataSize);
+        }
+        if out.len() != data.len() - SEMIBLOCK {
+            return Err(Error::InvalidOutputSize);
+        }
+        let mut a = [0u8; SEMIBLOCK];
+        a.copy_from_slice(&data[..SEMIBLOCK]);
+        out.copy_from_slice(&data[SEMIBLOCK..]);
+        self.w_inv(&mut a, out);
+        if ct_eq(&a, &IV) {
+            Ok(())
+        } else {
+            zeroize(out);
+            Err(Error::IntegrityCheckFailed)
+        }
+    }
+
+    /// Wrap `data` using KWP algorithm and write result into `out`.
+    ///
+    /// Length of `data` must be in the range from 1 to `2^32 - 1` bytes,
+    /// length of `out` must be equal to `data.len()` rounded up to a multiple
+    /// of 8 plus 8 bytes.
+    pub fn wrap_with_padding(&self, data: &[u8], out: &mut [u8])
+        -> Result<(), Error>
+    {
+        if data.is_empty() || data.len() as u64 > u32::MAX as u64 {
+            return Err(Error::InvalidDataSize);
+        }
+        let padded_len = (data.len() + SEMIBLOCK - 1) / SEMIBLOCK * SEMIBLOCK;
+        if out.len() != padded_len + SEMIBLOCK {
+            return Err(Error::InvalidOutputSize);
+        }
+        let (a, r) = out.split_at_mut(SEMIBLOCK);
+        a[..4].copy_from_slice(&KWP_IV_PREFIX);
+        a[4..].copy_from_slice(&(data.len() as u32).to_be_bytes());
+        r[..data.len()].copy_from_slice(data);
+        zeroize(&mut r[data.len()..]);
+        if padded_len == SEMIBLOCK {
+            let block = GenericArray::from_mut_slice(out);
+            self.cipher.encrypt_block(block);
+        } else {
+            self.w(a, r);
+        }
+        Ok(())
+    }
+
+    /// Unwrap `data` using KWP algorithm, verify its integrity and write
+    /// result into `out`.
+    ///
+    /// Length of `out` must be at least `data.len() - 8`. Returns part of
+    /// `out` which contains unwrapped key. On integrity check failure `out`
+    /// is filled with zeros.
+    pub fn unwrap_with_padding<'a>(&self, data: &[u8], out: &'a mut [u8])
+        -> Result<&'a [u8], Error>
+    {
+        if data.len() < 2 * SEMIBLOCK
+            || data.len() % SEMIBLOCK != 0
+        {
+            return Err(Error::InvalidDataSize);
+        }
+        let padded_len = data.len() - SEMIBLOCK;
+        if out.len() < padded_len {
+            return Err(Error::InvalidOutputSize);
+        }
+        let out = &mut out[..padded_len];
+        let mut a = [0u8; SEMIBLOCK];
+        if padded_len == SEMIBLOCK {
+            let mut block = GenericArray::clone_from_slice(data);
+            self.cipher.decrypt_block(&mut block);
+            a.copy_from_slice(&block[..SEMIBLOCK]);
+            out.copy_from_slice(&block[SEMIBLOCK..]);
+        } else {
+            a.copy_from_slice(&data[..SEMIBLOCK]);
+            out.copy_from_slice(&data[SEMIBLOCK..]);
+            self.w_inv(&mut a, out);
+        }
+
+        let mli = u32::from_be_bytes([a[4], a[5], a[6], a[7]]) as usize;
+        // MLI must satisfy `8*(n - 1) < MLI <= 8*n`
+        let valid_len = mli <= padded_len && mli + SEMIBLOCK > padded_len;
+        let mut valid = ct_eq(&a[..4], &KWP_IV_PREFIX) & valid_len;
+        if valid_len {
+            valid &= ct_eq(&out[mli..], &[0; SEMIBLOCK][..padded_len - mli]);
+        }
+        if valid {
+            Ok(&out[..mli])
+        } else {
+            zeroize(out);
+            Err(Error::IntegrityCheckFailed)
+        }
+    }
+
+    /// Wrap `data` using KW algorithm and return result.
+    #[cfg(feature = "alloc")]
+    pub fn wrap_vec(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut out = vec![0; data.len() + SEMIBLOCK];
+        self.wrap(data, &mut out)?;
+        Ok(out)
+    }
+
+    /// Unwrap `data` using KW algorithm and return result.
+    #[cfg(feature = "alloc")]
+    pub fn unwrap_vec(&self, data: &[u8]) -> Result<Vec<u8>, Error> {
+        if data.len() < SEMIBLOCK {
+            return Err(Error::InvalidDataSize);
+        }
+        let mut out = vec![0; data.len() - SEMIBLOCK];
+        self.unwrap(data, &mut out)?;
+        Ok(out)
+    }
+
+    /// Wrap `data` using KWP algorithm and return result.
+    #[cfg(feature = "alloc")]
+    pub fn wrap_with_padding_vec(&self, data: &[u8])
+        -> Result<Vec<u8>, Error>
+    {
+        let padded_len = (data.len() + SEMIBLOCK - 1) / SEMIBLOCK * SEMIBLOCK;
+        let mut out = vec![0; padded_len + SEMIBLOCK];
+        self.wrap_with_padding(data, &mut out)?;
+        Ok(out)
+    }
+
+    /// Unwrap `data` using KWP algorithm and return result.
+    #[cfg(feature = "alloc")]
+    pub fn unwrap_with_padding_vec(&self, data: &[u8])
+        -> Result<Vec<u8>, Error>
+    {
+        if data.len() < SEMIBLOCK {
+            return Err(Error::InvalidDataSize);
+        }
+        let mut out = vec![0; data.len() - SEMIBLOCK];
+        let n = self.unwrap_with_padding(data, &mut out)?.len();
+        out.truncate(n);
+        Ok(out)
+    }
+
+    /// Wrapping function `W` which processes semiblocks `r` in-place using
+    /// initial value `a`.
+    fn w(&self, a: &mut [u8], r: &mut [u8]) {
+        let n = r.len() / SEMIBLOCK;
+        let mut block = GenericArray::<u8, U16>::default();
+        for j in 0..6 {
+            for (i, ri) in r.chunks_mut(SEMIBLOCK).enumerate() {
+                block[..SEMIBLOCK].copy_from_slice(a);
+                block[SEMIBLOCK..].copy_from_slice(ri);
+                self.cipher.encrypt_block(&mut block);
+                let t = (n * j + i + 1) as u64;
+                a.copy_from_slice(&block[..SEMIBLOCK]);
+                xor(a, &t.to_be_bytes());
+                ri.copy_from_slice(&block[SEMIBLOCK..]);
+            }
+        }
+    }
+
+    /// Unwrapping function `W^-1` which processes semiblocks `r` in-place
+    /// and writes the recovered initial value into `a`.
+    fn w_inv(&self, a: &mut [u8], r: &mut [u8]) {
+        let n = r.len() / SEMIBLOCK;
+        let mut block = GenericArray::<u8, U16>::default();
+        for j in (0..6).rev() {
+            for (i, ri) in r.chunks_mut(SEMIBLOCK).enumerate().rev() {
+                let t = (n * j + i + 1) as u64;
+                xor(a, &t.to_be_bytes());
+                block[..SEMIBLOCK].copy_from_slice(a);
+                block[SEMIBLOCK..].copy_from_slice(ri);
+                self.cipher.decrypt_block(&mut block);
+                a.copy_from_slice(&block[..SEMIBLOCK]);
+                ri.copy_from_slice(&block[SEMIBLOCK..]);
+            }
+        }
+    }
+}
+
+#[inline(always)]
+fn xor(buf: &mut [u8], key: &[u8]) {
+    debug_assert_eq!(buf.len(), key.len());
+    for (a, b) in buf.iter_mut().zip(key) {
+        *a ^= *b;
+    }
+}
+
+/// Compare slices of equal length in constant time.
+#[inline(always)]
+fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    debug_assert_eq!(a.len(), b.len());
+    a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[inline(always)]
+fn zeroize(buf: &mut [u8]) {
+    for b in buf.iter_mut() {
+        *b = 0;
+    }
+}
@@ -0,0 +1,18 @@
+extern crate aes;
+#[macro_use(impl_cipher)]
+extern crate block_cipher_trait;
+#[macro_use]
+extern crate key_wrap;
+
+use block_cipher_trait::generic_array::typenum::{U16, U24, U32};
+
+impl_cipher!(Aes128, aes::Aes128, U16, U16);
+impl_cipher!(Aes192, aes::Aes192, U24, U16);
+impl_cipher!(Aes256, aes::Aes256, U32, U16);
+
+new_kw_test!(kw_aes128, key_wrap::dev::KW_AES128, Aes128);
+new_kw_test!(kw_aes192, key_wrap::dev::KW_AES192, Aes192);
+new_kw_test!(kw_aes256, key_wrap::dev::KW_AES256, Aes256);
+new_kwp_test!(kwp_aes128, key_wrap::dev::KWP_AES128, Aes128);
+new_kwp_test!(kwp_aes192, key_wrap::dev::KWP_AES192, Aes192);
+new_kwp_test!(kwp_aes256, key_wrap::dev::KWP_AES256, Aes256);
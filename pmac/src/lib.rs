@@ -0,0 +1,198 @@
+//! Generic implementation of [Parallelizable Message Authentication Code][1]
+//! (PMAC1).
+//!
+//! PMAC works with any block cipher whose block size is supported by the
+//! `dbl` crate, i.e. with 64, 128 and 256 bit block ciphers. Output size is
+//! equal to the block size.
+//!
+//! Input is buffered in batches of `BlockEncryptMut::ParBlocks` blocks which
+//! are encrypted using `encrypt_blocks` method of the cipher.
+//!
+//! `Pmac::from_cipher` accepts ciphers which only implement `BlockEncryptMut`,
+//! while the `Mac` trait implementation requires `BlockEncrypt`.
+//!
+//! # Usage example
+//! ```rust,ignore
+//! use pmac::{Pmac, Mac};
+//!
+//! let mut mac = Pmac::<Aes128>::new_varkey(&key).unwrap();
+//! mac.input(b"input message");
+//! let code = mac.result().code();
+//! ```
+//!
+//! [1]: http://web.cs.ucdavis.edu/~rogaway/ocb/pmac.htm
+#![no_std]
+pub extern crate block_cipher_trait;
+pub extern crate crypto_mac;
+extern crate dbl;
+
+use block_cipher_trait::{BlockEncrypt, BlockEncryptMut};
+use block_cipher_trait::generic_array::GenericArray;
+use block_cipher_trait::generic_array::typenum::Unsigned;
+use dbl::Dbl;
+use core::cmp;
+
+pub use crypto_mac::{Mac, MacResult, InvalidKeyLength, KeySizes};
+
+type Block<C> = GenericArray<u8, <C as BlockEncryptMut>::BlockSize>;
+type ParBlocks<C> =
+    GenericArray<Block<C>, <C as BlockEncryptMut>::ParBlocks>;
+
+/// Number of cached `L(i)` values, offsets for block indices with more
+/// trailing zeros are computed on the fly
+const LC_SIZE: usize = 16;
+
+/// PMAC instance over block cipher `C`.
+#[derive(Clone)]
+pub struct Pmac<C> where C: BlockEncryptMut, Block<C>: Dbl {
+    cipher: C,
+    /// `L(i) = L * x^i` for `i < LC_SIZE`
+    l_cache: [Block<C>; LC_SIZE],
+    /// `L * x^-1`
+    l_inv: Block<C>,
+    offset: Block<C>,
+    tag: Block<C>,
+    /// Unprocessed blocks, the last one is processed only in `result` or
+    /// after more data was received
+    buffer: ParBlocks<C>,
+    /// Number of bytes in `buffer`
+    pos: usize,
+    /// Number of processed blocks
+    counter: usize,
+}
+
+impl<C> Pmac<C> where C: BlockEncryptMut, Block<C>: Dbl {
+    /// Create new PMAC instance from initialized block cipher.
+    pub fn from_cipher(mut cipher: C) -> Self {
+        let mut l = Block::<C>::default();
+        cipher.encrypt_block(&mut l);
+        let l_inv = l.clone().inv_dbl();
+
+        let mut l_cache: [Block<C>; LC_SIZE] = Default::default();
+        for v in l_cache.iter_mut() {
+            *v = l.clone();
+            l = l.dbl();
+        }
+
+        Self {
+            cipher,
+            l_cache,
+            l_inv,
+            offset: Default::default(),
+            tag: Default::default(),
+            buffer: Default::default(),
+            pos: 0,
+            counter: 0,
+        }
+    }
+
+    /// Update offset for the next block and xor it into `block`.
+    fn mask_block(&mut self, block: &mut Block<C>) {
+        self.counter += 1;
+        let ntz = self.counter.trailing_zeros() as usize;
+        if ntz < LC_SIZE {
+            xor(&mut self.offset, &self.l_cache[ntz]);
+        } else {
+            let mut l = self.l_cache[LC_SIZE - 1].clone();
+            for _ in LC_SIZE - 1..ntz {
+                l = l.dbl();
+            }
+            xor(&mut self.offset, &l);
+        }
+        xor(block, &self.offset);
+    }
+
+    fn process_buffer(&mut self) {
+        let mut buffer = self.buffer.clone();
+        for block in buffer.iter_mut() {
+            self.mask_block(block);
+        }
+        self.cipher.encrypt_blocks(&mut buffer);
+        for block in buffer.iter() {
+            xor(&mut self.tag, block);
+        }
+        self.pos = 0;
+    }
+
+    fn input_data(&mut self, mut data: &[u8]) {
+        let bs = C::BlockSize::to_usize();
+        let cap = bs * C::ParBlocks::to_usize();
+        while !data.is_empty() {
+            // full buffer is processed only when more data is available
+            if self.pos == cap {
+                self.process_buffer();
+            }
+            let (i, j) = (self.pos / bs, self.pos % bs);
+            let n = cmp::min(bs - j, data.len());
+            self.buffer[i][j..j + n].copy_from_slice(&data[..n]);
+            self.pos += n;
+            data = &data[n..];
+        }
+    }
+
+    fn finalize(&mut self) -> Block<C> {
+        let bs = C::BlockSize::to_usize();
+        let n = if self.pos == 0 { 0 } else { (self.pos - 1) / bs };
+        for i in 0..n {
+            let mut block = self.buffer[i].clone();
+            self.mask_block(&mut block);
+            self.cipher.encrypt_block(&mut block);
+            xor(&mut self.tag, &block);
+        }
+
+        let len = self.pos - n * bs;
+        let mut last = self.buffer[n].clone();
+        if len == bs {
+            xor(&mut last, &self.l_inv);
+        } else {
+            last[len] = 0x80;
+            for b in last[len + 1..].iter_mut() {
+                *b = 0;
+            }
+        }
+        xor(&mut self.tag, &last);
+        self.cipher.encrypt_block(&mut self.tag);
+
+        let tag = self.tag.clone();
+        self.offset = Default::default();
+        self.tag = Default::default();
+        self.pos = 0;
+        self.counter = 0;
+        tag
+    }
+}
+
+impl<C> Mac for Pmac<C> where C: BlockEncrypt, Block<C>: Dbl {
+    type OutputSize = C::BlockSize;
+    type KeySize = C::KeySize;
+
+    fn new(key: &GenericArray<u8, C::KeySize>) -> Self {
+        Self::from_cipher(C::new(key))
+    }
+
+    fn new_varkey(key: &[u8]) -> Result<Self, InvalidKeyLength> {
+        let cipher = C::new_varkey(key).map_err(|_| InvalidKeyLength)?;
+        Ok(Self::from_cipher(cipher))
+    }
+
+    fn key_sizes() -> KeySizes {
+        C::key_sizes()
+    }
+
+    #[inline]
+    fn input(&mut self, data: &[u8]) {
+        self.input_data(data);
+    }
+
+    #[inline]
+    fn result(&mut self) -> MacResult<C::BlockSize> {
+        MacResult::new(self.finalize())
+    }
+}
+
+#[inline(always)]
+fn xor(buf: &mut [u8], data: &[u8]) {
+    for (a, b) in buf.iter_mut().zip(data) {
+        *a ^= *b;
+    }
+}
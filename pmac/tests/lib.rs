@@ -0,0 +1,13 @@
+extern crate aes;
+#[macro_use(impl_cipher)]
+extern crate block_cipher_trait;
+#[macro_use]
+extern crate crypto_mac;
+extern crate pmac;
+
+use block_cipher_trait::generic_array::typenum::U16;
+use pmac::Pmac;
+
+impl_cipher!(Aes128, aes::Aes128, U16, U16);
+
+new_test!(pmac_aes128, "aes128", Pmac<Aes128>);
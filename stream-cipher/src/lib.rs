@@ -0,0 +1,95 @@
+//! This crate defines a set of traits which define functionality of
+//! stream ciphers.
+#![no_std]
+pub extern crate generic_array;
+
+use generic_array::{GenericArray, ArrayLength};
+use generic_array::typenum::Unsigned;
+
+/// Error which notifies that stream cipher has reached the end of a keystream
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct LoopError;
+
+/// Error struct which used with `NewStreamCipher::new_var`
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq)]
+pub struct InvalidKeyNonceLength;
+
+/// Stream cipher creation trait.
+pub trait NewStreamCipher: core::marker::Sized {
+    /// Key size in bytes
+    type KeySize: ArrayLength<u8>;
+    /// Nonce size in bytes
+    type NonceSize: ArrayLength<u8>;
+
+    /// Create new stream cipher instance from key and nonce arrays.
+    fn new(
+        key: &GenericArray<u8, Self::KeySize>,
+        nonce: &GenericArray<u8, Self::NonceSize>,
+    ) -> Self;
+
+    /// Create new stream cipher instance from variable length key and nonce.
+    ///
+    /// Default implementation will accept only key and nonce with lengths
+    /// equal to `KeySize` and `NonceSize` respectively.
+    fn new_var(key: &[u8], nonce: &[u8])
+        -> Result<Self, InvalidKeyNonceLength>
+    {
+        let kl = Self::KeySize::to_usize();
+        let nl = Self::NonceSize::to_usize();
+        if key.len() != kl || nonce.len() != nl {
+            Err(InvalidKeyNonceLength)
+        } else {
+            let key = GenericArray::from_slice(key);
+            let nonce = GenericArray::from_slice(nonce);
+            Ok(Self::new(key, nonce))
+        }
+    }
+}
+
+/// Synchronous stream cipher core trait.
+pub trait SyncStreamCipher {
+    /// Apply keystream to the data.
+    ///
+    /// It will XOR generated keystream with the data, which can be both
+    /// encryption and decryption.
+    ///
+    /// # Panics
+    /// If end of the keystream will be reached with the given data length,
+    /// method will panic without modifying the provided `data`.
+    #[inline]
+    fn apply_keystream(&mut self, data: &mut [u8]) {
+        let res = self.try_apply_keystream(data);
+        if res.is_err() {
+            panic!("stream cipher loop detected");
+        }
+    }
+
+    /// Apply keystream to the data, but return an error if end of
+    /// a keystream will be reached.
+    ///
+    /// If end of the keystream will be achieved with the given data length,
+    /// method will return `Err(LoopError)` without modifying provided `data`.
+    fn try_apply_keystream(&mut self, data: &mut [u8])
+        -> Result<(), LoopError>;
+}
+
+/// Trait for seekable stream ciphers.
+pub trait SyncStreamCipherSeek {
+    /// Return current position of a keystream in bytes from the beginning.
+    fn current_pos(&self) -> u64;
+
+    /// Seek keystream to the given byte position.
+    ///
+    /// # Panics
+    /// If provided position is beyond the end of the keystream.
+    #[inline]
+    fn seek(&mut self, pos: u64) {
+        if self.try_seek(pos).is_err() {
+            panic!("seek position is beyond the end of the keystream");
+        }
+    }
+
+    /// Seek keystream to the given byte position, but return an error if
+    /// position is beyond the end of the keystream.
+    fn try_seek(&mut self, pos: u64) -> Result<(), LoopError>;
+}